@@ -1,16 +1,32 @@
+use std::{fs, path::Path, rc::Rc};
+
+use crate::commands::{SlashCommand, SlashCommandProvider};
+use crate::config::{ActiveConfig, set_working_dir, update_config};
+use crate::drafts;
+use crate::prompt_history::{self, PromptRecall};
+use crate::settings::model_select;
 use crate::state::{self, CONTEXT, ChatState, DownMessage, Message, Part, Role, UpMessage};
+use crate::tasks::{TaskList, TaskListEvent};
 use gpui::{
     AnyElement, App, ClickEvent, ClipboardItem, Context, Div, Entity, EventEmitter, FocusHandle,
-    Focusable, KeyDownEvent, Keystroke, ListState, Render, SharedString, Stateful, Styled,
-    WeakEntity, Window, div, list, prelude::*, px, rems,
+    Focusable, FontWeight, KeyDownEvent, Keystroke, ListState, Render, SharedString, Stateful,
+    Styled, Subscription, WeakEntity, Window, div, list, prelude::*, px, relative, rems,
 };
 use ui::{
-    ActiveTheme, Button, ButtonVariants, ContextModal, Disableable, Icon, IconName, Sizable,
-    StyledExt, focus, h_flex,
+    ActiveTheme, Button, ButtonVariants, ContextMenuExt, ContextModal, Disableable, Icon, IconName,
+    Placement, Sizable, StyledExt,
+    accordion::{Accordion, AccordionItem},
+    description_list::{DescriptionItem, DescriptionList},
+    focus, h_flex,
     input::{InputEvent, InputState, TextInput},
     notification::Notification,
+    popup_menu::PopupMenuItem,
+    progress::Progress,
+    select::{Select, SelectState},
+    skeleton::Skeleton,
     v_flex,
 };
+use rfd::FileDialog;
 
 #[derive(IntoElement)]
 pub struct MessageBubble {
@@ -41,7 +57,7 @@ impl MessageBubble {
     fn render_part(part: &Part, _window: &mut Window, _cx: &mut App) -> AnyElement {
         match part {
             Part::Text(t) => SharedString::from(t).into_any_element(),
-            Part::ToolCall(_t) => unreachable!(),
+            Part::ToolCall(_) | Part::Thinking(_) => unreachable!(),
         }
         .into_any_element()
     }
@@ -221,12 +237,25 @@ impl RenderOnce for MessageBubble {
             )
     }
 }
+
+/// Export the conversation to the markdown file, return the notification of the result.
+pub fn export_conversation(chat_state: &ChatState, path: &Path) -> Notification {
+    match fs::write(path, chat_state.to_markdown()) {
+        Ok(()) => Notification::success(format!("Exported to {}.", path.display())),
+        Err(err) => Notification::error(format!("Failed to export the conversation: {}", err)),
+    }
+}
+
 pub struct ActiveChat {
     pub chat_state: Entity<ChatState>,
     pub edit_message_textarea: Entity<InputState>,
     pub chat_textarea: Entity<InputState>,
+    prompt_recall: Entity<PromptRecall>,
+    model_select: Entity<SelectState>,
     pub focus_handle: FocusHandle,
     pub list_state: ListState,
+    /// Save the draft of the `chat_textarea` of the active task.
+    _draft_autosave: Subscription,
 }
 
 pub enum ActiveChatEvent {
@@ -237,16 +266,111 @@ pub enum ActiveChatEvent {
 impl EventEmitter<ActiveChatEvent> for ActiveChat {}
 
 impl ActiveChat {
-    fn submit_message(&self, cx: &mut Context<Self>) {
-        self.chat_state.update(cx, |state, cx| {
-            let text = self.chat_textarea.read(cx).value();
-            if text.trim().is_empty() {
+    fn submit_message(&mut self, cx: &mut Context<Self>) {
+        let input = self.chat_textarea.read(cx).value().clone();
+        let text = match SlashCommand::parse(&input, &cx.config().snippets) {
+            Ok(None) => input.trim().to_string(),
+            Ok(Some(SlashCommand::Send(text))) => text,
+            Ok(Some(command)) => {
+                if self.run_command(command, cx) {
+                    self.push_prompt(&input, cx);
+                    self.clear_chat_textarea(cx);
+                }
+                return;
+            }
+            Err(err) => {
+                self.push_notification(Notification::error(err.to_string()), cx);
                 return;
             }
-            let id = state.add_message(Role::User, vec![Part::Text(text.trim().into())]);
+        };
+
+        if text.trim().is_empty() {
+            return;
+        }
+        self.chat_state.update(cx, |state, cx| {
+            let id = state.send(text);
             self.list_state.splice(id..id, 1);
             self.list_state.reset(state.messages.len());
         });
+        self.push_prompt(&input, cx);
+        self.clear_chat_textarea(cx);
+        cx.notify();
+    }
+
+    fn push_prompt(&self, text: &str, cx: &mut Context<Self>) {
+        self.prompt_recall
+            .update(cx, |recall, cx| recall.push(text, cx));
+    }
+
+    /// Run the slash command, return `false` if failed to keep the input.
+    fn run_command(&mut self, command: SlashCommand, cx: &mut Context<Self>) -> bool {
+        match command {
+            SlashCommand::Clear => {
+                self.chat_state.update(cx, |state, _cx| state.clear());
+                self.list_state.reset(0);
+            }
+            SlashCommand::Model(None) => {
+                let message = match &cx.config().model {
+                    Some(model) => format!("Using {}.", model),
+                    None => "No model selected, use /model <name> to select one.".into(),
+                };
+                self.push_notification(Notification::info(message), cx);
+            }
+            SlashCommand::Model(Some(model)) => {
+                let message = format!("Switched to {}.", model);
+                update_config(cx, |config| config.model = Some(model));
+                self.push_notification(Notification::success(message), cx);
+            }
+            SlashCommand::Cwd(dir) => {
+                let dir = match dir {
+                    Some(dir) => match &cx.config().working_dir {
+                        Some(working_dir) => working_dir.join(dir),
+                        None => dir,
+                    },
+                    None => match FileDialog::new().pick_folder() {
+                        Some(dir) => dir,
+                        None => return false,
+                    },
+                };
+                if !dir.is_dir() {
+                    let message = format!("{} is not a directory.", dir.display());
+                    self.push_notification(Notification::error(message), cx);
+                    return false;
+                }
+
+                let message = format!("Working directory changed to {}.", dir.display());
+                set_working_dir(dir, cx);
+                self.push_notification(Notification::success(message), cx);
+            }
+            SlashCommand::Export(path) => {
+                let path = match path.or_else(|| {
+                    FileDialog::new()
+                        .set_file_name("conversation.md")
+                        .save_file()
+                }) {
+                    Some(path) => path,
+                    None => return false,
+                };
+                let notification = export_conversation(self.chat_state.read(cx), &path);
+                self.push_notification(notification, cx);
+            }
+            SlashCommand::Retry => {
+                let retried = self.chat_state.update(cx, |state, _cx| state.retry());
+                if retried.is_none() {
+                    self.push_notification(Notification::error("No message to retry."), cx);
+                    return false;
+                }
+                let len = self.chat_state.read(cx).messages.len();
+                self.list_state.reset(len);
+            }
+            SlashCommand::Send(_) => unreachable!("sent as a message"),
+        }
+
+        cx.notify();
+        true
+    }
+
+    fn clear_chat_textarea(&self, cx: &mut Context<Self>) {
         let window_handle = cx.active_window();
 
         if let Some(window) = window_handle {
@@ -257,9 +381,60 @@ impl ActiveChat {
                 })
                 .ok();
         }
+    }
+
+    fn push_notification(&self, notification: Notification, cx: &mut Context<Self>) {
+        if let Some(window) = cx.active_window() {
+            window
+                .update(cx, |_, window, cx| window.push_notification(notification, cx))
+                .ok();
+        }
+    }
+
+    /// Show the conversation of the task, and restore the draft of the task.
+    fn open_task(&mut self, id: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let task = TaskList::global(cx)
+            .read(cx)
+            .tasks()
+            .iter()
+            .find(|task| task.id == id)
+            .map(|task| (task.chat_state.clone(), task.draft_key.clone()));
+        let Some((chat_state, draft_key)) = task else {
+            return;
+        };
+
+        if self.chat_state.read(cx).edit_message_id.is_some() {
+            self.on_cancel_click(Some(window), cx);
+        }
+        self.chat_state.update(cx, |state, _cx| {
+            state.focused_message_idx = None;
+            state.fake_focused_textarea = false;
+        });
+        self.chat_state = chat_state;
+        let len = self.chat_state.read(cx).messages.len();
+        self.list_state.reset(len);
+        self._draft_autosave = drafts::autosave(draft_key, &self.chat_textarea, window, cx);
+        cx.notify();
+    }
+
+    fn delete_message(&mut self, id: usize, cx: &mut Context<Self>) {
+        if self.chat_state.read(cx).edit_message_id == Some(id) {
+            self.on_cancel_click(None, cx);
+        }
+        self.chat_state
+            .update(cx, |state, _cx| state.delete_message(id));
+        let len = self.chat_state.read(cx).messages.len();
+        self.list_state.reset(len);
         cx.notify();
     }
 
+    /// Continue the conversation until the message in a new task.
+    fn branch_from_message(&mut self, id: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let branch = self.chat_state.read(cx).branch(id);
+        TaskList::global(cx).update(cx, |tasks, cx| tasks.add(branch, cx));
+        window.push_notification(Notification::info("Branched into a new task."), cx);
+    }
+
     fn start_editing_message(
         &mut self,
         id: usize,
@@ -359,16 +534,26 @@ impl ActiveChat {
     }
 
     pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let chat_textarea = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line()
+                .auto_grow(3, 6)
+                .placeholder("Message control, type / for commands")
+                .completion_provider(Rc::new(SlashCommandProvider))
+        });
+        let task_list = TaskList::global(cx);
+        let (draft_key, chat_state) = {
+            let task = task_list.read(cx).active();
+            (task.draft_key.clone(), task.chat_state.clone())
+        };
         let a = Self {
-            chat_state: cx.new(|cx| ChatState::new(window, cx)),
+            chat_state,
             edit_message_textarea: cx
                 .new(|cx| InputState::new(window, cx).multi_line().auto_grow(2, 6)),
-            chat_textarea: cx.new(|cx| {
-                InputState::new(window, cx)
-                    .multi_line()
-                    .auto_grow(3, 6)
-                    .placeholder("Message control")
-            }),
+            prompt_recall: cx.new(|cx| PromptRecall::new(chat_textarea.clone(), window, cx)),
+            model_select: model_select(window, cx),
+            _draft_autosave: drafts::autosave(draft_key, &chat_textarea, window, cx),
+            chat_textarea,
             list_state: ListState::new(0, gpui::ListAlignment::Bottom, px(3000.), {
                 let this = cx.entity().downgrade();
                 move |i, window, cx| {
@@ -415,6 +600,15 @@ impl ActiveChat {
 
         state::init(cx);
 
+        cx.subscribe_in(
+            &task_list,
+            window,
+            |this, _, event: &TaskListEvent, window, cx| match event {
+                TaskListEvent::Activated(id) => this.open_task(*id, window, cx),
+            },
+        )
+        .detach();
+
         cx.subscribe(
             &a.edit_message_textarea,
             |this, i, e: &InputEvent, cx| match e {
@@ -500,7 +694,8 @@ impl ActiveChat {
             });
 
         let text_part_for_copy = text_part.clone();
-        let text_part_for_edit = text_part;
+        let text_part_for_edit = text_part.clone();
+        let this = cx.entity().downgrade();
 
         let focused = self
             .chat_state
@@ -510,7 +705,7 @@ impl ActiveChat {
             .map(|idx| idx == msg.id)
             .unwrap_or_default();
 
-        MessageBubble::new(msg)
+        let bubble = MessageBubble::new(msg)
             .on_copy_click(cx.listener(move |_, _, window, cx| {
                 cx.write_to_clipboard(ClipboardItem::new_string(text_part_for_copy.to_string()));
                 window.push_notification(Notification::info("Copied to Clipboard."), cx);
@@ -527,16 +722,171 @@ impl ActiveChat {
                 cx.notify();
             }))
             .edit_message_view(textarea)
-            .focused(focused)
+            .focused(focused);
+
+        div()
+            .id(("message", id))
+            .w_full()
+            .child(bubble)
+            .context_menu(move |menu, _window, _cx| {
+                menu.key_context(CONTEXT)
+                    .item(
+                        PopupMenuItem::new("Copy")
+                            .icon(IconName::Copy)
+                            .action(Box::new(state::Copy))
+                            .on_click({
+                                let text = text_part.clone();
+                                move |window, cx| {
+                                    cx.write_to_clipboard(ClipboardItem::new_string(
+                                        text.to_string(),
+                                    ));
+                                    window.push_notification(
+                                        Notification::info("Copied to Clipboard."),
+                                        cx,
+                                    );
+                                }
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new("Edit")
+                            .icon(IconName::Pencil)
+                            .action(Box::new(state::Edit))
+                            .on_click({
+                                let this = this.clone();
+                                let text = text_part.clone();
+                                move |window, cx| {
+                                    this.update(cx, |this, cx| {
+                                        let text = text.clone();
+                                        this.start_editing_message(id, text, Some(window), cx)
+                                    })
+                                    .ok();
+                                }
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new("Delete")
+                            .icon(IconName::Delete)
+                            .action(Box::new(state::Delete))
+                            .on_click({
+                                let this = this.clone();
+                                move |_window, cx| {
+                                    this.update(cx, |this, cx| this.delete_message(id, cx)).ok();
+                                }
+                            }),
+                    )
+                    .separator()
+                    .item(PopupMenuItem::new("Branch").on_click({
+                        let this = this.clone();
+                        move |window, cx| {
+                            this.update(cx, |this, cx| this.branch_from_message(id, window, cx))
+                                .ok();
+                        }
+                    }))
+            })
     }
 
     fn render_assistant_message(
         &mut self,
-        _msg: &Message,
+        msg: &Message,
         _window: &mut Window,
-        _cx: &mut App,
+        cx: &mut Context<Self>,
     ) -> impl gpui::IntoElement {
-        div()
+        let mut children = vec![];
+        // The consecutive tool calls and thinking parts are collapsed in one accordion.
+        let mut collapsible = vec![];
+        for (ix, part) in msg.parts.iter().enumerate() {
+            match part {
+                Part::Text(text) => {
+                    if !collapsible.is_empty() {
+                        let parts = std::mem::take(&mut collapsible);
+                        children.push(self.render_collapsible_parts(msg, parts, cx));
+                    }
+                    children.push(div().child(SharedString::from(text)).into_any_element());
+                }
+                Part::ToolCall(_) | Part::Thinking(_) => collapsible.push(ix),
+            }
+        }
+        if !collapsible.is_empty() {
+            children.push(self.render_collapsible_parts(msg, collapsible, cx));
+        }
+
+        v_flex()
+            .w_full()
+            .max_w(rems(46.))
+            .gap_2()
+            .pb(px(5.))
+            .whitespace_normal()
+            .children(children)
+    }
+
+    /// Render the tool calls and the thinking parts of the message as an accordion, `parts` are
+    /// the indices of the parts.
+    fn render_collapsible_parts(
+        &self,
+        msg: &Message,
+        parts: Vec<usize>,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let id = msg.id;
+        let open_parts = &self.chat_state.read(cx).open_parts;
+        // The ids are scoped by the message, e.g.: for the copy buttons of the arguments.
+        let args_id = |ix: usize| SharedString::from(format!("tool-args-{}-{}", id, ix));
+        let items = parts
+            .iter()
+            .map(|&ix| {
+                let item = match &msg.parts[ix] {
+                    Part::ToolCall(tool) => AccordionItem::new(
+                        div()
+                            .text_sm()
+                            .font_weight(FontWeight::MEDIUM)
+                            .child(SharedString::from(tool.name().to_string())),
+                    )
+                    .icon(IconName::SquareTerminal)
+                    .content(
+                        DescriptionList::new(args_id(ix))
+                            .columns(1)
+                            .small()
+                            .label_width(px(100.))
+                            .children(tool_arg_items(tool.args())),
+                    ),
+                    Part::Thinking(text) => AccordionItem::new(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("Thinking"),
+                    )
+                    .icon(IconName::Bot)
+                    .content(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(SharedString::from(text)),
+                    ),
+                    Part::Text(_) => unreachable!(),
+                };
+                item.open(open_parts.contains(&(id, ix)))
+            })
+            .collect::<Vec<_>>();
+
+        let accordion_id = SharedString::from(format!("message-parts-{}-{}", id, parts[0]));
+        let chat_state = self.chat_state.downgrade();
+        Accordion::new(accordion_id)
+            .multiple(true)
+            .small()
+            .children(items)
+            .on_toggle_click(move |open, _window, cx| {
+                let open = open
+                    .iter()
+                    .map(|&position| parts[position])
+                    .collect::<Vec<_>>();
+                chat_state
+                    .update(cx, |state, cx| {
+                        state.set_open_parts(id, &parts, &open);
+                        cx.notify();
+                    })
+                    .ok();
+            })
+            .into_any_element()
     }
 
     pub fn render_message(
@@ -555,6 +905,30 @@ impl ActiveChat {
     }
 }
 
+/// The arguments of a tool call, the fields of an object argument are listed one by one.
+fn tool_arg_items(args: &[serde_json::Value]) -> Vec<DescriptionItem> {
+    let item = |label: String, value: &serde_json::Value| {
+        let text = match value {
+            serde_json::Value::String(text) => text.clone(),
+            value => serde_json::to_string_pretty(value).unwrap_or_default(),
+        };
+        DescriptionItem::new(label)
+            .value(SharedString::from(text.clone()))
+            .copyable(text)
+    };
+
+    args.iter()
+        .enumerate()
+        .flat_map(|(ix, arg)| match arg {
+            serde_json::Value::Object(fields) => fields
+                .iter()
+                .map(|(key, value)| item(key.clone(), value))
+                .collect::<Vec<_>>(),
+            arg => vec![item(format!("#{}", ix + 1), arg)],
+        })
+        .collect()
+}
+
 impl Render for ActiveChat {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let textinput = TextInput::new(&self.chat_textarea).bordered(false);
@@ -565,6 +939,10 @@ impl Render for ActiveChat {
             cx.theme().background
         };
         let appearance = textinput.appearance;
+        let stop_listener = cx.listener(|this, _: &ClickEvent, _window, cx| {
+            this.chat_state.update(cx, |state, _cx| state.stop());
+            cx.notify();
+        });
         let listener = cx.listener(|_this, _, _window, cx| {
             cx.spawn(async |this: WeakEntity<Self>, cx| {
                 this.update(cx, |this, cx| {
@@ -575,6 +953,8 @@ impl Render for ActiveChat {
             .detach();
         });
         let chat_state = self.chat_state.downgrade();
+        let streaming = self.chat_state.read(cx).streaming;
+        let awaiting_response = self.chat_state.read(cx).awaiting_response();
 
         println!("{}", self.list_state.viewport_bounds().size.width);
 
@@ -588,6 +968,16 @@ impl Render for ActiveChat {
             .size_full()
             .items_center()
             .relative()
+            .when(streaming, |this| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .w_full()
+                        .child(Progress::new().xsmall()),
+                )
+            })
             .child(
                 div()
                     .size_full()
@@ -605,10 +995,31 @@ impl Render for ActiveChat {
                             .relative()
                             .pb(px(30.)),
                     )
+                    .when(awaiting_response, |this| {
+                        this.child(
+                            v_flex()
+                                .w_full()
+                                .gap_2()
+                                .px(px(10.))
+                                .pb(px(20.))
+                                .child(Skeleton::new().w(relative(0.4)))
+                                .child(Skeleton::new().w_full().secondary(true))
+                                .child(Skeleton::new().w(relative(0.7)).secondary(true)),
+                        )
+                    })
                     .h_full()
                     .child(
                         div()
                             .w_full()
+                            .key_context(prompt_history::CONTEXT)
+                            .on_action(
+                                window.listener_for(&self.prompt_recall, PromptRecall::previous),
+                            )
+                            .on_action(window.listener_for(&self.prompt_recall, PromptRecall::next))
+                            .on_action(
+                                window.listener_for(&self.prompt_recall, PromptRecall::search),
+                            )
+                            .child(self.prompt_recall.clone())
                             .child(textinput)
                             .pb(px(10.))
                             .rounded(cx.theme().radius * 1.5)
@@ -634,6 +1045,25 @@ impl Render for ActiveChat {
                                     .w_full()
                                     .gap_2()
                                     .px(px(10.))
+                                    .child(
+                                        div()
+                                            .w(px(180.))
+                                            .child(
+                                                Select::new(&self.model_select)
+                                                    .small()
+                                                    .placement(Placement::Top),
+                                            ),
+                                    )
+                                    .when(streaming, |this| {
+                                        this.child(
+                                            Button::new("stop")
+                                                .ghost()
+                                                .icon(IconName::CircleX)
+                                                .tooltip("Stop waiting for the reply")
+                                                .on_click(cx, stop_listener)
+                                                .not_focusable(),
+                                        )
+                                    })
                                     .child(
                                         Button::new("submit")
                                             .primary()
@@ -713,6 +1143,17 @@ impl Render for ActiveChat {
                             .ok();
                         }
                     })
+                    .on_action({
+                        let this = cx.entity().downgrade();
+                        move |_: &state::Delete, _window, cx| {
+                            this.update(cx, |this, cx| {
+                                if let Some(id) = this.chat_state.read(cx).focused_message_idx {
+                                    this.delete_message(id, cx);
+                                }
+                            })
+                            .ok();
+                        }
+                    })
                     .on_action({
                         let chat_state = chat_state.clone();
                         move |_: &state::Copy, window, cx| {
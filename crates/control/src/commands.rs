@@ -0,0 +1,217 @@
+use std::{ops::Range, path::PathBuf};
+
+use anyhow::{Result, bail};
+use gpui::{Context, Task, Window};
+use serde::{Deserialize, Serialize};
+use ui::input::{CompletionItem, CompletionProvider, InputState};
+
+use crate::config::ActiveConfig;
+
+/// The built-in commands, `(name, description)`.
+const COMMANDS: &[(&str, &str)] = &[
+    ("clear", "Clear the conversation"),
+    (
+        "model",
+        "Show the model, or switch to another one: /model <name>",
+    ),
+    ("cwd", "Change the working directory: /cwd [path]"),
+    (
+        "export",
+        "Export the conversation as markdown: /export [path]",
+    ),
+    ("retry", "Send the last message again"),
+];
+
+/// A canned prompt sent by `/<name>`, e.g.:
+///
+/// ```toml
+/// [[snippets]]
+/// name = "review"
+/// description = "Review the changes"
+/// prompt = "Review the uncommitted changes in {input}, point out the bugs first."
+/// ```
+///
+/// The text after the command replaces `{input}` in the prompt, or is appended to the prompt
+/// if there is no `{input}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PromptSnippet {
+    pub name: String,
+    pub prompt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl PromptSnippet {
+    fn expand(&self, input: &str) -> String {
+        if self.prompt.contains("{input}") {
+            self.prompt.replace("{input}", input)
+        } else if input.is_empty() {
+            self.prompt.clone()
+        } else {
+            format!("{}\n\n{}", self.prompt, input)
+        }
+    }
+}
+
+/// A command typed in the chat input, e.g.: `/clear`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlashCommand {
+    Clear,
+    /// Show the model if `None`, otherwise switch to the model.
+    Model(Option<String>),
+    /// Pick the working directory if `None`.
+    Cwd(Option<PathBuf>),
+    /// Pick the file to export to if `None`.
+    Export(Option<PathBuf>),
+    Retry,
+    /// Send the text as a message, e.g.: an expanded snippet, or the text escaped by `//`.
+    Send(String),
+}
+
+impl SlashCommand {
+    /// Parse the submitted text, return `None` if it is not a command.
+    ///
+    /// The built-in commands take precedence over the snippets with the same name, and a
+    /// leading `//` sends the text with a single `/`.
+    pub fn parse(text: &str, snippets: &[PromptSnippet]) -> Result<Option<Self>> {
+        let text = text.trim();
+        let Some(command) = text.strip_prefix('/') else {
+            return Ok(None);
+        };
+        if command.starts_with('/') {
+            return Ok(Some(Self::Send(command.to_string())));
+        }
+
+        let (name, args) = match command.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim()),
+            None => (command, ""),
+        };
+        let arg = (!args.is_empty()).then(|| args.to_string());
+
+        let command = match name {
+            "clear" | "retry" if arg.is_some() => bail!("/{} takes no arguments", name),
+            "clear" => Self::Clear,
+            "retry" => Self::Retry,
+            "model" => Self::Model(arg),
+            "cwd" => Self::Cwd(arg.map(PathBuf::from)),
+            "export" => Self::Export(arg.map(PathBuf::from)),
+            _ => match snippets.iter().find(|snippet| snippet.name == name) {
+                Some(snippet) => Self::Send(snippet.expand(args)),
+                None => bail!("Unknown command /{}", name),
+            },
+        };
+
+        Ok(Some(command))
+    }
+}
+
+/// Suggest the commands and the snippets while typing the `/<name>` at the start of the input.
+pub struct SlashCommandProvider;
+
+impl CompletionProvider for SlashCommandProvider {
+    fn query_range(&self, text: &str, offset: usize) -> Option<Range<usize>> {
+        let query = &text[..offset];
+        if !query.starts_with('/') || query.starts_with("//") || query.contains(char::is_whitespace)
+        {
+            return None;
+        }
+
+        Some(0..offset)
+    }
+
+    fn completions(
+        &self,
+        _text: &str,
+        _query_range: Range<usize>,
+        _window: &mut Window,
+        cx: &mut Context<InputState>,
+    ) -> Task<Result<Vec<CompletionItem>>> {
+        let commands = COMMANDS.iter().map(|(name, description)| {
+            CompletionItem::new(format!("/{}", name))
+                .detail(*description)
+                .insert_text(format!("/{} ", name))
+        });
+        let snippets = cx
+            .config()
+            .snippets
+            .iter()
+            .filter(|snippet| !COMMANDS.iter().any(|(name, _)| *name == snippet.name))
+            .map(|snippet| {
+                CompletionItem::new(format!("/{}", snippet.name))
+                    .detail(
+                        snippet
+                            .description
+                            .clone()
+                            .unwrap_or_else(|| "Snippet".into()),
+                    )
+                    .documentation(snippet.prompt.clone())
+                    .insert_text(format!("/{} ", snippet.name))
+            });
+
+        Task::ready(Ok(commands.chain(snippets).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{PromptSnippet, SlashCommand};
+
+    #[test]
+    fn test_parse() {
+        let snippets = vec![
+            PromptSnippet {
+                name: "review".into(),
+                prompt: "Review {input} carefully.".into(),
+                description: None,
+            },
+            PromptSnippet {
+                name: "tests".into(),
+                prompt: "Write the tests.".into(),
+                description: None,
+            },
+            PromptSnippet {
+                name: "clear".into(),
+                prompt: "Shadowed by the built-in command.".into(),
+                description: None,
+            },
+        ];
+        let parse = |text| SlashCommand::parse(text, &snippets).unwrap();
+
+        assert_eq!(parse("hello /clear"), None);
+        assert_eq!(parse(" /clear\n"), Some(SlashCommand::Clear));
+        assert_eq!(parse("/model"), Some(SlashCommand::Model(None)));
+        assert_eq!(
+            parse("/model  gpt-5 "),
+            Some(SlashCommand::Model(Some("gpt-5".into())))
+        );
+        assert_eq!(
+            parse("/cwd ~/my project"),
+            Some(SlashCommand::Cwd(Some(PathBuf::from("~/my project"))))
+        );
+        assert_eq!(parse("/export"), Some(SlashCommand::Export(None)));
+        assert_eq!(
+            parse("//usr/bin is missing"),
+            Some(SlashCommand::Send("/usr/bin is missing".into()))
+        );
+
+        assert_eq!(
+            parse("/review src/main.rs"),
+            Some(SlashCommand::Send("Review src/main.rs carefully.".into()))
+        );
+        assert_eq!(
+            parse("/tests"),
+            Some(SlashCommand::Send("Write the tests.".into()))
+        );
+        assert_eq!(
+            parse("/tests\nfor the parser"),
+            Some(SlashCommand::Send(
+                "Write the tests.\n\nfor the parser".into()
+            ))
+        );
+
+        assert!(SlashCommand::parse("/clear all", &snippets).is_err());
+        assert!(SlashCommand::parse("/unknown", &snippets).is_err());
+    }
+}
@@ -5,12 +5,51 @@ use anyhow::{Result, Context};
 use directories::ProjectDirs;
 use ui::theme::{ThemeColor, ThemeMode};
 use ui::Colorize;
+use ui::highlighter::LanguageRegistry;
+use ui::lsp::{LanguageServerConfig, LspStore};
+use ui::spell_check::{HunspellDictionary, SpellCheck};
+use crate::commands::PromptSnippet;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AppConfig {
     pub working_dir: Option<PathBuf>,
     pub theme_mode: ThemeMode,
     pub ui_settings: UiSettings,
+    /// The language servers for the files in the `working_dir`.
+    #[serde(default)]
+    pub language_servers: Vec<LanguageServerConfig>,
+    /// The model of the chat, switched by `/model`.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// The models listed in the model pickers.
+    #[serde(default = "default_models")]
+    pub models: Vec<String>,
+    /// The prompts sent by `/<name>` in the chat input.
+    #[serde(default)]
+    pub snippets: Vec<PromptSnippet>,
+    /// Whether to check the spelling of the prompts.
+    #[serde(default = "default_spell_check")]
+    pub spell_check: bool,
+    /// The language of the spell check dictionary, see [`dictionaries_dir`].
+    #[serde(default = "default_spell_check_language")]
+    pub spell_check_language: String,
+    /// The sizes of the chat and the changes panel, as parts of the window.
+    #[serde(default)]
+    pub panel_sizes: Vec<f32>,
+}
+
+fn default_models() -> Vec<String> {
+    ["gpt-5", "gpt-5-mini", "claude-sonnet-4-5", "gemini-2.5-pro"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_spell_check() -> bool {
+    true
+}
+
+fn default_spell_check_language() -> String {
+    "en_US".into()
 }
 /// A trait for things which can access the global AppConfig.
 ///
@@ -240,6 +279,13 @@ impl Default for AppConfig {
             working_dir: None,
             theme_mode: ThemeMode::Dark,
             ui_settings: UiSettings::default(),
+            language_servers: vec![],
+            model: None,
+            models: default_models(),
+            snippets: vec![],
+            spell_check: default_spell_check(),
+            spell_check_language: default_spell_check_language(),
+            panel_sizes: vec![],
         }
     }
 }
@@ -252,6 +298,58 @@ pub fn config_path() -> Result<PathBuf> {
     Ok(config_dir.join("config.toml"))
 }
 
+/// The directory of the tree-sitter grammars loaded at startup, e.g.: `grammars/kotlin`.
+pub fn grammars_dir() -> Result<PathBuf> {
+    Ok(config_path()?.with_file_name("grammars"))
+}
+
+/// The file of the sent prompts, see [`crate::prompt_history::PromptHistory`].
+pub fn prompt_history_path() -> Result<PathBuf> {
+    Ok(config_path()?.with_file_name("prompt_history.json"))
+}
+
+/// The file of the unsent drafts, see [`crate::drafts::Drafts`].
+pub fn drafts_path() -> Result<PathBuf> {
+    Ok(config_path()?.with_file_name("drafts.json"))
+}
+
+/// The directory of the hunspell dictionaries, e.g.: `dictionaries/en_US.aff` and
+/// `dictionaries/en_US.dic`. The system dictionaries in `/usr/share/hunspell` are used if
+/// missing.
+pub fn dictionaries_dir() -> Result<PathBuf> {
+    Ok(config_path()?.with_file_name("dictionaries"))
+}
+
+/// The directories to look up the dictionaries in, the user's dictionaries first.
+pub fn dictionary_dirs() -> impl Iterator<Item = PathBuf> {
+    dictionaries_dir()
+        .into_iter()
+        .chain([PathBuf::from("/usr/share/hunspell")])
+}
+
+/// The languages of the dictionaries in the [`dictionary_dirs`], sorted, e.g.: `en_US`.
+pub fn dictionary_languages() -> Vec<String> {
+    let mut languages = dictionary_dirs()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "dic" || !path.with_extension("aff").exists() {
+                return None;
+            }
+            path.file_stem()?.to_str().map(String::from)
+        })
+        .collect::<Vec<_>>();
+    languages.sort();
+    languages.dedup();
+    languages
+}
+
+/// The words added by "Add to dictionary", one word per line.
+pub fn personal_dictionary_path() -> Result<PathBuf> {
+    Ok(config_path()?.with_file_name("personal_dictionary.txt"))
+}
+
 pub fn load_config() -> Result<AppConfig> {
     let path = config_path()?;
     if path.exists() {
@@ -270,3 +368,79 @@ pub fn save_config(config: &AppConfig) -> Result<()> {
     fs::write(path, content)?;
     Ok(())
 }
+
+/// Update the global AppConfig and save it.
+pub fn update_config(cx: &mut gpui::App, f: impl FnOnce(&mut AppConfig)) {
+    let mut new_config = cx.config().clone();
+    f(&mut new_config);
+    save_config(&new_config).ok();
+    *cx.global_mut::<AppConfig>() = new_config;
+}
+
+/// Change the working directory, the language servers are restarted in the new directory.
+pub fn set_working_dir(dir: PathBuf, cx: &mut gpui::App) {
+    LspStore::global(cx).update(cx, |store, cx| {
+        store.set_root(Some(dir.clone()), cx);
+    });
+    update_config(cx, |config| config.working_dir = Some(dir));
+}
+
+/// Load the tree-sitter grammars of the grammars directory in the background, the languages are
+/// registered once loaded.
+pub fn load_grammars(cx: &mut gpui::App) {
+    let Ok(dir) = grammars_dir() else {
+        return;
+    };
+
+    let task = cx
+        .background_executor()
+        .spawn(async move { ui::highlighter::load_grammars(dir) });
+    cx.spawn(async move |cx| {
+        let grammars = task.await;
+        _ = cx.update(|cx| {
+            let registry = LanguageRegistry::global_mut(cx);
+            for (name, config) in grammars {
+                registry.register(&name, &config);
+            }
+        });
+    })
+    .detach();
+}
+
+/// Load the dictionary of the `spell_check_language` in the background, the inputs are checked
+/// once loaded.
+pub fn load_spell_check(cx: &mut gpui::App) {
+    if let Ok(path) = personal_dictionary_path() {
+        SpellCheck::global_mut(cx).set_personal_dictionary(path);
+    }
+    if !cx.config().spell_check {
+        SpellCheck::global_mut(cx).clear_checker();
+        cx.refresh_windows();
+        return;
+    }
+    let language = cx.config().spell_check_language.clone();
+    let Some(path) = dictionary_dirs()
+        .map(|dir| dir.join(&language))
+        .find(|path| path.with_extension("dic").exists())
+    else {
+        tracing::warn!("no dictionary found for {}", language);
+        return;
+    };
+
+    // The dictionary of the previous language may be still loading.
+    let generation = SpellCheck::global_mut(cx).start_loading();
+    let task = cx
+        .background_executor()
+        .spawn(async move { HunspellDictionary::load(path) });
+    cx.spawn(async move |cx| match task.await {
+        Ok(dictionary) => {
+            _ = cx.update(|cx| {
+                if SpellCheck::global_mut(cx).set_loaded_checker(generation, dictionary) {
+                    cx.refresh_windows();
+                }
+            });
+        }
+        Err(err) => tracing::error!("failed to load the dictionary of {}: {:?}", language, err),
+    })
+    .detach();
+}
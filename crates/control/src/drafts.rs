@@ -0,0 +1,302 @@
+use std::{collections::HashMap, fs, ops::Range, path::PathBuf, time::Duration};
+
+use gpui::{App, Entity, Global, Subscription, Task, Timer, Window};
+use serde::{Deserialize, Serialize};
+use ui::input::{InputEvent, InputState};
+use uuid::Uuid;
+
+use crate::config::drafts_path;
+
+/// The delay to save the drafts after the last change.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The key of the draft in the home textarea, the description of the new task.
+pub const NEW_TASK: &str = "new-task";
+
+/// The prefix of the keys of the task drafts, see [`task_key`].
+const TASK_PREFIX: &str = "task:";
+
+/// Return a new key of the draft of a task, unlike the task ids that restart from 0 in the
+/// next session.
+pub fn task_key() -> String {
+    format!("{}{}", TASK_PREFIX, Uuid::new_v4())
+}
+
+pub fn init(cx: &mut App) {
+    let drafts = match drafts_path() {
+        Ok(path) => Drafts::load(path),
+        Err(_) => Drafts::default(),
+    };
+    cx.set_global(drafts);
+
+    // Save the pending changes, the debounced save is dropped on quit.
+    cx.on_app_quit(|cx| {
+        let drafts = Drafts::global_mut(cx);
+        if drafts.dirty {
+            drafts.save();
+        }
+        async {}
+    })
+    .detach();
+}
+
+/// The unsent text of a textarea.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Draft {
+    pub text: String,
+    /// The selection in the text, see [`InputState::selection`].
+    pub selection: Range<usize>,
+}
+
+/// The content of the drafts file.
+#[derive(Serialize, Deserialize, Default)]
+struct DraftsFile {
+    drafts: HashMap<String, Draft>,
+    /// The draft key of the active task, see [`Drafts::set_active_task`].
+    active_task: Option<String>,
+}
+
+/// The drafts of the textareas, e.g.: the message of a task, saved to disk while typing.
+#[derive(Default)]
+pub struct Drafts {
+    drafts: HashMap<String, Draft>,
+    active_task: Option<String>,
+    path: Option<PathBuf>,
+    /// Whether the drafts are changed after the last save.
+    dirty: bool,
+    _save_task: Option<Task<()>>,
+}
+
+impl Global for Drafts {}
+
+impl Drafts {
+    /// Load the drafts from the JSON file. The tasks are not restored in the next session, so
+    /// the drafts of the tasks are dropped except the one of the active task, that is restored
+    /// to the first task, see [`Drafts::restored_task`].
+    fn load(path: PathBuf) -> Self {
+        let DraftsFile {
+            mut drafts,
+            active_task,
+        } = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        drafts.retain(|key, _| !key.starts_with(TASK_PREFIX) || Some(key) == active_task.as_ref());
+
+        Self {
+            drafts,
+            active_task,
+            path: Some(path),
+            dirty: false,
+            _save_task: None,
+        }
+    }
+
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    pub fn global_mut(cx: &mut App) -> &mut Self {
+        cx.global_mut::<Self>()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Draft> {
+        self.drafts.get(key)
+    }
+
+    /// Update the draft of the key, the empty draft is removed. The drafts are saved after
+    /// [`SAVE_DEBOUNCE`] if changed.
+    pub fn update(key: &str, draft: Draft, cx: &mut App) {
+        if !Self::global_mut(cx).set(key, draft) {
+            return;
+        }
+        Self::schedule_save(cx);
+    }
+
+    /// Set the draft key of the active task, its draft is kept for the first task of the next
+    /// session.
+    pub fn set_active_task(key: &str, cx: &mut App) {
+        let drafts = Self::global_mut(cx);
+        if drafts.active_task.as_deref() == Some(key) {
+            return;
+        }
+        drafts.active_task = Some(key.to_string());
+        drafts.dirty = true;
+        Self::schedule_save(cx);
+    }
+
+    /// Return the draft key of the active task of the last session if it has a draft.
+    pub fn restored_task(&self) -> Option<&str> {
+        self.active_task
+            .as_deref()
+            .filter(|key| self.drafts.contains_key(*key))
+    }
+
+    fn schedule_save(cx: &mut App) {
+        // Replacing the task cancels the previous save.
+        let task = cx.spawn(async move |cx| {
+            Timer::after(SAVE_DEBOUNCE).await;
+            _ = cx.update(|cx| Self::global_mut(cx).save());
+        });
+        Self::global_mut(cx)._save_task = Some(task);
+    }
+
+    /// Set the draft of the key, the empty draft is removed. Returns true if changed.
+    fn set(&mut self, key: &str, draft: Draft) -> bool {
+        let changed = if draft.text.trim().is_empty() {
+            self.drafts.remove(key).is_some()
+        } else {
+            self.drafts.insert(key.to_string(), draft.clone()) != Some(draft)
+        };
+        self.dirty |= changed;
+        changed
+    }
+
+    /// Return the draft to restore to the textarea of the key, that has the `text` of the
+    /// previous key. The empty draft clears the text, `None` if nothing to change.
+    fn restore(&self, key: &str, text: &str) -> Option<Draft> {
+        match self.get(key) {
+            Some(draft) => Some(draft.clone()),
+            None if !text.is_empty() => Some(Draft::default()),
+            None => None,
+        }
+    }
+
+    fn save(&mut self) {
+        self.dirty = false;
+        let Some(path) = &self.path else {
+            return;
+        };
+        let file = DraftsFile {
+            drafts: self.drafts.clone(),
+            active_task: self.active_task.clone(),
+        };
+        if let Ok(content) = serde_json::to_string(&file) {
+            fs::write(path, content).ok();
+        }
+    }
+}
+
+/// Restore the draft of the key to the textarea, or clear it if there is no draft, and keep
+/// the draft updated while the subscription is alive.
+///
+/// The text is saved on change, and the selection is saved on change and blur.
+pub fn autosave(
+    key: impl Into<String>,
+    textarea: &Entity<InputState>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Subscription {
+    let key = key.into();
+    // Replace the text of the previous key, e.g.: when switching the tasks.
+    if let Some(draft) = Drafts::global(cx).restore(&key, textarea.read(cx).value()) {
+        textarea.update(cx, |textarea, cx| {
+            textarea.set_value(draft.text, window, cx);
+            textarea.set_selection(draft.selection, window, cx);
+        });
+    }
+
+    cx.subscribe(textarea, move |textarea, event: &InputEvent, cx| {
+        if !matches!(event, InputEvent::Change(_) | InputEvent::Blur) {
+            return;
+        }
+
+        let textarea = textarea.read(cx);
+        let draft = Draft {
+            text: textarea.value().to_string(),
+            selection: textarea.selection(),
+        };
+        Drafts::update(&key, draft, cx);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{Draft, Drafts, NEW_TASK, task_key};
+
+    fn draft(text: &str, selection: std::ops::Range<usize>) -> Draft {
+        Draft {
+            text: text.into(),
+            selection,
+        }
+    }
+
+    #[test]
+    fn test_set() {
+        let mut drafts = Drafts::default();
+        assert!(!drafts.set("a", draft(" \n", 0..0)));
+        assert!(!drafts.dirty);
+
+        assert!(drafts.set("a", draft("Hello", 5..5)));
+        assert!(drafts.dirty);
+        assert!(!drafts.set("a", draft("Hello", 5..5)));
+        // The selection is a change.
+        assert!(drafts.set("a", draft("Hello", 0..5)));
+        assert_eq!(drafts.get("a"), Some(&draft("Hello", 0..5)));
+
+        // The empty draft is removed.
+        assert!(drafts.set("a", draft("  ", 2..2)));
+        assert_eq!(drafts.get("a"), None);
+        assert!(!drafts.set("a", draft("", 0..0)));
+    }
+
+    #[test]
+    fn test_restore() {
+        let mut drafts = Drafts::default();
+        drafts.set("a", draft("Hello", 1..3));
+
+        assert_eq!(drafts.restore("a", ""), Some(draft("Hello", 1..3)));
+        assert_eq!(drafts.restore("a", "World"), Some(draft("Hello", 1..3)));
+        // Clear the text of the previous key.
+        assert_eq!(drafts.restore("b", "World"), Some(Draft::default()));
+        assert_eq!(drafts.restore("b", ""), None);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("drafts-{}.json", std::process::id()));
+        _ = fs::remove_file(&path);
+
+        let mut drafts = Drafts::load(path.clone());
+        assert_eq!(drafts.get("a"), None);
+        drafts.set("a", draft("Hello", 5..5));
+        drafts.save();
+        assert!(!drafts.dirty);
+
+        let drafts = Drafts::load(path.clone());
+        assert_eq!(drafts.get("a"), Some(&draft("Hello", 5..5)));
+        _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_load_keeps_active_task_draft() {
+        let path = std::env::temp_dir().join(format!("drafts-tasks-{}.json", std::process::id()));
+        let (key, active_key) = (task_key(), task_key());
+        assert_ne!(key, active_key);
+        let mut drafts = Drafts::load(path.clone());
+        drafts.set(NEW_TASK, draft("New", 3..3));
+        drafts.set(&key, draft("Reply", 5..5));
+        drafts.set(&active_key, draft("Active", 6..6));
+        drafts.active_task = Some(active_key.clone());
+        drafts.save();
+
+        // The tasks are not restored, so are their drafts except the one of the active task.
+        let drafts = Drafts::load(path.clone());
+        assert_eq!(drafts.get(NEW_TASK), Some(&draft("New", 3..3)));
+        assert_eq!(drafts.get(&key), None);
+        assert_eq!(drafts.get(&active_key), Some(&draft("Active", 6..6)));
+        assert_eq!(drafts.restored_task(), Some(active_key.as_str()));
+        _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_restored_task() {
+        let mut drafts = Drafts::default();
+        assert_eq!(drafts.restored_task(), None);
+        drafts.active_task = Some(task_key());
+        // The active task has no draft.
+        assert_eq!(drafts.restored_task(), None);
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use gpui::{ClickEvent, Context, Entity, Render, Task, Timer, Window, div, prelude::*};
+use rfd::FileDialog;
+use ui::{
+    ActiveTheme, Button, ButtonGroup, ButtonVariants, ContextModal, IconName, Sizable,
+    diff_view::DiffView,
+    h_flex,
+    highlighter::LanguageRegistry,
+    input::{InputState, TextInput},
+    lsp::{Location, LspStore, position_to_offset},
+    notification::Notification,
+    v_flex,
+};
+
+use crate::config::ActiveConfig;
+
+/// The interval to check if the opened file is changed on disk.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ViewMode {
+    /// The current text of the file.
+    #[default]
+    File,
+    /// The diff of the file since it was opened.
+    Changes,
+}
+
+/// A read-only code editor of a file in the working directory, the language is detected from
+/// the path and the first line of the file.
+///
+/// The file is reloaded when changed on disk, e.g.: edited by the agent, and the changes since
+/// it was opened are shown in a [`DiffView`].
+pub struct FileViewer {
+    path: Option<PathBuf>,
+    editor: Entity<InputState>,
+    diff_view: Option<Entity<DiffView>>,
+    mode: ViewMode,
+    /// The text of the file when opened.
+    baseline: String,
+    /// The modified time of the file when last read.
+    modified: Option<SystemTime>,
+    _watch_task: Option<Task<()>>,
+}
+
+impl FileViewer {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let editor = cx.new(|cx| {
+            InputState::new(window, cx)
+                .code_editor("text")
+                .disabled(true)
+        });
+
+        Self {
+            path: None,
+            editor,
+            diff_view: None,
+            mode: ViewMode::default(),
+            baseline: String::new(),
+            modified: None,
+            _watch_task: None,
+        }
+    }
+
+    /// Open the file and highlight it by the detected language, the changes are compared to
+    /// the text read now. The file is opened in the language server of the language if any,
+    /// see [`LspStore::open`].
+    pub fn open(
+        &mut self,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        let text = fs::read_to_string(&path)?;
+        let first_line = text.split('\n').next().unwrap_or_default();
+        let language = LanguageRegistry::global(cx).detect(&path, first_line);
+        let diff_view = cx.new(|cx| {
+            let diff_view = DiffView::new(text.clone(), text.clone(), window, cx);
+            match language {
+                Some(language) => diff_view.language(language),
+                None => diff_view,
+            }
+        });
+
+        self.editor.update(cx, |editor, cx| {
+            editor.set_value(text.clone(), window, cx);
+            editor.set_highlighter_for_path(&path, cx);
+        });
+        let editor = self.editor.clone();
+        LspStore::global(cx).update(cx, |store, cx| store.open(&editor, &path, window, cx));
+        self.modified = modified_time(&path);
+        self.baseline = text;
+        self.diff_view = Some(diff_view);
+        self.path = Some(path);
+        self._watch_task = Some(cx.spawn_in(window, async move |this, cx| {
+            loop {
+                Timer::after(WATCH_INTERVAL).await;
+                if this
+                    .update_in(cx, |this, window, cx| this.reload_if_modified(window, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }));
+        cx.notify();
+        Ok(())
+    }
+
+    /// Open the file if not opened, and move the cursor to the start of the location.
+    pub fn open_location(
+        &mut self,
+        path: PathBuf,
+        location: Location,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        if self.path.as_ref() != Some(&path) {
+            self.open(path, window, cx)?;
+        }
+        self.mode = ViewMode::File;
+        self.editor.update(cx, |editor, cx| {
+            let offset = position_to_offset(editor.value(), location.range.start);
+            editor.move_cursor_to(offset, window, cx);
+        });
+        cx.notify();
+        Ok(())
+    }
+
+    /// Reload the file if the modified time is changed, and update the changes.
+    fn reload_if_modified(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        let modified = modified_time(&path);
+        if modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) => {
+                tracing::error!("failed to reload {}: {:?}", path.display(), err);
+                return;
+            }
+        };
+        self.editor.update(cx, |editor, cx| {
+            editor.set_value(text.clone(), window, cx);
+        });
+        if let Some(diff_view) = &self.diff_view {
+            diff_view.update(cx, |diff_view, cx| {
+                diff_view.set_texts(self.baseline.clone(), text, cx);
+            });
+        }
+        cx.notify();
+    }
+
+    fn pick_file(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let mut dialog = FileDialog::new();
+        if let Some(dir) = &cx.config().working_dir {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+
+        if let Err(err) = self.open(path, window, cx) {
+            window.push_notification(Notification::error(err.to_string()), cx);
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Return the path relative to the working directory, or the full path if outside of it.
+fn display_path(path: &Path, working_dir: Option<&Path>) -> String {
+    working_dir
+        .and_then(|dir| path.strip_prefix(dir).ok())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+impl Render for FileViewer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = match &self.path {
+            Some(path) => display_path(path, cx.config().working_dir.as_deref()),
+            None => "No file opened".to_string(),
+        };
+        let open_listener = cx.listener(Self::pick_file);
+        let on_mode_click = cx.listener(|this, selected: &Vec<usize>, _, cx| {
+            this.mode = if selected.contains(&1) {
+                ViewMode::Changes
+            } else {
+                ViewMode::File
+            };
+            cx.notify();
+        });
+
+        v_flex()
+            .size_full()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .px_3()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .text_sm()
+                    .child(div().flex_1().truncate().child(title))
+                    .when(self.path.is_some(), |this| {
+                        this.child(
+                            ButtonGroup::new("file-view-mode")
+                                .outline()
+                                .xsmall()
+                                .child(Button::new("file-view-file").label("File"))
+                                .child(Button::new("file-view-changes").label("Changes"))
+                                .selected([match self.mode {
+                                    ViewMode::File => 0,
+                                    ViewMode::Changes => 1,
+                                }])
+                                .on_click(on_mode_click),
+                        )
+                    })
+                    .child(
+                        Button::new("open-file")
+                            .ghost()
+                            .xsmall()
+                            .icon(IconName::Folder)
+                            .tooltip("Open file")
+                            .on_click(cx, open_listener),
+                    ),
+            )
+            .when(self.path.is_some(), |this| {
+                this.child(div().flex_1().min_h_0().map(|this| {
+                    match (self.mode, &self.diff_view) {
+                        (ViewMode::Changes, Some(diff_view)) => this.child(diff_view.clone()),
+                        _ => this.child(TextInput::new(&self.editor).bordered(false).h_full()),
+                    }
+                }))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::display_path;
+
+    #[test]
+    fn test_display_path() {
+        let dir = Path::new("/work/project");
+        assert_eq!(
+            display_path(Path::new("/work/project/src/main.rs"), Some(dir)),
+            "src/main.rs"
+        );
+        assert_eq!(
+            display_path(Path::new("/etc/hosts"), Some(dir)),
+            "/etc/hosts"
+        );
+        assert_eq!(display_path(Path::new("/etc/hosts"), None), "/etc/hosts");
+    }
+}
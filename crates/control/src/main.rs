@@ -1,24 +1,46 @@
 mod chat;
+mod commands;
 mod config;
+mod drafts;
+mod file_viewer;
+mod prompt_history;
+mod settings;
 mod state;
+mod tasks;
 
-use crate::chat::ActiveChat;
-use crate::config::{ActiveConfig, AppConfig, load_config, save_config};
-use crate::state::{Part, Role};
+use std::path::PathBuf;
+
+use crate::chat::{ActiveChat, export_conversation};
+use crate::config::{
+    ActiveConfig, AppConfig, load_config, load_grammars, load_spell_check, save_config,
+    set_working_dir, update_config,
+};
+use crate::file_viewer::FileViewer;
+use crate::prompt_history::PromptRecall;
+use crate::settings::SettingsPage;
+use crate::tasks::{SwitchTask, TaskItems, TaskList, TaskListEvent, TaskTable};
 use gpui::{
-    AnyElement, AnyView, App, Application, Bounds, ClickEvent, Context, Decorations, ElementId,
-    Entity, EventEmitter, Focusable, Global, SharedString, Styled, WeakEntity, Window,
-    WindowBounds, WindowDecorations, WindowOptions, div, prelude::*, px, rems, size,
-    transparent_black,
+    AnyElement, AnyView, App, Application, Axis, Bounds, ClickEvent, Context, Decorations,
+    ElementId, Entity, EventEmitter, Focusable, Global, ScrollHandle, SharedString, Styled,
+    Subscription, WeakEntity, Window, WindowBounds, WindowDecorations, WindowOptions, div,
+    prelude::*, px, rems, size, transparent_black,
 };
 use rfd::FileDialog;
 use ui::{
-    Assets, Button, Icon, IconName, NewTaskSidebar, Root, Sidebar, SidebarToggleButton, StyledExt,
-    TitleBar,
+    Assets, Button, Icon, IconName, NewTaskSidebar, Root, Sidebar, SidebarMenu, SidebarMenuItem,
+    SidebarToggleButton, Sizable, StyledExt, TitleBar, diff_view,
     focus::{self, EnterFocusEvent},
     h_flex, highlighter,
     input::{self, InputEvent, InputState, TextInput},
+    list::{self, List, ListEvent},
+    lsp::{self, Location, LspStore},
     notification::Notification,
+    popover,
+    popup_menu::{self, PopupMenuItem},
+    resizable::{ResizableEvent, ResizablePanel, ResizablePanelGroup, ResizableState},
+    select, slider, spell_check,
+    tab::{Tab, TabBar},
+    table::{self, Table, TableDelegate, TableEvent},
     theme,
     theme::{ActiveTheme, Theme, ThemeMode},
     v_flex,
@@ -43,8 +65,9 @@ impl Route {
                 "textarea_main",
                 "working_dir",
                 "submit",
+                "task_table",
             ],
-            Self::Settings => Vec::new(),
+            Self::Settings => SettingsPage::FOCUS_CYCLE.to_vec(),
             Self::Chat => vec![
                 "edit_message_textarea",
                 "cancel_edit_message",
@@ -108,13 +131,17 @@ struct ControlRoot {
     title_bar: Entity<ControlTitleBar>,
     view: AnyView,
     sidebar_collapsed: bool,
+    tabs_scroll_handle: ScrollHandle,
+    panels: Entity<ResizableState>,
+    changes_open: bool,
+    file_viewer: Entity<FileViewer>,
 }
 
 impl ControlRoot {
     pub fn new(
         title: impl Into<SharedString>,
         view: impl Into<AnyView>,
-        _window: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
         let title_bar = cx.new(|cx| ControlTitleBar::new(title, cx));
@@ -126,13 +153,217 @@ impl ControlRoot {
             }
         })
         .detach();
+        let task_list = TaskList::global(cx);
+        cx.observe(&task_list, |_, _, cx| cx.notify()).detach();
+        cx.subscribe(&task_list, |this, task_list, event, cx| match event {
+            TaskListEvent::Activated(id) => {
+                let ix = task_list
+                    .read(cx)
+                    .tasks()
+                    .iter()
+                    .position(|task| task.id == *id);
+                if let Some(ix) = ix {
+                    this.tabs_scroll_handle.scroll_to_item(ix);
+                }
+            }
+        })
+        .detach();
+        let panel_sizes = cx.config().panel_sizes.clone();
+        let panels = cx.new(|_| ResizableState::new(Axis::Horizontal).sizes(panel_sizes));
+        cx.subscribe(&panels, |_, _, event, cx| match event {
+            ResizableEvent::Resized(sizes) => {
+                update_config(cx, |config| config.panel_sizes = sizes.clone());
+            }
+        })
+        .detach();
+        let this = cx.entity().downgrade();
+        LspStore::global(cx).update(cx, |store, _| {
+            store.on_open_location(move |path, location, window, cx| {
+                _ = this.update(cx, |this, cx| {
+                    this.open_location(path, location, window, cx)
+                });
+            });
+        });
 
         Self {
             title_bar,
             view: view.into(),
             sidebar_collapsed: false,
+            tabs_scroll_handle: ScrollHandle::new(),
+            panels,
+            changes_open: false,
+            file_viewer: cx.new(|cx| FileViewer::new(window, cx)),
         }
     }
+
+    fn open_task(&mut self, id: usize, cx: &mut Context<Self>) {
+        TaskList::global(cx).update(cx, |tasks, cx| tasks.activate(id, cx));
+        nav!(cx, Route::Chat);
+    }
+
+    /// Open the location in the file viewer of the changes panel, e.g.: the definition in
+    /// another file.
+    fn open_location(
+        &mut self,
+        path: PathBuf,
+        location: Location,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let result = self.file_viewer.update(cx, |viewer, cx| {
+            viewer.open_location(path, location, window, cx)
+        });
+        match result {
+            Ok(()) => {
+                self.changes_open = true;
+                cx.notify();
+            }
+            Err(err) => window.push_notification(Notification::error(err.to_string()), cx),
+        }
+    }
+
+    /// Open the modal to search the tasks and open one.
+    fn switch_task(&mut self, _: &SwitchTask, window: &mut Window, cx: &mut Context<Self>) {
+        let list = cx.new(|cx| List::new(TaskItems::new(cx), window, cx).searchable(true));
+        cx.subscribe_in(
+            &list,
+            window,
+            |this, list, event: &ListEvent, window, cx| match event {
+                ListEvent::Confirm(ix) => {
+                    if let Some(id) = list.read(cx).delegate().task_id(*ix) {
+                        window.close_modal(cx);
+                        this.open_task(id, cx);
+                    }
+                }
+                ListEvent::Cancel => window.close_modal(cx),
+                ListEvent::Select(_) => {}
+            },
+        )
+        .detach();
+
+        window.open_modal(cx, {
+            let list = list.clone();
+            move |modal, _, _| {
+                modal
+                    .title("Switch task")
+                    .child(div().h(px(320.)).child(list.clone()))
+            }
+        });
+        list.update(cx, |list, cx| {
+            list.refresh(cx);
+            list.focus(window, cx);
+        });
+    }
+
+    /// The open tasks as tabs above the chat.
+    fn render_tab_bar(&mut self, cx: &mut Context<Self>) -> TabBar {
+        let new_task_listener =
+            cx.listener(|_this, _: &ClickEvent, _window, cx| nav!(cx, Route::Home));
+        let new_task_button = Button::new("new-task-tab")
+            .ghost()
+            .compact()
+            .icon(IconName::Plus)
+            .tooltip("New task")
+            .on_click(cx, new_task_listener);
+        let changes_listener = cx.listener(|this, _: &ClickEvent, _window, cx| {
+            this.changes_open = !this.changes_open;
+            cx.notify();
+        });
+        let changes_button = Button::new("toggle-changes")
+            .ghost()
+            .compact()
+            .icon(if self.changes_open {
+                IconName::PanelRightClose
+            } else {
+                IconName::PanelRightOpen
+            })
+            .tooltip("Toggle changes")
+            .on_click(cx, changes_listener);
+
+        let task_list = TaskList::global(cx);
+        let tasks = task_list.read(cx).tasks();
+        let active_id = task_list.read(cx).active().id;
+        let ids = tasks.iter().map(|task| task.id).collect::<Vec<_>>();
+        let this = cx.entity().downgrade();
+
+        TabBar::new("task-tabs")
+            .small()
+            .track_scroll(&self.tabs_scroll_handle)
+            .children(
+                tasks
+                    .iter()
+                    .map(|task| Tab::new(task.title(cx)).closable(true)),
+            )
+            .selected_index(ids.iter().position(|id| *id == active_id))
+            .on_click({
+                let ids = ids.clone();
+                move |ix, _window, cx| {
+                    let id = ids[*ix];
+                    this.update(cx, |this, cx| this.open_task(id, cx)).ok();
+                }
+            })
+            .on_close(move |ix, _window, cx| {
+                let id = ids[*ix];
+                TaskList::global(cx).update(cx, |tasks, cx| tasks.remove(id, cx));
+            })
+            .on_reorder(|from, to, _window, cx| {
+                TaskList::global(cx).update(cx, |tasks, cx| tasks.move_task(from, to, cx));
+            })
+            .suffix(h_flex().child(new_task_button).child(changes_button))
+    }
+
+    /// The panel beside the chat to view a file of the working directory and its changes.
+    fn render_changes_panel(&self, _: &mut Context<Self>) -> impl IntoElement {
+        self.file_viewer.clone()
+    }
+
+    fn render_tasks(&mut self, cx: &mut Context<Self>) -> SidebarMenu {
+        let task_list = TaskList::global(cx);
+        let active_id = task_list.read(cx).active().id;
+        let on_chat = matches!(cx.try_global::<Route>(), Some(Route::Chat));
+        let this = cx.entity().downgrade();
+
+        SidebarMenu::new().children(task_list.read(cx).tasks().iter().rev().map(|task| {
+            let id = task.id;
+            let chat_state = task.chat_state.clone();
+            let this = this.clone();
+
+            SidebarMenuItem::new(task.title(cx))
+                .active(on_chat && id == active_id)
+                .on_click(
+                    cx.listener(move |this, _: &ClickEvent, _window, cx| this.open_task(id, cx)),
+                )
+                .context_menu(move |menu, _window, _cx| {
+                    menu.item(PopupMenuItem::new("Open").on_click({
+                        let this = this.clone();
+                        move |_window, cx| {
+                            this.update(cx, |this, cx| this.open_task(id, cx)).ok();
+                        }
+                    }))
+                    .item(PopupMenuItem::new("Export…").on_click({
+                        let chat_state = chat_state.clone();
+                        move |window, cx| {
+                            let Some(path) = FileDialog::new()
+                                .set_file_name("conversation.md")
+                                .save_file()
+                            else {
+                                return;
+                            };
+                            let notification = export_conversation(chat_state.read(cx), &path);
+                            window.push_notification(notification, cx);
+                        }
+                    }))
+                    .separator()
+                    .item(
+                        PopupMenuItem::new("Delete")
+                            .icon(IconName::Delete)
+                            .on_click(move |_window, cx| {
+                                TaskList::global(cx).update(cx, |tasks, cx| tasks.remove(id, cx));
+                            }),
+                    )
+                })
+        }))
+    }
 }
 
 impl Render for ControlRoot {
@@ -141,6 +372,10 @@ impl Render for ControlRoot {
 
         let settings_listener =
             cx.listener(|_this, _: &ClickEvent, _window, cx| nav!(cx, Route::Settings));
+        let new_task_listener =
+            cx.listener(|_this, _: &ClickEvent, _window, cx| nav!(cx, Route::Home));
+        let tasks = self.render_tasks(cx);
+        let on_chat = matches!(cx.try_global::<Route>(), Some(Route::Chat));
 
         let settings_button = Button::new("settings")
             .w_full()
@@ -150,7 +385,9 @@ impl Render for ControlRoot {
             .on_click(cx, settings_listener);
 
         let notification_layer = Root::render_notification_layer(window, cx);
+        let modal_layer = Root::render_modal_layer(window, cx);
         v_flex()
+            .on_action(cx.listener(Self::switch_task))
             .size_full()
             .rounded(rounded_size)
             .child(self.title_bar.clone())
@@ -165,23 +402,41 @@ impl Render for ControlRoot {
                                 .collapsed(self.sidebar_collapsed)
                                 .floating(true)
                                 .width(px(230.))
-                                .child(NewTaskSidebar::new().on_new_task(|_ev, window, cx| {
-                                    window.push_notification(
-                                        Notification::info("Creating new task..."),
-                                        cx,
-                                    );
-                                }))
+                                .child(
+                                    NewTaskSidebar::new()
+                                        .on_new_task(new_task_listener)
+                                        .tasks(tasks),
+                                )
                                 .footer(settings_button),
                         ),
                     )
                     .child(
-                        div()
+                        v_flex()
                             .flex_1()
                             .size_full()
                             .overflow_hidden()
-                            .child(self.view.clone()),
+                            .when(on_chat, |this| this.child(self.render_tab_bar(cx)))
+                            .child(
+                                div().flex_1().overflow_hidden().child(
+                                    ResizablePanelGroup::new("main-panels", &self.panels)
+                                        .child(
+                                            ResizablePanel::new()
+                                                .min_size(px(360.))
+                                                .child(self.view.clone()),
+                                        )
+                                        .when(on_chat && self.changes_open, |this| {
+                                            this.child(
+                                                ResizablePanel::new()
+                                                    .size(0.4)
+                                                    .min_size(px(240.))
+                                                    .child(self.render_changes_panel(cx)),
+                                            )
+                                        }),
+                                ),
+                            ),
                     ),
             )
+            .children(modal_layer)
             .child(div().absolute().top_12().children(notification_layer))
     }
 }
@@ -250,47 +505,52 @@ impl Render for ControlTitleBar {
 
 pub struct MainApp {
     textarea: Entity<InputState>,
+    prompt_recall: Entity<PromptRecall>,
     active_chat: Entity<ActiveChat>,
+    settings_page: Entity<SettingsPage>,
+    task_table: Entity<Table<TaskTable>>,
+    _draft_autosave: Subscription,
 }
 
 impl MainApp {
+    /// Start a new task with the message, the [`ActiveChat`] shows the task once activated.
     fn submit_message(&self, cx: &mut Context<Self>, textarea: &Entity<InputState>) {
-        self.active_chat.update(cx, |chat, cx| {
-            let pushed = chat.chat_state.update(cx, |state, cx| {
-                let text = textarea.read(cx).value();
-                if text.trim().is_empty() {
-                    return false;
-                }
-
-                let id = state.add_message(Role::User, vec![Part::Text(text.trim().into())]);
-                chat.list_state.splice(id..id, 1);
-                true
+        let text = textarea.read(cx).value().clone();
+        if !text.trim().is_empty() {
+            let chat_state = TaskList::global(cx).update(cx, |tasks, cx| tasks.new_task(cx));
+            chat_state.update(cx, |state, _cx| {
+                state.send(text.trim().into());
             });
-            if pushed {
-                cx.goto(Route::Chat);
-                cx.notify();
-            }
-            let window_handle = cx.active_window();
+            self.prompt_recall
+                .update(cx, |recall, cx| recall.push(&text, cx));
+            cx.goto(Route::Chat);
+            cx.notify();
+        }
+        let window_handle = cx.active_window();
 
-            if let Some(window) = window_handle {
-                window
-                    .update(cx, |_, window, cx| {
-                        textarea.update(cx, |s, cx| s.set_value("", window, cx));
-                    })
-                    .ok();
-            }
-        });
+        if let Some(window) = window_handle {
+            window
+                .update(cx, |_, window, cx| {
+                    textarea.update(cx, |s, cx| s.set_value("", window, cx));
+                })
+                .ok();
+        }
     }
 
     fn new(window: &mut Window, cx: &mut Context<MainApp>) -> Self {
+        let textarea = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Describe your task")
+                .multi_line()
+                .auto_grow(3, 6)
+        });
         let m = MainApp {
-            textarea: cx.new(|cx| {
-                InputState::new(window, cx)
-                    .placeholder("Describe your task")
-                    .multi_line()
-                    .auto_grow(3, 6)
-            }),
+            prompt_recall: cx.new(|cx| PromptRecall::new(textarea.clone(), window, cx)),
+            _draft_autosave: drafts::autosave(drafts::NEW_TASK, &textarea, window, cx),
+            textarea,
             active_chat: cx.new(|cx| ActiveChat::new(window, cx)),
+            settings_page: cx.new(|cx| SettingsPage::new(window, cx)),
+            task_table: cx.new(|cx| Table::new(TaskTable::new(cx), window, cx).stripe(true)),
         };
         let hs = [
             ("textarea_main", m.textarea.focus_handle(cx)),
@@ -306,6 +566,7 @@ impl MainApp {
                     .edit_message_textarea
                     .focus_handle(cx),
             ),
+            ("task_table", m.task_table.focus_handle(cx)),
         ];
 
         for (n, h) in hs.clone() {
@@ -320,11 +581,40 @@ impl MainApp {
         })
         .detach();
 
+        cx.subscribe(&m.task_table, |_, table, event: &TableEvent, cx| {
+            let TableEvent::ConfirmRow(row_ix) = event else {
+                return;
+            };
+            if let Some(id) = table.read(cx).delegate().task_id(*row_ix) {
+                TaskList::global(cx).update(cx, |tasks, cx| tasks.activate(id, cx));
+                cx.goto(Route::Chat);
+            }
+        })
+        .detach();
+
+        // The messages are added to the tasks in the chat, reload when going back home.
+        let task_list = TaskList::global(cx);
+        cx.observe(&task_list, |this, _, cx| this.reload_task_table(cx))
+            .detach();
+        cx.observe_global::<Route>(|this, cx| {
+            if matches!(cx.global::<Route>(), Route::Home) {
+                this.reload_task_table(cx);
+            }
+        })
+        .detach();
+
         nav!(cx, Route::Home);
 
         m
     }
 
+    fn reload_task_table(&mut self, cx: &mut Context<Self>) {
+        self.task_table.update(cx, |table, cx| {
+            table.delegate_mut().reload(cx);
+            table.refresh(cx);
+        });
+    }
+
     fn render_settings_route(
         &mut self,
         window: &mut Window,
@@ -342,7 +632,7 @@ impl MainApp {
             .when(matches!(window.window_decorations(), Decorations::Client { tiling, .. } if !(tiling.bottom || tiling.right)), |el| {
                 el.rounded_br(cx.theme().radius)
             })
-            .child(div().child("Settings!"))
+            .child(self.settings_page.clone())
     }
 
     fn render_home_route(
@@ -357,13 +647,11 @@ impl MainApp {
             cx.theme().background
         };
         let appearance = textinput.appearance;
+        let has_tasks = self.task_table.read(cx).delegate().rows_count(cx) > 0;
         let on_file_click = cx.listener(|_this, _event: &ClickEvent, window, cx| {
             if let Some(p) = FileDialog::new().pick_folder() {
                 if p.to_str().unwrap_or("") != "" {
-                    let mut new_config = cx.config().clone();
-                    new_config.working_dir = Some(p);
-                    save_config(&new_config).ok();
-                    *cx.global_mut::<AppConfig>() = new_config;
+                    set_working_dir(p, cx);
                 }
             } else {
                 window.push_notification(Notification::error("Please select a folder."), cx);
@@ -404,6 +692,11 @@ impl MainApp {
                 div()
                     .w_full()
                     .max_w(rems(48.))
+                    .key_context(prompt_history::CONTEXT)
+                    .on_action(window.listener_for(&self.prompt_recall, PromptRecall::previous))
+                    .on_action(window.listener_for(&self.prompt_recall, PromptRecall::next))
+                    .on_action(window.listener_for(&self.prompt_recall, PromptRecall::search))
+                    .child(self.prompt_recall.clone())
                     .child(
                         textinput
                     )
@@ -436,6 +729,15 @@ impl MainApp {
                             )
                     )
             )
+            .when(has_tasks, |this| {
+                this.child(
+                    div()
+                        .w_full()
+                        .max_w(rems(48.))
+                        .h(px(200.))
+                        .child(self.task_table.clone()),
+                )
+            })
     }
 
     fn render_chat_route(
@@ -525,10 +827,28 @@ fn main() {
                 |window, cx| {
                     theme::init(cx, &t);
                     highlighter::init(cx);
+                    load_grammars(cx);
                     input::init(cx);
+                    diff_view::init(cx);
+                    lsp::init(cx);
+                    LspStore::global(cx).update(cx, |store, cx| {
+                        store.set_servers(config.language_servers.clone(), cx);
+                        store.set_root(config.working_dir.clone(), cx);
+                    });
                     Theme::change(config.theme_mode, None, cx);
                     println!("{:?}", window.gpu_specs());
                     focus::init(cx);
+                    prompt_history::init(cx);
+                    drafts::init(cx);
+                    tasks::init(cx);
+                    popup_menu::init(cx);
+                    popover::init(cx);
+                    select::init(cx);
+                    table::init(cx);
+                    list::init(cx);
+                    slider::init(cx);
+                    spell_check::init(cx);
+                    load_spell_check(cx);
                     let main_app = cx.new(|cx| MainApp::new(window, cx));
                     let control_root =
                         cx.new(|cx| ControlRoot::new("Control", main_app.clone(), window, cx));
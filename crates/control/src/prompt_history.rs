@@ -0,0 +1,418 @@
+use std::{fs, path::PathBuf};
+
+use gpui::{
+    App, AppContext, Context, Entity, Global, KeyBinding, Render, Subscription, Window, actions,
+    div, prelude::*, px,
+};
+use ui::{
+    ActiveTheme, h_flex,
+    history::{History, HistoryItem},
+    input::{InputEvent, InputState, TextInput},
+};
+
+use crate::config::prompt_history_path;
+
+pub const CONTEXT: &'static str = "PromptHistory";
+
+/// The maximum number of the prompts to keep, the oldest prompts are dropped.
+const MAX_PROMPTS: usize = 500;
+
+actions!(prompt_history, [PreviousPrompt, NextPrompt, SearchPrompts]);
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("alt-up", PreviousPrompt, Some(CONTEXT)),
+        KeyBinding::new("alt-down", NextPrompt, Some(CONTEXT)),
+        KeyBinding::new("ctrl-r", SearchPrompts, Some(CONTEXT)),
+    ]);
+
+    let history = match prompt_history_path() {
+        Ok(path) => PromptHistory::load(path),
+        Err(_) => PromptHistory::new(vec![]),
+    };
+    cx.set_global(history);
+}
+
+#[derive(Debug, Clone)]
+struct Prompt {
+    text: String,
+    version: usize,
+}
+
+impl PartialEq for Prompt {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl HistoryItem for Prompt {
+    fn version(&self) -> usize {
+        self.version
+    }
+
+    fn set_version(&mut self, version: usize) {
+        self.version = version;
+    }
+}
+
+/// The prompts sent in all the tasks, persisted across the sessions.
+///
+/// Recalling the previous prompt undoes it into the redo stack, so the last redo is the prompt
+/// shown in the input.
+pub struct PromptHistory {
+    history: History<Prompt>,
+    path: Option<PathBuf>,
+}
+
+impl Global for PromptHistory {}
+
+impl PromptHistory {
+    fn new(prompts: Vec<String>) -> Self {
+        let mut history = History::new().max_undo(MAX_PROMPTS).unique();
+        for text in prompts {
+            history.push(Prompt { text, version: 0 });
+        }
+
+        Self {
+            history,
+            path: None,
+        }
+    }
+
+    /// Load the prompts from the JSON file, the file is saved when a prompt is pushed.
+    fn load(path: PathBuf) -> Self {
+        let prompts = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            path: Some(path),
+            ..Self::new(prompts)
+        }
+    }
+
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    pub fn global_mut(cx: &mut App) -> &mut Self {
+        cx.global_mut::<Self>()
+    }
+
+    /// Push the sent prompt as the latest one, the same prompt sent before is moved.
+    pub fn push(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+
+        self.reset();
+        self.history.push(Prompt {
+            text: text.to_string(),
+            version: 0,
+        });
+        self.save();
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let prompts = self.prompts().collect::<Vec<_>>();
+        if let Ok(content) = serde_json::to_string(&prompts) {
+            fs::write(path, content).ok();
+        }
+    }
+
+    /// Return the prompts from the oldest to the latest.
+    fn prompts(&self) -> impl Iterator<Item = &str> {
+        self.history
+            .undos()
+            .iter()
+            .chain(self.history.redos().iter().rev())
+            .map(|prompt| prompt.text.as_str())
+    }
+
+    /// Stop recalling, the next [`PromptHistory::previous`] starts from the latest prompt.
+    fn reset(&mut self) {
+        while self.history.redo().is_some() {}
+    }
+
+    /// Return the prompt before the recalled one, `None` if at the oldest.
+    fn previous(&mut self) -> Option<String> {
+        self.history
+            .undo()
+            .and_then(|prompts| prompts.into_iter().next())
+            .map(|prompt| prompt.text)
+    }
+
+    /// Return the prompt after the recalled one, `None` if past the latest.
+    fn next(&mut self) -> Option<String> {
+        self.history.redo()?;
+        self.history
+            .redos()
+            .last()
+            .map(|prompt| prompt.text.clone())
+    }
+
+    /// Return the index of the latest prompt before `before` containing the query, ignoring
+    /// case. Call [`PromptHistory::reset`] first to search all the prompts.
+    fn search(&self, query: &str, before: usize) -> Option<usize> {
+        if query.is_empty() {
+            return None;
+        }
+
+        let query = query.to_lowercase();
+        self.prompts()
+            .take(before)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rposition(|text| text.to_lowercase().contains(&query))
+    }
+}
+
+struct Search {
+    /// The text before the search, restored if the search is cancelled.
+    draft: String,
+    match_ix: Option<usize>,
+}
+
+/// Recall the sent prompts in a textarea, `alt-up`/`alt-down` to cycle the prompts, and
+/// `ctrl-r` to search the prompts like the reverse-i-search of the shell.
+///
+/// Render it above the textarea, in an element with the [`CONTEXT`] key context that handles
+/// the [`PreviousPrompt`], [`NextPrompt`] and [`SearchPrompts`] actions.
+pub struct PromptRecall {
+    textarea: Entity<InputState>,
+    search_input: Entity<InputState>,
+    /// The text before recalling, `None` if not recalling.
+    draft: Option<String>,
+    search: Option<Search>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl PromptRecall {
+    pub fn new(textarea: Entity<InputState>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_input = cx.new(|cx| InputState::new(window, cx).placeholder("Search prompts"));
+        let _subscriptions = vec![cx.subscribe_in(
+            &search_input,
+            window,
+            |this, _, event: &InputEvent, window, cx| match event {
+                InputEvent::Change(query) => {
+                    let before = PromptHistory::global(cx).prompts().count();
+                    this.search_before(query, before, window, cx);
+                }
+                InputEvent::PressEnter { .. } | InputEvent::Blur => this.accept_search(window, cx),
+                InputEvent::PressEscape => this.cancel_search(window, cx),
+                _ => {}
+            },
+        )];
+
+        Self {
+            textarea,
+            search_input,
+            draft: None,
+            search: None,
+            _subscriptions,
+        }
+    }
+
+    /// Push the sent prompt to the history, and stop recalling.
+    pub fn push(&mut self, text: &str, cx: &mut Context<Self>) {
+        self.draft = None;
+        PromptHistory::global_mut(cx).push(text);
+    }
+
+    pub fn previous(&mut self, _: &PreviousPrompt, window: &mut Window, cx: &mut Context<Self>) {
+        if self.search.is_some() {
+            return;
+        }
+        if self.draft.is_none() {
+            PromptHistory::global_mut(cx).reset();
+        }
+        let Some(text) = PromptHistory::global_mut(cx).previous() else {
+            return;
+        };
+
+        if self.draft.is_none() {
+            self.draft = Some(self.textarea.read(cx).value().to_string());
+        }
+        self.show(text, window, cx);
+    }
+
+    pub fn next(&mut self, _: &NextPrompt, window: &mut Window, cx: &mut Context<Self>) {
+        if self.search.is_some() || self.draft.is_none() {
+            return;
+        }
+
+        match PromptHistory::global_mut(cx).next() {
+            Some(text) => self.show(text, window, cx),
+            None => {
+                let draft = self.draft.take().unwrap_or_default();
+                self.show(draft, window, cx);
+            }
+        }
+    }
+
+    /// Start searching, or search the older prompt if searching.
+    pub fn search(&mut self, _: &SearchPrompts, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(search) = &self.search {
+            let query = self.search_input.read(cx).value().to_string();
+            let before = match search.match_ix {
+                Some(ix) => ix,
+                None => PromptHistory::global(cx).prompts().count(),
+            };
+            self.search_before(&query, before, window, cx);
+            return;
+        }
+
+        let draft = match self.draft.take() {
+            Some(draft) => draft,
+            None => self.textarea.read(cx).value().to_string(),
+        };
+        PromptHistory::global_mut(cx).reset();
+        self.search = Some(Search {
+            draft,
+            match_ix: None,
+        });
+        self.search_input.update(cx, |input, cx| {
+            input.set_value("", window, cx);
+            input.focus(window, cx);
+        });
+        cx.notify();
+    }
+
+    fn search_before(
+        &mut self,
+        query: &str,
+        before: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let history = PromptHistory::global(cx);
+        let Some(ix) = history.search(query, before) else {
+            if let Some(search) = &mut self.search {
+                // Keep the older match if no more matches.
+                if before == history.prompts().count() {
+                    search.match_ix = None;
+                }
+            }
+            cx.notify();
+            return;
+        };
+
+        let text = history.prompts().nth(ix).unwrap_or_default().to_string();
+        if let Some(search) = &mut self.search {
+            search.match_ix = Some(ix);
+        }
+        self.show(text, window, cx);
+    }
+
+    fn accept_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.search.take().is_none() {
+            return;
+        }
+
+        self.textarea.update(cx, |textarea, cx| {
+            textarea.focus(window, cx);
+            textarea.move_cursor_to_end(window, cx);
+        });
+        cx.notify();
+    }
+
+    fn cancel_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(search) = self.search.take() else {
+            return;
+        };
+
+        self.show(search.draft, window, cx);
+        self.textarea
+            .update(cx, |textarea, cx| textarea.focus(window, cx));
+        cx.notify();
+    }
+
+    fn show(&mut self, text: String, window: &mut Window, cx: &mut Context<Self>) {
+        self.textarea.update(cx, |textarea, cx| {
+            textarea.set_value(text, window, cx);
+            textarea.move_cursor_to_end(window, cx);
+        });
+        cx.notify();
+    }
+}
+
+impl Render for PromptRecall {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(search) = &self.search else {
+            return div();
+        };
+
+        let has_query = !self.search_input.read(cx).value().is_empty();
+        h_flex()
+            .gap_2()
+            .px(px(10.))
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .text_sm()
+            .child(
+                div()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("reverse-i-search"),
+            )
+            .child(
+                div().flex_1().child(
+                    TextInput::new(&self.search_input)
+                        .appearance(false)
+                        .bordered(false),
+                ),
+            )
+            .when(has_query && search.match_ix.is_none(), |this| {
+                this.child(div().text_color(cx.theme().danger).child("No match"))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PromptHistory;
+
+    #[test]
+    fn test_recall() {
+        let mut history = PromptHistory::new(vec!["a".into(), "b".into(), "c".into()]);
+        assert_eq!(history.previous().as_deref(), Some("c"));
+        assert_eq!(history.previous().as_deref(), Some("b"));
+        assert_eq!(history.next().as_deref(), Some("c"));
+        assert_eq!(history.next(), None);
+        assert_eq!(history.next(), None);
+
+        assert_eq!(history.previous().as_deref(), Some("c"));
+        assert_eq!(history.previous().as_deref(), Some("b"));
+        assert_eq!(history.previous().as_deref(), Some("a"));
+        assert_eq!(history.previous(), None);
+
+        // Pushing stops recalling, and moves the same prompt to the latest.
+        history.push(" b\n");
+        assert_eq!(history.prompts().collect::<Vec<_>>(), vec!["a", "c", "b"]);
+        assert_eq!(history.previous().as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_search() {
+        let mut history = PromptHistory::new(vec![]);
+        for text in [
+            "Fix the tests",
+            "Explain the diff",
+            "fix the build",
+            "Refactor",
+        ] {
+            history.push(text);
+        }
+
+        let len = history.prompts().count();
+        assert_eq!(history.search("fix", len), Some(2));
+        assert_eq!(history.search("fix", 2), Some(0));
+        assert_eq!(history.search("fix", 0), None);
+        assert_eq!(history.search("", len), None);
+        assert_eq!(history.search("missing", len), None);
+    }
+}
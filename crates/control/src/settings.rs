@@ -0,0 +1,227 @@
+use gpui::{App, Context, Entity, FontWeight, SharedString, Window, div, prelude::*, px, rems};
+use ui::{
+    ActiveTheme, h_flex,
+    select::{Select, SelectEvent, SelectItem, SelectState},
+    slider::{Slider, SliderEvent, SliderState},
+    switch::Switch,
+    theme::{Theme, ThemeMode},
+    v_flex,
+};
+
+use crate::config::{
+    ActiveConfig, AppConfig, dictionary_languages, load_spell_check, update_config,
+};
+
+/// A select of the `models` of the config, the selected model is the `model` of the config.
+///
+/// The select is kept in sync with the config, e.g.: after `/model <name>`.
+pub fn model_select(window: &mut Window, cx: &mut App) -> Entity<SelectState> {
+    cx.new(|cx| {
+        cx.observe_global::<AppConfig>(|select: &mut SelectState, cx| {
+            let model = cx.config().model.clone().unwrap_or_default();
+            select.set_items(model_items(cx), cx);
+            select.set_selected_value(&model, cx);
+        })
+        .detach();
+        cx.subscribe_self(|_, event: &SelectEvent, cx| match event {
+            SelectEvent::Change(model) => {
+                update_config(cx, |config| config.model = Some(model.to_string()))
+            }
+        })
+        .detach();
+
+        SelectState::new(window, cx)
+            .searchable(true)
+            .placeholder("Select a model")
+            .items(model_items(cx))
+            .default_value(cx.config().model.clone().unwrap_or_default())
+    })
+}
+
+/// The configured models, and the selected model if switched to another one by `/model`.
+fn model_items(cx: &App) -> Vec<String> {
+    let config = cx.config();
+    let mut models = config.models.clone();
+    models.extend(
+        config
+            .model
+            .clone()
+            .filter(|model| !config.models.contains(model)),
+    );
+    models
+}
+
+/// The page of the settings route.
+pub struct SettingsPage {
+    theme_select: Entity<SelectState>,
+    model_select: Entity<SelectState>,
+    language_select: Entity<SelectState>,
+    radius_slider: Entity<SliderState>,
+}
+
+impl SettingsPage {
+    /// The ids of the controls in the focus cycle of the settings route.
+    pub const FOCUS_CYCLE: [&str; 2] = ["settings-radius", "settings-spell-check"];
+
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let theme_select = cx.new(|cx| {
+            SelectState::new(window, cx)
+                .items([
+                    SelectItem::new(ThemeMode::Light.name()).label("Light"),
+                    SelectItem::new(ThemeMode::Dark.name()).label("Dark"),
+                ])
+                .default_value(cx.theme().mode.name())
+        });
+        let language_select = cx.new(|cx| {
+            SelectState::new(window, cx)
+                .searchable(true)
+                .placeholder("No dictionary found")
+                .items(dictionary_languages())
+                .default_value(cx.config().spell_check_language.clone())
+        });
+
+        let radius = cx.config().ui_settings.rounded_size;
+        let radius_slider = cx.new(|_| SliderState::new().max(16.).default_value(radius));
+
+        cx.subscribe_in(
+            &theme_select,
+            window,
+            |_, _, event: &SelectEvent, window, cx| match event {
+                SelectEvent::Change(mode) => {
+                    let mode = match mode.as_ref() {
+                        "light" => ThemeMode::Light,
+                        _ => ThemeMode::Dark,
+                    };
+                    Theme::change(mode, Some(window), cx);
+                    update_config(cx, |config| config.theme_mode = mode);
+                }
+            },
+        )
+        .detach();
+        cx.subscribe(
+            &language_select,
+            |_, _, event: &SelectEvent, cx| match event {
+                SelectEvent::Change(language) => {
+                    update_config(cx, |config| {
+                        config.spell_check_language = language.to_string()
+                    });
+                    load_spell_check(cx);
+                }
+            },
+        )
+        .detach();
+        cx.subscribe(
+            &radius_slider,
+            |_, _, event: &SliderEvent, cx| match event {
+                SliderEvent::Change(radius) => {
+                    Theme::global_mut(cx).radius = px(*radius);
+                    update_config(cx, |config| config.ui_settings.rounded_size = *radius);
+                    cx.refresh_windows();
+                }
+            },
+        )
+        .detach();
+
+        // The theme is also toggled in the title bar.
+        cx.observe_global::<AppConfig>(|this, cx| {
+            let mode = cx.config().theme_mode;
+            this.theme_select
+                .update(cx, |select, cx| select.set_selected_value(mode.name(), cx));
+            cx.notify();
+        })
+        .detach();
+
+        Self {
+            theme_select,
+            model_select: model_select(window, cx),
+            language_select,
+            radius_slider,
+        }
+    }
+
+    fn render_row(
+        label: impl Into<SharedString>,
+        description: impl Into<SharedString>,
+        control: impl IntoElement,
+        cx: &App,
+    ) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .gap_4()
+            .py_3()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                v_flex()
+                    .flex_1()
+                    .gap_1()
+                    .child(div().font_weight(FontWeight::MEDIUM).child(label.into()))
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(description.into()),
+                    ),
+            )
+            .child(div().w(px(220.)).flex_shrink_0().child(control))
+    }
+}
+
+impl Render for SettingsPage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .id("settings")
+            .size_full()
+            .items_center()
+            .overflow_y_scroll()
+            .p(px(20.))
+            .child(
+                v_flex()
+                    .w_full()
+                    .max_w(rems(40.))
+                    .child(
+                        div()
+                            .text_xl()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .pb_2()
+                            .child("Settings"),
+                    )
+                    .child(Self::render_row(
+                        "Theme",
+                        "The appearance of the app.",
+                        Select::new(&self.theme_select),
+                        cx,
+                    ))
+                    .child(Self::render_row(
+                        "Corner radius",
+                        "The roundness of the corners, in pixels.",
+                        Slider::new("settings-radius", &self.radius_slider),
+                        cx,
+                    ))
+                    .child(Self::render_row(
+                        "Model",
+                        "The model of the chat, also switched by /model.",
+                        Select::new(&self.model_select),
+                        cx,
+                    ))
+                    .child(Self::render_row(
+                        "Spell check",
+                        "Underline the misspelled words of the prompts.",
+                        Switch::new("settings-spell-check")
+                            .checked(cx.config().spell_check)
+                            .on_click(|checked, _, cx| {
+                                let checked = *checked;
+                                update_config(cx, |config| config.spell_check = checked);
+                                load_spell_check(cx);
+                            }),
+                        cx,
+                    ))
+                    .child(Self::render_row(
+                        "Spell check language",
+                        "The dictionary to check the spelling of the prompts.",
+                        Select::new(&self.language_select).disabled(!cx.config().spell_check),
+                        cx,
+                    )),
+            )
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -20,6 +21,8 @@ pub enum Role {
 pub enum Part {
     Text(String),
     ToolCall(Tool),
+    /// The reasoning of the assistant before the answer.
+    Thinking(String),
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +31,16 @@ pub struct Tool {
     args: Arc<[serde_json::Value]>,
 }
 
+impl Tool {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn args(&self) -> &[serde_json::Value] {
+        &self.args
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Message {
     pub id: usize,
@@ -43,11 +56,13 @@ pub struct ChatState {
     pub edit_message_id: Option<usize>,
     pub focused_message_idx: Option<usize>,
     pub fake_focused_textarea: bool,
+    /// The tool calls and the thinking parts expanded in the chat, `(message id, part index)`.
+    pub open_parts: HashSet<(usize, usize)>,
     last_id: usize,
 }
 
 impl ChatState {
-    pub fn new(_window: &mut Window, _cx: &mut Context<Self>) -> Self {
+    pub fn new() -> Self {
         Self {
             messages: Vec::new(),
             last_id: 0,
@@ -55,6 +70,7 @@ impl ChatState {
             edit_message_id: None,
             focused_message_idx: None,
             fake_focused_textarea: false,
+            open_parts: HashSet::new(),
         }
     }
 
@@ -82,6 +98,123 @@ impl ChatState {
         }
     }
 
+    /// Remove the message, the ids of the following messages are shifted to keep them equal to
+    /// the indices.
+    pub fn delete_message(&mut self, id: usize) {
+        let Some(ix) = self.messages.iter().position(|m| m.id == id) else {
+            return;
+        };
+        self.messages.remove(ix);
+        for (ix, message) in self.messages.iter_mut().enumerate().skip(ix) {
+            message.id = ix;
+        }
+        self.last_id = self.messages.len();
+        self.open_parts = self
+            .open_parts
+            .drain()
+            .filter(|(message_id, _)| *message_id != id)
+            .map(|(message_id, part)| {
+                if message_id > id {
+                    (message_id - 1, part)
+                } else {
+                    (message_id, part)
+                }
+            })
+            .collect();
+
+        if self.edit_message_id == Some(id) {
+            self.edit_message_id = None;
+        }
+        let len = self.messages.len();
+        self.focused_message_idx = self
+            .focused_message_idx
+            .filter(|_| len > 0)
+            .map(|idx| idx.min(len - 1));
+    }
+
+    /// Return a new conversation with the messages until the message, inclusive.
+    pub fn branch(&self, id: usize) -> Self {
+        let messages = match self.messages.iter().position(|m| m.id == id) {
+            Some(ix) => self.messages[..=ix].to_vec(),
+            None => self.messages.clone(),
+        };
+        let mut branch = Self::new();
+        for message in messages {
+            branch.add_message(message.role, message.parts);
+        }
+        branch
+    }
+
+    /// Add the user message and wait for the reply of the agent, return the id.
+    pub fn send(&mut self, text: String) -> usize {
+        self.streaming = true;
+        self.add_message(Role::User, vec![Part::Text(text)])
+    }
+
+    /// Stop waiting for the reply, e.g.: when the run of the agent is stopped.
+    pub fn stop(&mut self) {
+        self.streaming = false;
+    }
+
+    /// Whether the agent is running and hasn't answered the last user message yet.
+    pub fn awaiting_response(&self) -> bool {
+        self.streaming
+            && self
+                .messages
+                .last()
+                .is_some_and(|message| message.role == Role::User)
+    }
+
+    pub fn clear(&mut self) {
+        self.streaming = false;
+        self.messages.clear();
+        self.edit_message_id = None;
+        self.focused_message_idx = None;
+        self.open_parts.clear();
+    }
+
+    /// Set the expanded parts among the `parts` of the message, the other parts are unchanged.
+    pub fn set_open_parts(&mut self, id: usize, parts: &[usize], open: &[usize]) {
+        self.open_parts
+            .retain(|(message_id, part)| *message_id != id || !parts.contains(part));
+        self.open_parts.extend(open.iter().map(|part| (id, *part)));
+    }
+
+    /// Remove the messages after the last user message to send it again and wait for the
+    /// reply, return the id of the last user message.
+    pub fn retry(&mut self) -> Option<usize> {
+        let ix = self.messages.iter().rposition(|m| m.role == Role::User)?;
+        self.streaming = true;
+        self.messages.truncate(ix + 1);
+        self.last_id = self.messages.len();
+        if self.focused_message_idx.is_some_and(|idx| idx > ix) {
+            self.focused_message_idx = None;
+        }
+        let id = self.messages[ix].id;
+        self.open_parts.retain(|(message_id, _)| *message_id <= id);
+        Some(id)
+    }
+
+    /// Return the conversation as markdown, the tool calls and the thinking are omitted.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        for message in &self.messages {
+            let title = match message.role {
+                Role::User => "## User",
+                Role::Assistant => "## Assistant",
+            };
+            markdown.push_str(title);
+            markdown.push_str("\n\n");
+            for part in &message.parts {
+                if let Part::Text(text) = part {
+                    markdown.push_str(text.trim());
+                    markdown.push_str("\n\n");
+                }
+            }
+        }
+        markdown
+    }
+
     pub fn up(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.messages.is_empty() {
             self.focused_message_idx = None;
@@ -140,3 +273,92 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("c", Copy, Some(CONTEXT)),
     ]);
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{ChatState, Part, Role};
+
+    fn chat(texts: &[&str]) -> ChatState {
+        let mut chat = ChatState::new();
+        for text in texts {
+            chat.add_message(Role::User, vec![Part::Text(text.to_string())]);
+        }
+        chat
+    }
+
+    fn texts(chat: &ChatState) -> Vec<(usize, String)> {
+        chat.messages
+            .iter()
+            .map(|m| match &m.parts[0] {
+                Part::Text(text) => (m.id, text.clone()),
+                Part::ToolCall(_) | Part::Thinking(_) => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_delete_message() {
+        let mut chat = chat(&["a", "b", "c"]);
+        chat.focused_message_idx = Some(2);
+        chat.open_parts = HashSet::from([(0, 0), (1, 0), (2, 1)]);
+        chat.delete_message(1);
+        assert_eq!(texts(&chat), vec![(0, "a".into()), (1, "c".into())]);
+        assert_eq!(chat.focused_message_idx, Some(1));
+        assert_eq!(chat.open_parts, HashSet::from([(0, 0), (1, 1)]));
+        assert_eq!(chat.add_message(Role::User, vec![]), 2);
+
+        chat.delete_message(5);
+        assert_eq!(chat.messages.len(), 3);
+    }
+
+    #[test]
+    fn test_retry() {
+        let mut chat = chat(&["a"]);
+        chat.add_message(Role::Assistant, vec![Part::Text("b".into())]);
+        chat.add_message(Role::Assistant, vec![Part::Text("c".into())]);
+        chat.focused_message_idx = Some(2);
+        chat.open_parts = HashSet::from([(0, 0), (1, 0), (2, 1)]);
+
+        assert_eq!(chat.retry(), Some(0));
+        assert!(chat.awaiting_response());
+        assert_eq!(texts(&chat), vec![(0, "a".into())]);
+        assert_eq!(chat.focused_message_idx, None);
+        assert_eq!(chat.open_parts, HashSet::from([(0, 0)]));
+        assert_eq!(chat.add_message(Role::Assistant, vec![]), 1);
+
+        assert_eq!(ChatState::new().retry(), None);
+    }
+
+    #[test]
+    fn test_send_and_stop() {
+        let mut chat = ChatState::new();
+        assert!(!chat.awaiting_response());
+        assert_eq!(chat.send("a".into()), 0);
+        assert!(chat.streaming);
+        assert!(chat.awaiting_response());
+
+        // The reply is streaming.
+        chat.add_message(Role::Assistant, vec![Part::Text("b".into())]);
+        assert!(chat.streaming);
+        assert!(!chat.awaiting_response());
+
+        chat.send("c".into());
+        chat.stop();
+        assert!(!chat.awaiting_response());
+        chat.send("d".into());
+        chat.clear();
+        assert!(!chat.streaming);
+    }
+
+    #[test]
+    fn test_branch() {
+        let mut chat = chat(&["a", "b", "c"]);
+        chat.focused_message_idx = Some(2);
+        let branch = chat.branch(1);
+        assert_eq!(texts(&branch), vec![(0, "a".into()), (1, "b".into())]);
+        assert_eq!(branch.focused_message_idx, None);
+        assert_eq!(chat.messages.len(), 3);
+    }
+}
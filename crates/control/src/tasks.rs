@@ -0,0 +1,395 @@
+use std::{
+    cmp::Reverse,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use gpui::{
+    App, AppContext, Context, Entity, EventEmitter, Global, IntoElement, KeyBinding, ParentElement,
+    SharedString, Styled, Task as AsyncTask, Window, actions, div, prelude::FluentBuilder, px,
+};
+use ui::{
+    ActiveTheme, h_flex,
+    list::{List, ListDelegate},
+    table::{Column, ColumnSort, Table, TableDelegate},
+};
+
+use crate::{
+    drafts::{self, Draft, Drafts},
+    state::{ChatState, Part, Role},
+};
+
+actions!(tasks, [SwitchTask]);
+
+pub fn init(cx: &mut App) {
+    let tasks = cx.new(TaskList::new);
+    cx.set_global(GlobalTaskList(tasks));
+    cx.bind_keys([KeyBinding::new("ctrl-p", SwitchTask, None)]);
+}
+
+/// A conversation listed in the sidebar.
+pub struct Task {
+    pub id: usize,
+    /// The key of the draft of the message, unique across the sessions, see [`drafts::task_key`].
+    pub draft_key: String,
+    pub chat_state: Entity<ChatState>,
+}
+
+impl Task {
+    /// The first line of the first user message, or "New task" if nothing is sent yet.
+    pub fn title(&self, cx: &App) -> SharedString {
+        self.chat_state
+            .read(cx)
+            .messages
+            .iter()
+            .filter(|m| m.role == Role::User)
+            .flat_map(|m| &m.parts)
+            .find_map(|part| match part {
+                Part::Text(text) => text.lines().find(|line| !line.trim().is_empty()),
+                _ => None,
+            })
+            .map(|line| SharedString::from(line.trim().to_string()))
+            .unwrap_or_else(|| "New task".into())
+    }
+}
+
+pub enum TaskListEvent {
+    /// The task with the id is shown in the chat.
+    Activated(usize),
+}
+
+/// The tasks of the session, there is always an active task.
+pub struct TaskList {
+    tasks: Vec<Task>,
+    active_id: usize,
+    last_id: usize,
+}
+
+struct GlobalTaskList(Entity<TaskList>);
+
+impl Global for GlobalTaskList {}
+
+impl EventEmitter<TaskListEvent> for TaskList {}
+
+impl TaskList {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let mut this = Self {
+            tasks: vec![],
+            active_id: 0,
+            last_id: 0,
+        };
+        // Keep the draft of the active task of the last session.
+        let draft_key = Drafts::global(cx)
+            .restored_task()
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(drafts::task_key);
+        this.insert(draft_key, ChatState::new(), cx);
+        this
+    }
+
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalTaskList>().0.clone()
+    }
+
+    pub fn tasks(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    pub fn active(&self) -> &Task {
+        self.tasks
+            .iter()
+            .find(|task| task.id == self.active_id)
+            .expect("the active task exists")
+    }
+
+    /// Add the task with the conversation and activate it, return the id.
+    pub fn add(&mut self, chat_state: ChatState, cx: &mut Context<Self>) -> usize {
+        self.insert(drafts::task_key(), chat_state, cx)
+    }
+
+    fn insert(
+        &mut self,
+        draft_key: String,
+        chat_state: ChatState,
+        cx: &mut Context<Self>,
+    ) -> usize {
+        let id = self.last_id;
+        self.last_id += 1;
+        self.tasks.push(Task {
+            id,
+            draft_key,
+            chat_state: cx.new(|_| chat_state),
+        });
+        self.activate(id, cx);
+        id
+    }
+
+    /// Activate a task to start a new conversation, the active task is reused if it is empty.
+    pub fn new_task(&mut self, cx: &mut Context<Self>) -> Entity<ChatState> {
+        let active = self.active();
+        if active.chat_state.read(cx).messages.is_empty() {
+            let id = active.id;
+            self.activate(id, cx);
+        } else {
+            self.add(ChatState::new(), cx);
+        }
+        self.active().chat_state.clone()
+    }
+
+    pub fn activate(&mut self, id: usize, cx: &mut Context<Self>) {
+        if !self.tasks.iter().any(|task| task.id == id) {
+            return;
+        }
+        self.active_id = id;
+        Drafts::set_active_task(&self.active().draft_key, cx);
+        cx.emit(TaskListEvent::Activated(id));
+        cx.notify();
+    }
+
+    /// Remove the task, the last task is activated if the active task is removed.
+    pub fn remove(&mut self, id: usize, cx: &mut Context<Self>) {
+        if let Some(task) = self.tasks.iter().find(|task| task.id == id) {
+            Drafts::update(&task.draft_key, Draft::default(), cx);
+        }
+        self.tasks.retain(|task| task.id != id);
+        if self.tasks.is_empty() {
+            self.add(ChatState::new(), cx);
+        } else if self.active_id == id {
+            let last_id = self.tasks[self.tasks.len() - 1].id;
+            self.activate(last_id, cx);
+        }
+        cx.notify();
+    }
+
+    /// Move the task at the index `from` to the index `to`, e.g.: after dragging its tab.
+    pub fn move_task(&mut self, from: usize, to: usize, cx: &mut Context<Self>) {
+        if from >= self.tasks.len() || to >= self.tasks.len() {
+            return;
+        }
+        let task = self.tasks.remove(from);
+        self.tasks.insert(to, task);
+        cx.notify();
+    }
+}
+
+struct TaskRow {
+    id: usize,
+    title: SharedString,
+    messages: usize,
+    /// The timestamp of the last message.
+    updated: u64,
+}
+
+/// The started tasks shown in the table of the home route, the most recent first.
+pub struct TaskTable {
+    rows: Vec<TaskRow>,
+    sort: Option<(usize, ColumnSort)>,
+}
+
+impl TaskTable {
+    const TITLE: usize = 0;
+    const MESSAGES: usize = 1;
+    const UPDATED: usize = 2;
+
+    pub fn new(cx: &App) -> Self {
+        let mut this = Self {
+            rows: vec![],
+            sort: None,
+        };
+        this.reload(cx);
+        this
+    }
+
+    /// Reload the rows from the [`TaskList`], the empty tasks are skipped.
+    pub fn reload(&mut self, cx: &App) {
+        self.rows = TaskList::global(cx)
+            .read(cx)
+            .tasks()
+            .iter()
+            .filter_map(|task| {
+                let messages = &task.chat_state.read(cx).messages;
+                Some(TaskRow {
+                    id: task.id,
+                    title: task.title(cx),
+                    messages: messages.len(),
+                    updated: messages.last()?.timestamp,
+                })
+            })
+            .collect();
+        self.sort_rows();
+    }
+
+    pub fn task_id(&self, row_ix: usize) -> Option<usize> {
+        self.rows.get(row_ix).map(|row| row.id)
+    }
+
+    fn sort_rows(&mut self) {
+        self.rows.sort_by_key(|row| Reverse(row.id));
+        let key = |row: &TaskRow, col_ix| match col_ix {
+            Self::MESSAGES => row.messages as u64,
+            _ => row.updated,
+        };
+        match self.sort {
+            Some((col_ix, ColumnSort::Ascending)) => self.rows.sort_by_key(|row| key(row, col_ix)),
+            Some((col_ix, ColumnSort::Descending)) => {
+                self.rows.sort_by_key(|row| Reverse(key(row, col_ix)))
+            }
+            _ => {}
+        }
+    }
+}
+
+impl TableDelegate for TaskTable {
+    fn columns(&self, _: &App) -> Vec<Column> {
+        vec![
+            Column::new("title", "Title").width(px(360.)),
+            Column::new("messages", "Messages")
+                .width(px(110.))
+                .sortable()
+                .text_right(),
+            Column::new("updated", "Updated")
+                .width(px(130.))
+                .sortable()
+                .text_right(),
+        ]
+    }
+
+    fn rows_count(&self, _: &App) -> usize {
+        self.rows.len()
+    }
+
+    fn render_cell(
+        &self,
+        row_ix: usize,
+        col_ix: usize,
+        _: &mut Window,
+        _: &mut Context<Table<Self>>,
+    ) -> impl IntoElement {
+        let row = &self.rows[row_ix];
+        match col_ix {
+            Self::TITLE => row.title.clone(),
+            Self::MESSAGES => row.messages.to_string().into(),
+            _ => format_elapsed(now().saturating_sub(row.updated)).into(),
+        }
+    }
+
+    fn perform_sort(
+        &mut self,
+        col_ix: usize,
+        sort: ColumnSort,
+        _: &mut Window,
+        _: &mut Context<Table<Self>>,
+    ) {
+        self.sort = Some((col_ix, sort));
+        self.sort_rows();
+    }
+}
+
+/// The tasks of the task switcher, the most recent first, filtered by the title.
+pub struct TaskItems {
+    /// `(id, title)`
+    tasks: Vec<(usize, SharedString)>,
+    active_id: usize,
+    /// The indices of the matched tasks.
+    matches: Vec<usize>,
+}
+
+impl TaskItems {
+    pub fn new(cx: &App) -> Self {
+        let task_list = TaskList::global(cx).read(cx);
+        let tasks = task_list
+            .tasks()
+            .iter()
+            .rev()
+            .map(|task| (task.id, task.title(cx)))
+            .collect::<Vec<_>>();
+        Self {
+            matches: (0..tasks.len()).collect(),
+            active_id: task_list.active().id,
+            tasks,
+        }
+    }
+
+    pub fn task_id(&self, ix: usize) -> Option<usize> {
+        self.matches.get(ix).map(|&task_ix| self.tasks[task_ix].0)
+    }
+}
+
+impl ListDelegate for TaskItems {
+    fn items_count(&self, _: &App) -> usize {
+        self.matches.len()
+    }
+
+    fn render_item(
+        &self,
+        ix: usize,
+        _: &mut Window,
+        cx: &mut Context<List<Self>>,
+    ) -> impl IntoElement {
+        let (id, title) = &self.tasks[self.matches[ix]];
+        h_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .child(title.clone()),
+            )
+            .when(*id == self.active_id, |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child("Active"),
+                )
+            })
+    }
+
+    fn perform_search(
+        &mut self,
+        query: &str,
+        _: &mut Window,
+        _: &mut Context<List<Self>>,
+    ) -> AsyncTask<()> {
+        let query = query.to_lowercase();
+        self.matches = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, title))| title.to_lowercase().contains(&query))
+            .map(|(ix, _)| ix)
+            .collect();
+        AsyncTask::ready(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Format the elapsed seconds, e.g.: `5 min ago`.
+fn format_elapsed(secs: u64) -> String {
+    match secs {
+        0..60 => "just now".into(),
+        60..3600 => format!("{} min ago", secs / 60),
+        3600..86400 => format!("{} h ago", secs / 3600),
+        _ => format!("{} d ago", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_elapsed;
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(5), "just now");
+        assert_eq!(format_elapsed(150), "2 min ago");
+        assert_eq!(format_elapsed(7200), "2 h ago");
+        assert_eq!(format_elapsed(3 * 86400 + 10), "3 d ago");
+    }
+}
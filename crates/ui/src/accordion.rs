@@ -0,0 +1,331 @@
+use std::{rc::Rc, time::Duration};
+
+use gpui::{
+    Animation, AnimationExt as _, AnyElement, App, AppContext as _, ClickEvent, ElementId,
+    InteractiveElement as _, IntoElement, ParentElement as _, RenderOnce, SharedString,
+    StatefulInteractiveElement as _, Styled as _, Task, Timer, Window, div, percentage,
+    prelude::FluentBuilder as _, px,
+};
+
+use crate::{
+    ActiveTheme as _, Disableable, Icon, IconName, Sizable, Size, animation::cubic_bezier, focus,
+    h_flex, v_flex,
+};
+
+type ToggleHandler = Rc<dyn Fn(&[usize], &mut Window, &mut App)>;
+
+/// The duration to animate the content when the item is opened or closed.
+const CONTENT_ANIMATION: Duration = Duration::from_millis(200);
+
+/// The state of the content of an item across the renders, to animate the close.
+struct ContentState {
+    /// Whether the content is rendered, it's still rendered while closing.
+    visible: bool,
+    /// Hide the content after the close animation.
+    hide_task: Option<Task<()>>,
+}
+
+/// An item of the [`Accordion`], the content is only rendered if it's open or closing.
+pub struct AccordionItem {
+    icon: Option<Icon>,
+    title: AnyElement,
+    content: AnyElement,
+    open: bool,
+    disabled: bool,
+}
+
+impl AccordionItem {
+    pub fn new(title: impl IntoElement) -> Self {
+        Self {
+            icon: None,
+            title: title.into_any_element(),
+            content: div().into_any_element(),
+            open: false,
+            disabled: false,
+        }
+    }
+
+    pub fn icon(mut self, icon: impl Into<Icon>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    pub fn content(mut self, content: impl IntoElement) -> Self {
+        self.content = content.into_any_element();
+        self
+    }
+
+    pub fn open(mut self, open: bool) -> Self {
+        self.open = open;
+        self
+    }
+}
+
+impl Disableable for AccordionItem {
+    fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+/// A list of collapsible items, only one item is open at once unless `multiple`.
+///
+/// The open items are kept by the parent, a click, `enter` or `space` on a title calls
+/// `on_toggle_click` with the indices of the open items.
+///
+/// ```ignore
+/// Accordion::new("tools")
+///     .multiple(true)
+///     .children(tools.iter().enumerate().map(|(ix, tool)| {
+///         AccordionItem::new(tool.name()).content(..).open(open.contains(&ix))
+///     }))
+///     .on_toggle_click(|open, _, cx| ..)
+/// ```
+#[derive(IntoElement)]
+pub struct Accordion {
+    id: ElementId,
+    items: Vec<AccordionItem>,
+    multiple: bool,
+    bordered: bool,
+    size: Size,
+    on_toggle_click: Option<ToggleHandler>,
+}
+
+impl Accordion {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            items: vec![],
+            multiple: false,
+            bordered: true,
+            size: Size::default(),
+            on_toggle_click: None,
+        }
+    }
+
+    /// Allow multiple items to be open at once.
+    pub fn multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    pub fn bordered(mut self, bordered: bool) -> Self {
+        self.bordered = bordered;
+        self
+    }
+
+    pub fn child(mut self, item: AccordionItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    pub fn children(mut self, items: impl IntoIterator<Item = AccordionItem>) -> Self {
+        self.items.extend(items);
+        self
+    }
+
+    /// Set the handler of the toggle, the argument is the indices of the open items.
+    pub fn on_toggle_click(
+        mut self,
+        handler: impl Fn(&[usize], &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_toggle_click = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl Sizable for Accordion {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+/// Return the open items after toggling the item `ix`.
+fn toggle(open: &[usize], ix: usize, multiple: bool) -> Vec<usize> {
+    let was_open = open.contains(&ix);
+    match (multiple, was_open) {
+        (true, true) => open.iter().copied().filter(|&i| i != ix).collect(),
+        (true, false) => open.iter().copied().chain([ix]).collect(),
+        (false, true) => vec![],
+        (false, false) => vec![ix],
+    }
+}
+
+impl RenderOnce for Accordion {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let open = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.open)
+            .map(|(ix, _)| ix)
+            .collect::<Vec<_>>();
+        let multiple = self.multiple;
+        let bordered = self.bordered;
+        let size = self.size;
+        let on_toggle_click = self.on_toggle_click;
+        let count = self.items.len();
+
+        let items = self
+            .items
+            .into_iter()
+            .enumerate()
+            .map(|(ix, item)| {
+                let header_id = SharedString::from(format!("{}-{}", self.id, ix));
+                let focus_handle = focus::register_control(
+                    cx,
+                    header_id.into(),
+                    item.disabled,
+                    None::<fn(&ClickEvent, &mut Window, &mut App)>,
+                );
+                let focused = focus_handle.is_focused(window);
+                let on_toggle: Option<Rc<dyn Fn(&mut Window, &mut App)>> = on_toggle_click
+                    .clone()
+                    .filter(|_| !item.disabled)
+                    .map(|on_toggle_click| {
+                        let open = toggle(&open, ix, multiple);
+                        Rc::new(move |window: &mut Window, cx: &mut App| {
+                            on_toggle_click(&open, window, cx)
+                        }) as _
+                    });
+                let item_open = item.open;
+                let chevron_id = ElementId::NamedInteger("chevron".into(), item_open as u64);
+                let content_state = window.use_keyed_state(
+                    ElementId::NamedInteger(format!("{}-content", self.id).into(), ix as u64),
+                    cx,
+                    |_, _| ContentState {
+                        visible: item_open,
+                        hide_task: None,
+                    },
+                );
+                let closing = !item_open && content_state.read(cx).visible;
+                if item_open {
+                    content_state.update(cx, |state, _| {
+                        state.visible = true;
+                        state.hide_task = None;
+                    });
+                } else if closing && content_state.read(cx).hide_task.is_none() {
+                    let task = cx.spawn({
+                        let content_state = content_state.clone();
+                        async move |cx| {
+                            Timer::after(CONTENT_ANIMATION).await;
+                            _ = content_state.update(cx, |state, cx| {
+                                state.visible = false;
+                                cx.notify();
+                            });
+                        }
+                    });
+                    content_state.update(cx, |state, _| state.hide_task = Some(task));
+                }
+
+                v_flex()
+                    .when(bordered && ix + 1 < count, |this| {
+                        this.border_b_1().border_color(cx.theme().border)
+                    })
+                    .child(
+                        h_flex()
+                            .id(("header", ix))
+                            .track_focus(&focus_handle)
+                            .gap_2()
+                            .map(|this| match size {
+                                Size::XSmall | Size::Small => this.px_2().py_1().text_sm(),
+                                _ => this.px_3().py_2(),
+                            })
+                            .bg(cx.theme().accordion)
+                            .when(item_open, |this| this.bg(cx.theme().accordion_active))
+                            .border_1()
+                            .border_color(cx.theme().transparent)
+                            .when(focused, |this| this.border_color(cx.theme().ring))
+                            .when(item.disabled, |this| {
+                                this.text_color(cx.theme().muted_foreground)
+                            })
+                            .when(!item.disabled, |this| {
+                                this.cursor_pointer()
+                                    .hover(|this| this.bg(cx.theme().accordion_hover))
+                            })
+                            .child(
+                                Icon::new(IconName::ChevronRight)
+                                    .size_4()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .with_animation(
+                                        chevron_id,
+                                        Animation::new(Duration::from_secs_f64(0.15))
+                                            .with_easing(cubic_bezier(0.4, 0., 0.2, 1.)),
+                                        move |this, delta| {
+                                            let turn = if item_open { delta } else { 1. - delta };
+                                            this.rotate(percentage(turn * 0.25))
+                                        },
+                                    ),
+                            )
+                            .when_some(item.icon, |this, icon| {
+                                this.child(icon.size_4().text_color(cx.theme().muted_foreground))
+                            })
+                            .child(div().flex_1().overflow_hidden().child(item.title))
+                            .when_some(on_toggle, |this, on_toggle| {
+                                this.on_click({
+                                    let on_toggle = on_toggle.clone();
+                                    move |_, window, cx| on_toggle(window, cx)
+                                })
+                                .on_key_down(
+                                    move |event, window, cx| {
+                                        if matches!(event.keystroke.key.as_str(), "enter" | "space")
+                                        {
+                                            cx.stop_propagation();
+                                            on_toggle(window, cx);
+                                        }
+                                    },
+                                )
+                            }),
+                    )
+                    .when(item_open || closing, |this| {
+                        this.child(
+                            div()
+                                .id(("content", ix))
+                                .relative()
+                                .map(|this| match size {
+                                    Size::XSmall | Size::Small => this.p_2(),
+                                    _ => this.p_3(),
+                                })
+                                .border_t_1()
+                                .border_color(cx.theme().border)
+                                .child(item.content)
+                                .with_animation(
+                                    (if item_open { "open" } else { "close" }, ix),
+                                    Animation::new(CONTENT_ANIMATION)
+                                        .with_easing(cubic_bezier(0.4, 0., 0.2, 1.)),
+                                    move |this, delta| {
+                                        let delta = if item_open { delta } else { 1. - delta };
+                                        this.opacity(delta).top(px(-6.) + delta * px(6.))
+                                    },
+                                ),
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id(self.id)
+            .overflow_hidden()
+            .when(bordered, |this| {
+                this.border_1()
+                    .border_color(cx.theme().border)
+                    .rounded(cx.theme().radius)
+            })
+            .children(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::toggle;
+
+    #[test]
+    fn test_toggle() {
+        assert_eq!(toggle(&[], 1, false), vec![1]);
+        assert_eq!(toggle(&[0], 1, false), vec![1]);
+        assert_eq!(toggle(&[1], 1, false), Vec::<usize>::new());
+        assert_eq!(toggle(&[0], 1, true), vec![0, 1]);
+        assert_eq!(toggle(&[0, 1], 0, true), vec![1]);
+    }
+}
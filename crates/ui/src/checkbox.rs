@@ -0,0 +1,134 @@
+use std::rc::Rc;
+
+use gpui::{
+    App, ClickEvent, ElementId, InteractiveElement as _, IntoElement, ParentElement as _,
+    RenderOnce, SharedString, StatefulInteractiveElement as _, Styled as _, Window, div,
+    prelude::FluentBuilder as _, px,
+};
+
+use crate::{ActiveTheme as _, Disableable, Icon, IconName, Sizable, Size, focus, h_flex};
+
+type ToggleHandler = Rc<dyn Fn(&bool, &mut Window, &mut App)>;
+
+/// A checkbox, registered in the focus registry by its id, `enter` and `space` toggle it.
+///
+/// ```ignore
+/// Checkbox::new("auto-approve")
+///     .label("Approve the edits automatically")
+///     .checked(auto_approve)
+///     .on_click(|checked, _, cx| ..)
+/// ```
+#[derive(IntoElement)]
+pub struct Checkbox {
+    id: ElementId,
+    checked: bool,
+    label: Option<SharedString>,
+    disabled: bool,
+    size: Size,
+    on_click: Option<ToggleHandler>,
+}
+
+impl Checkbox {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            checked: false,
+            label: None,
+            disabled: false,
+            size: Size::default(),
+            on_click: None,
+        }
+    }
+
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    pub fn label(mut self, label: impl Into<SharedString>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set the handler of the toggle, the argument is the new checked state.
+    pub fn on_click(mut self, handler: impl Fn(&bool, &mut Window, &mut App) + 'static) -> Self {
+        self.on_click = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl Disableable for Checkbox {
+    fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl Sizable for Checkbox {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+impl RenderOnce for Checkbox {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let checked = self.checked;
+        let toggle: Option<Rc<dyn Fn(&mut Window, &mut App)>> =
+            self.on_click.filter(|_| !self.disabled).map(|on_click| {
+                Rc::new(move |window: &mut Window, cx: &mut App| on_click(&!checked, window, cx))
+                    as _
+            });
+        let focus_handle = focus::register_control(
+            cx,
+            self.id.clone(),
+            self.disabled,
+            toggle.clone().map(|toggle| {
+                move |_: &ClickEvent, window: &mut Window, cx: &mut App| toggle(window, cx)
+            }),
+        );
+        let focused = focus_handle.is_focused(window);
+
+        let box_size = match self.size {
+            Size::XSmall | Size::Small => px(14.),
+            _ => px(16.),
+        };
+
+        h_flex()
+            .id(self.id)
+            .track_focus(&focus_handle)
+            .gap_2()
+            .when(self.disabled, |this| this.opacity(0.5))
+            .when(!self.disabled, |this| this.cursor_pointer())
+            .child(
+                h_flex()
+                    .flex_shrink_0()
+                    .justify_center()
+                    .size(box_size)
+                    .rounded(cx.theme().radius * 0.5)
+                    .border_1()
+                    .border_color(cx.theme().primary)
+                    .text_color(cx.theme().primary_foreground)
+                    .when(checked, |this| {
+                        this.bg(cx.theme().primary)
+                            .child(Icon::new(IconName::Check).size(box_size - px(4.)))
+                    })
+                    .when(focused, |this| this.border_color(cx.theme().ring)),
+            )
+            .when_some(self.label, |this, label| {
+                this.child(div().text_sm().child(label))
+            })
+            .when_some(toggle, |this, toggle| {
+                this.on_click({
+                    let toggle = toggle.clone();
+                    move |_, window, cx| toggle(window, cx)
+                })
+                .on_key_down(move |event, window, cx| {
+                    if event.keystroke.key == "space" {
+                        cx.stop_propagation();
+                        toggle(window, cx);
+                    }
+                })
+            })
+    }
+}
@@ -0,0 +1,158 @@
+use std::{cell::RefCell, rc::Rc};
+
+use gpui::{
+    AnyElement, App, Context, DismissEvent, Div, Element, ElementId, Entity, Focusable as _,
+    GlobalElementId, InteractiveElement, IntoElement, LayoutId, MouseButton, ParentElement, Pixels,
+    Point, Stateful, Styled as _, Subscription, Window, anchored, deferred, div,
+};
+
+use crate::popup_menu::PopupMenu;
+
+/// Extend the elements to show a [`PopupMenu`] on right click.
+pub trait ContextMenuExt: ParentElement + InteractiveElement + Element + Sized {
+    /// Show the popup menu built by `f` at the mouse position on right click.
+    fn context_menu(
+        self,
+        f: impl Fn(PopupMenu, &mut Window, &mut Context<PopupMenu>) -> PopupMenu + 'static,
+    ) -> ContextMenu<Self> {
+        ContextMenu::new(self, f)
+    }
+}
+
+impl ContextMenuExt for Stateful<Div> {}
+
+/// An element with a [`PopupMenu`] shown on right click, see [`ContextMenuExt::context_menu`].
+pub struct ContextMenu<E> {
+    id: ElementId,
+    element: Option<E>,
+    menu: Rc<dyn Fn(PopupMenu, &mut Window, &mut Context<PopupMenu>) -> PopupMenu>,
+}
+
+struct OpenMenu {
+    menu: Entity<PopupMenu>,
+    position: Point<Pixels>,
+    _subscription: Subscription,
+}
+
+#[derive(Default)]
+struct ContextMenuState {
+    open_menu: Rc<RefCell<Option<OpenMenu>>>,
+}
+
+impl<E: ParentElement + InteractiveElement + Element> ContextMenu<E> {
+    fn new(
+        element: E,
+        f: impl Fn(PopupMenu, &mut Window, &mut Context<PopupMenu>) -> PopupMenu + 'static,
+    ) -> Self {
+        Self {
+            id: element.id().unwrap_or_else(|| "context-menu".into()),
+            element: Some(element),
+            menu: Rc::new(f),
+        }
+    }
+}
+
+impl<E: ParentElement + InteractiveElement + Element> IntoElement for ContextMenu<E> {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl<E: ParentElement + InteractiveElement + Element> Element for ContextMenu<E> {
+    type RequestLayoutState = AnyElement;
+    type PrepaintState = ();
+
+    fn id(&self) -> Option<ElementId> {
+        Some(self.id.clone())
+    }
+
+    fn source_location(&self) -> Option<&'static std::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        id: Option<&GlobalElementId>,
+        _: Option<&gpui::InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let open_menu =
+            window.with_optional_element_state::<ContextMenuState, _>(id, |state, _| {
+                let state = state.unwrap().unwrap_or_default();
+                (state.open_menu.clone(), Some(state))
+            });
+
+        let build = self.menu.clone();
+        let mut element = self
+            .element
+            .take()
+            .expect("the context menu element is laid out once")
+            .on_mouse_down(MouseButton::Right, {
+                let open_menu = open_menu.clone();
+                move |event, window, cx| {
+                    cx.stop_propagation();
+
+                    let menu =
+                        PopupMenu::build(window, cx, |menu, window, cx| build(menu, window, cx));
+                    let _subscription = cx.subscribe(&menu, {
+                        let open_menu = open_menu.clone();
+                        move |_, _: &DismissEvent, cx| {
+                            let closed = open_menu.borrow_mut().take();
+                            drop(closed);
+                            cx.refresh_windows();
+                        }
+                    });
+                    menu.focus_handle(cx).focus(window);
+                    *open_menu.borrow_mut() = Some(OpenMenu {
+                        menu,
+                        position: event.position,
+                        _subscription,
+                    });
+                    window.refresh();
+                }
+            });
+
+        if let Some(open_menu) = open_menu.borrow().as_ref() {
+            element = element.child(
+                div().absolute().child(deferred(
+                    anchored()
+                        .position(open_menu.position)
+                        .snap_to_window()
+                        .child(open_menu.menu.clone()),
+                )),
+            );
+        }
+
+        let mut element = element.into_any_element();
+        let layout_id = element.request_layout(window, cx);
+        (layout_id, element)
+    }
+
+    fn prepaint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Option<&gpui::InspectorElementId>,
+        _: gpui::Bounds<Pixels>,
+        element: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self::PrepaintState {
+        element.prepaint(window, cx);
+    }
+
+    fn paint(
+        &mut self,
+        _: Option<&GlobalElementId>,
+        _: Option<&gpui::InspectorElementId>,
+        _: gpui::Bounds<Pixels>,
+        element: &mut Self::RequestLayoutState,
+        _: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        element.paint(window, cx);
+    }
+}
@@ -0,0 +1,284 @@
+use gpui::{
+    AnyElement, App, ClipboardItem, ElementId, InteractiveElement as _, IntoElement,
+    ParentElement as _, Pixels, RenderOnce, SharedString, Styled as _, Window, div,
+    prelude::FluentBuilder as _, px, relative,
+};
+
+use crate::{
+    ActiveTheme as _, Button, ButtonVariants as _, ContextModal as _, IconName, Sizable, Size,
+    h_flex, notification::Notification, v_flex,
+};
+
+/// An item of the [`DescriptionList`], a label and its value.
+pub struct DescriptionItem {
+    label: SharedString,
+    value: AnyElement,
+    span: usize,
+    copy_text: Option<SharedString>,
+}
+
+impl DescriptionItem {
+    pub fn new(label: impl Into<SharedString>) -> Self {
+        Self {
+            label: label.into(),
+            value: div().into_any_element(),
+            span: 1,
+            copy_text: None,
+        }
+    }
+
+    pub fn value(mut self, value: impl IntoElement) -> Self {
+        self.value = value.into_any_element();
+        self
+    }
+
+    /// Set the number of columns taken by the item, `1` by default.
+    pub fn span(mut self, span: usize) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Show a button to copy the text to the clipboard when the item is hovered.
+    pub fn copyable(mut self, text: impl Into<SharedString>) -> Self {
+        self.copy_text = Some(text.into());
+        self
+    }
+}
+
+/// A list of labels and values in columns, e.g.: the metadata of a task.
+///
+/// The label is beside the value by default, or above it if `vertical`.
+///
+/// ```ignore
+/// DescriptionList::new("task")
+///     .columns(2)
+///     .child(DescriptionItem::new("Model").value(model))
+///     .child(DescriptionItem::new("Working directory").value(dir.clone()).copyable(dir))
+///     .child(DescriptionItem::new("Prompt").value(prompt).span(2))
+/// ```
+#[derive(IntoElement)]
+pub struct DescriptionList {
+    id: ElementId,
+    items: Vec<DescriptionItem>,
+    columns: usize,
+    vertical: bool,
+    bordered: bool,
+    label_width: Pixels,
+    size: Size,
+}
+
+impl DescriptionList {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            items: vec![],
+            columns: 3,
+            vertical: false,
+            bordered: true,
+            label_width: px(120.),
+            size: Size::default(),
+        }
+    }
+
+    /// Set the number of columns, `3` by default.
+    pub fn columns(mut self, columns: usize) -> Self {
+        self.columns = columns.max(1);
+        self
+    }
+
+    /// Put the labels above the values.
+    pub fn vertical(mut self) -> Self {
+        self.vertical = true;
+        self
+    }
+
+    pub fn bordered(mut self, bordered: bool) -> Self {
+        self.bordered = bordered;
+        self
+    }
+
+    /// Set the width of the labels beside the values, unused if `vertical`.
+    pub fn label_width(mut self, width: impl Into<Pixels>) -> Self {
+        self.label_width = width.into();
+        self
+    }
+
+    pub fn child(mut self, item: DescriptionItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    pub fn children(mut self, items: impl IntoIterator<Item = DescriptionItem>) -> Self {
+        self.items.extend(items);
+        self
+    }
+}
+
+impl Sizable for DescriptionList {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+/// Split the items in rows of `columns`, return the index and the span of the items.
+///
+/// An item that doesn't fit starts the next row, and the last item of a row takes the rest of it.
+fn rows(spans: &[usize], columns: usize) -> Vec<Vec<(usize, usize)>> {
+    let mut rows: Vec<Vec<(usize, usize)>> = vec![];
+    let mut row: Vec<(usize, usize)> = vec![];
+    let mut used = 0;
+    for (ix, &span) in spans.iter().enumerate() {
+        let span = span.clamp(1, columns);
+        if used + span > columns {
+            if let Some((_, last_span)) = row.last_mut() {
+                *last_span += columns - used;
+            }
+            rows.push(std::mem::take(&mut row));
+            used = 0;
+        }
+        row.push((ix, span));
+        used += span;
+    }
+    if let Some((_, last_span)) = row.last_mut() {
+        *last_span += columns - used;
+        rows.push(row);
+    }
+    rows
+}
+
+impl RenderOnce for DescriptionList {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let columns = self.columns;
+        let vertical = self.vertical;
+        let bordered = self.bordered;
+        let label_width = self.label_width;
+        let size = self.size;
+        let spans = self.items.iter().map(|item| item.span).collect::<Vec<_>>();
+        let rows = rows(&spans, columns);
+        let row_count = rows.len();
+        let mut items = self.items.into_iter();
+        // The ids of the buttons are global in the focus registry, so scope them by the list.
+        let copy_prefix = SharedString::from(format!("{}-copy", self.id));
+        let group_prefix = format!("{}-item", self.id);
+
+        let cell = move || {
+            div().map(|this| match size {
+                Size::XSmall | Size::Small => this.px_2().py_1().text_xs(),
+                _ => this.px_3().py_2().text_sm(),
+            })
+        };
+
+        v_flex()
+            .id(self.id)
+            .w_full()
+            .overflow_hidden()
+            .when(bordered, |this| {
+                this.border_1()
+                    .border_color(cx.theme().border)
+                    .rounded(cx.theme().radius)
+            })
+            .when(!bordered, |this| this.gap_2())
+            .children(rows.into_iter().enumerate().map(|(row_ix, row)| {
+                let count = row.len();
+                h_flex()
+                    .w_full()
+                    .items_stretch()
+                    .when(bordered && row_ix + 1 < row_count, |this| {
+                        this.border_b_1().border_color(cx.theme().border)
+                    })
+                    .children(row.into_iter().zip(items.by_ref()).enumerate().map(
+                        |(col_ix, ((ix, span), item))| {
+                            let group = SharedString::from(format!("{}-{}", group_prefix, ix));
+
+                            let label = cell()
+                                .flex_shrink_0()
+                                .text_color(cx.theme().muted_foreground)
+                                .when(bordered, |this| {
+                                    this.bg(cx.theme().description_list_label)
+                                        .text_color(cx.theme().description_list_label_foreground)
+                                })
+                                .map(|this| {
+                                    if vertical {
+                                        this.w_full().when(bordered, |this| {
+                                            this.border_b_1().border_color(cx.theme().border)
+                                        })
+                                    } else {
+                                        this.w(label_width).when(bordered, |this| {
+                                            this.border_r_1().border_color(cx.theme().border)
+                                        })
+                                    }
+                                })
+                                .child(item.label);
+
+                            let value = cell()
+                                .flex()
+                                .flex_1()
+                                .items_start()
+                                .gap_1()
+                                .min_w_0()
+                                .child(div().flex_1().min_w_0().overflow_hidden().child(item.value))
+                                .when_some(item.copy_text, |this, text| {
+                                    let copy_id =
+                                        ElementId::NamedInteger(copy_prefix.clone(), ix as u64);
+                                    this.child(
+                                        div()
+                                            .invisible()
+                                            .group_hover(group.clone(), |this| this.visible())
+                                            .child(
+                                                Button::new(copy_id)
+                                                    .ghost()
+                                                    .xsmall()
+                                                    .icon(IconName::Copy)
+                                                    .tooltip("Copy")
+                                                    .on_click(cx, move |_, window, cx| {
+                                                        cx.write_to_clipboard(
+                                                            ClipboardItem::new_string(
+                                                                text.to_string(),
+                                                            ),
+                                                        );
+                                                        window.push_notification(
+                                                            Notification::info(
+                                                                "Copied to Clipboard.",
+                                                            ),
+                                                            cx,
+                                                        );
+                                                    }),
+                                            ),
+                                    )
+                                });
+
+                            div()
+                                .group(group)
+                                .flex()
+                                .when(vertical, |this| this.flex_col())
+                                .w(relative(span as f32 / columns as f32))
+                                .when(bordered && col_ix + 1 < count, |this| {
+                                    this.border_r_1().border_color(cx.theme().border)
+                                })
+                                .child(label)
+                                .child(value)
+                        },
+                    ))
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rows;
+
+    #[test]
+    fn test_rows() {
+        assert_eq!(
+            rows(&[1, 1, 1, 1], 3),
+            vec![vec![(0, 1), (1, 1), (2, 1)], vec![(3, 3)]]
+        );
+        assert_eq!(
+            rows(&[1, 3, 1, 1], 3),
+            vec![vec![(0, 3)], vec![(1, 3)], vec![(2, 1), (3, 2)]]
+        );
+        assert_eq!(rows(&[5, 0], 2), vec![vec![(0, 2)], vec![(1, 2)]]);
+        assert_eq!(rows(&[], 2), Vec::<Vec<(usize, usize)>>::new());
+    }
+}
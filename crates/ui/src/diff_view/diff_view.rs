@@ -0,0 +1,737 @@
+use std::{cell::Cell, ops::Range, rc::Rc};
+
+use gpui::{
+    AnyElement, App, Context, FocusHandle, Focusable, Hsla, InteractiveElement as _, IntoElement,
+    KeyBinding, ParentElement as _, Pixels, Render, ScrollStrategy, SharedString, Styled as _,
+    StyledText, UniformListScrollHandle, Window, actions, div, prelude::FluentBuilder as _, px,
+    rems, uniform_list,
+};
+
+use crate::{
+    ActiveTheme, Disableable as _, IconName, Sizable as _,
+    button::{Button, ButtonGroup, ButtonVariants as _},
+    h_flex,
+    highlighter::{HighlightTheme, LanguageRegistry, SyntaxHighlighter},
+    scroll::{Scrollbar, ScrollbarState},
+    v_flex,
+};
+
+use super::{DiffHunk, DiffLine, DiffLineKind, TextDiff};
+
+const CONTEXT: &str = "DiffView";
+const ROW_HEIGHT: Pixels = px(24.);
+
+actions!(diff_view, [NextHunk, PreviousHunk]);
+
+pub(super) fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("f7", NextHunk, Some(CONTEXT)),
+        KeyBinding::new("shift-f7", PreviousHunk, Some(CONTEXT)),
+    ]);
+}
+
+/// The layout of the diff.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DiffMode {
+    /// The removed and added lines in one column.
+    #[default]
+    Unified,
+    /// The old text on the left and the new text on the right.
+    Split,
+}
+
+/// The decision made on a hunk by the accept or reject button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkStatus {
+    Accepted,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffRow {
+    /// The header of the hunk (index).
+    Header(usize),
+    /// A line in the unified mode.
+    Line(DiffLine),
+    /// The old and new lines in the split mode.
+    Pair(Option<DiffLine>, Option<DiffLine>),
+}
+
+type HunkHandler = Rc<dyn Fn(usize, &DiffHunk, &mut Window, &mut App)>;
+
+/// A view to show the line diff between two texts, with syntax highlighting.
+///
+/// ```ignore
+/// let diff_view = cx.new(|cx| {
+///     DiffView::new(old_text, new_text, window, cx)
+///         .language("rust")
+///         .mode(DiffMode::Split)
+///         .on_accept(|hunk_ix, hunk, _, _| println!("accept {} {}", hunk_ix, hunk.header()))
+/// });
+/// ```
+pub struct DiffView {
+    focus_handle: FocusHandle,
+    diff: TextDiff,
+    mode: DiffMode,
+    language: Option<SharedString>,
+    old_highlighter: Option<SyntaxHighlighter>,
+    new_highlighter: Option<SyntaxHighlighter>,
+    /// Whether the highlighters need to be rebuilt on the next render, as the language is
+    /// resolved with the [`LanguageRegistry`].
+    highlighters_outdated: bool,
+    rows: Vec<DiffRow>,
+    /// The row index of the header of each hunk.
+    hunk_rows: Vec<usize>,
+    selected_hunk: Option<usize>,
+    statuses: Vec<Option<HunkStatus>>,
+    scroll_handle: UniformListScrollHandle,
+    scrollbar_state: Rc<Cell<ScrollbarState>>,
+    on_accept: Option<HunkHandler>,
+    on_reject: Option<HunkHandler>,
+}
+
+impl DiffView {
+    pub fn new(
+        old_text: impl Into<SharedString>,
+        new_text: impl Into<SharedString>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let diff = TextDiff::new(old_text, new_text);
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            statuses: vec![None; diff.hunks().len()],
+            diff,
+            mode: DiffMode::default(),
+            language: None,
+            old_highlighter: None,
+            new_highlighter: None,
+            highlighters_outdated: false,
+            rows: vec![],
+            hunk_rows: vec![],
+            selected_hunk: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            scrollbar_state: Rc::new(Cell::new(ScrollbarState::default())),
+            on_accept: None,
+            on_reject: None,
+        };
+        this.rebuild_rows();
+        this
+    }
+
+    /// Set the language to highlight the texts, e.g.: `rust`, `json`.
+    ///
+    /// The texts are not highlighted if the language is not supported.
+    pub fn language(mut self, language: impl Into<SharedString>) -> Self {
+        self.language = Some(language.into());
+        self.highlighters_outdated = true;
+        self
+    }
+
+    /// Set the layout of the diff, default is [`DiffMode::Unified`].
+    pub fn mode(mut self, mode: DiffMode) -> Self {
+        self.mode = mode;
+        self.rebuild_rows();
+        self
+    }
+
+    /// Set the callback when a hunk is accepted, the arguments are the hunk index and the hunk.
+    pub fn on_accept(
+        mut self,
+        handler: impl Fn(usize, &DiffHunk, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_accept = Some(Rc::new(handler));
+        self
+    }
+
+    /// Set the callback when a hunk is rejected, the arguments are the hunk index and the hunk.
+    pub fn on_reject(
+        mut self,
+        handler: impl Fn(usize, &DiffHunk, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_reject = Some(Rc::new(handler));
+        self
+    }
+
+    pub fn set_mode(&mut self, mode: DiffMode, cx: &mut Context<Self>) {
+        if self.mode == mode {
+            return;
+        }
+
+        self.mode = mode;
+        self.rebuild_rows();
+        if let Some(hunk_ix) = self.selected_hunk {
+            self.scroll_to_hunk(hunk_ix);
+        }
+        cx.notify();
+    }
+
+    /// Replace the texts to compare, the statuses of the hunks are reset.
+    pub fn set_texts(
+        &mut self,
+        old_text: impl Into<SharedString>,
+        new_text: impl Into<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        self.diff = TextDiff::new(old_text, new_text);
+        self.statuses = vec![None; self.diff.hunks().len()];
+        self.selected_hunk = None;
+        self.highlighters_outdated = true;
+        self.rebuild_rows();
+        cx.notify();
+    }
+
+    pub fn diff(&self) -> &TextDiff {
+        &self.diff
+    }
+
+    pub fn hunk_status(&self, hunk_ix: usize) -> Option<HunkStatus> {
+        self.statuses.get(hunk_ix).copied().flatten()
+    }
+
+    /// Return the old text with only the accepted hunks applied.
+    pub fn resolved_text(&self) -> String {
+        self.diff
+            .apply(|hunk_ix| self.hunk_status(hunk_ix) == Some(HunkStatus::Accepted))
+    }
+
+    pub fn accept_hunk(&mut self, hunk_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_hunk_status(hunk_ix, HunkStatus::Accepted, window, cx);
+    }
+
+    pub fn reject_hunk(&mut self, hunk_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_hunk_status(hunk_ix, HunkStatus::Rejected, window, cx);
+    }
+
+    fn set_hunk_status(
+        &mut self,
+        hunk_ix: usize,
+        status: HunkStatus,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(hunk) = self.diff.hunks().get(hunk_ix) else {
+            return;
+        };
+
+        self.statuses[hunk_ix] = Some(status);
+        let handler = match status {
+            HunkStatus::Accepted => self.on_accept.clone(),
+            HunkStatus::Rejected => self.on_reject.clone(),
+        };
+        if let Some(handler) = handler {
+            handler(hunk_ix, hunk, window, cx);
+        }
+        cx.notify();
+    }
+
+    fn next_hunk(&mut self, _: &NextHunk, _: &mut Window, cx: &mut Context<Self>) {
+        let count = self.diff.hunks().len();
+        if count == 0 {
+            return;
+        }
+
+        let hunk_ix = match self.selected_hunk {
+            Some(ix) => (ix + 1).min(count - 1),
+            None => 0,
+        };
+        self.select_hunk(hunk_ix, cx);
+    }
+
+    fn previous_hunk(&mut self, _: &PreviousHunk, _: &mut Window, cx: &mut Context<Self>) {
+        let count = self.diff.hunks().len();
+        if count == 0 {
+            return;
+        }
+
+        let hunk_ix = match self.selected_hunk {
+            Some(ix) => ix.saturating_sub(1),
+            None => count - 1,
+        };
+        self.select_hunk(hunk_ix, cx);
+    }
+
+    fn select_hunk(&mut self, hunk_ix: usize, cx: &mut Context<Self>) {
+        self.selected_hunk = Some(hunk_ix);
+        self.scroll_to_hunk(hunk_ix);
+        cx.notify();
+    }
+
+    fn scroll_to_hunk(&self, hunk_ix: usize) {
+        if let Some(row_ix) = self.hunk_rows.get(hunk_ix) {
+            self.scroll_handle
+                .scroll_to_item(*row_ix, ScrollStrategy::Top);
+        }
+    }
+
+    /// Build the highlighters like the editor, including the languages registered in the
+    /// [`LanguageRegistry`].
+    fn rebuild_highlighters(&mut self, cx: &App) {
+        let build = |language: &Option<SharedString>| {
+            language
+                .as_ref()
+                .and_then(|language| SyntaxHighlighter::for_language(language, cx))
+        };
+
+        self.old_highlighter = build(&self.language);
+        self.new_highlighter = build(&self.language);
+        self.highlighters_outdated = false;
+    }
+
+    fn rebuild_rows(&mut self) {
+        self.rows.clear();
+        self.hunk_rows.clear();
+        for (hunk_ix, hunk) in self.diff.hunks().iter().enumerate() {
+            self.hunk_rows.push(self.rows.len());
+            self.rows.push(DiffRow::Header(hunk_ix));
+            match self.mode {
+                DiffMode::Unified => self
+                    .rows
+                    .extend(hunk.lines.iter().copied().map(DiffRow::Line)),
+                DiffMode::Split => self.rows.extend(split_rows(&hunk.lines)),
+            }
+        }
+    }
+
+    /// Return the highlighted text of the line, the removed line is highlighted by the old text.
+    fn line_text(&self, line: &DiffLine, theme: &HighlightTheme, window: &Window) -> StyledText {
+        let (highlighter, line_range) = if line.kind == DiffLineKind::Removed {
+            (
+                self.old_highlighter.as_ref(),
+                line.old_ix.and_then(|ix| self.diff.old_line_range(ix)),
+            )
+        } else {
+            (
+                self.new_highlighter.as_ref(),
+                line.new_ix.and_then(|ix| self.diff.new_line_range(ix)),
+            )
+        };
+
+        let mut highlights = vec![];
+        if let (Some(highlighter), Some(line_range)) = (highlighter, line_range) {
+            let styles = highlighter.styles(&line_range, theme);
+            for (range, style) in gpui::combine_highlights(vec![], styles) {
+                // Convert to the offsets in the line.
+                let range: Range<usize> = range.start.saturating_sub(line_range.start)
+                    ..range
+                        .end
+                        .min(line_range.end)
+                        .saturating_sub(line_range.start);
+                if !range.is_empty() {
+                    highlights.push((range, style));
+                }
+            }
+        }
+
+        let text = SharedString::from(self.diff.line_text(line).to_string());
+        StyledText::new(text).with_default_highlights(&window.text_style(), highlights)
+    }
+
+    fn line_colors(
+        &self,
+        kind: DiffLineKind,
+        theme: &HighlightTheme,
+        cx: &App,
+    ) -> (&'static str, Hsla, Option<Hsla>) {
+        match kind {
+            DiffLineKind::Unchanged => (" ", cx.theme().muted_foreground, None),
+            DiffLineKind::Added => (
+                "+",
+                cx.theme().success,
+                Some(theme.style.status.created_background()),
+            ),
+            DiffLineKind::Removed => (
+                "-",
+                cx.theme().danger,
+                Some(theme.style.status.deleted_background()),
+            ),
+        }
+    }
+
+    fn render_line_number(
+        &self,
+        ix: Option<usize>,
+        theme: &HighlightTheme,
+        cx: &App,
+    ) -> impl IntoElement {
+        // The last hunk has the largest line numbers.
+        let digits = self
+            .diff
+            .hunks()
+            .last()
+            .map_or(1, |hunk| hunk.old_range.end.max(hunk.new_range.end))
+            .to_string()
+            .len();
+
+        div()
+            .flex_none()
+            .w(rems(digits as f32 * 0.6 + 1.))
+            .pr_2()
+            .text_right()
+            .text_xs()
+            .text_color(
+                theme
+                    .style
+                    .line_number
+                    .unwrap_or(cx.theme().muted_foreground),
+            )
+            .when_some(ix, |this, ix| this.child((ix + 1).to_string()))
+    }
+
+    fn render_line(
+        &self,
+        line: &DiffLine,
+        numbers: impl IntoIterator<Item = Option<usize>>,
+        theme: &HighlightTheme,
+        window: &Window,
+        cx: &App,
+    ) -> gpui::Div {
+        let (sign, sign_color, background) = self.line_colors(line.kind, theme, cx);
+
+        h_flex()
+            .h(ROW_HEIGHT)
+            .overflow_hidden()
+            .when_some(background, |this, background| this.bg(background))
+            .children(
+                numbers
+                    .into_iter()
+                    .map(|ix| self.render_line_number(ix, theme, cx)),
+            )
+            .child(
+                div()
+                    .flex_none()
+                    .w(rems(1.))
+                    .text_center()
+                    .text_color(sign_color)
+                    .child(sign),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .child(self.line_text(line, theme, window)),
+            )
+    }
+
+    fn render_hunk_header(&self, hunk_ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let hunk = &self.diff.hunks()[hunk_ix];
+        let selected = self.selected_hunk == Some(hunk_ix);
+        let (added, removed) = hunk.stats();
+
+        let header = h_flex()
+            .w_full()
+            .h(ROW_HEIGHT)
+            .px_2()
+            .gap_2()
+            .text_xs()
+            .bg(if selected {
+                cx.theme().list_active
+            } else {
+                cx.theme().muted
+            })
+            .text_color(cx.theme().muted_foreground)
+            .child(hunk.header())
+            .child(
+                div()
+                    .text_color(cx.theme().success)
+                    .child(format!("+{}", added)),
+            )
+            .child(
+                div()
+                    .text_color(cx.theme().danger)
+                    .child(format!("-{}", removed)),
+            )
+            .child(div().flex_1());
+
+        match self.hunk_status(hunk_ix) {
+            Some(HunkStatus::Accepted) => header.child("Accepted").into_any_element(),
+            Some(HunkStatus::Rejected) => header.child("Rejected").into_any_element(),
+            None => {
+                let on_accept = cx.listener(move |this, _, window, cx| {
+                    this.accept_hunk(hunk_ix, window, cx);
+                });
+                let on_reject = cx.listener(move |this, _, window, cx| {
+                    this.reject_hunk(hunk_ix, window, cx);
+                });
+
+                header
+                    .child(
+                        Button::new(("accept-hunk", hunk_ix))
+                            .icon(IconName::Check)
+                            .label("Accept")
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx, on_accept),
+                    )
+                    .child(
+                        Button::new(("reject-hunk", hunk_ix))
+                            .icon(IconName::Close)
+                            .label("Reject")
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx, on_reject),
+                    )
+                    .into_any_element()
+            }
+        }
+    }
+
+    fn render_row(
+        &self,
+        row_ix: usize,
+        theme: &HighlightTheme,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        match self.rows[row_ix] {
+            DiffRow::Header(hunk_ix) => self.render_hunk_header(hunk_ix, cx),
+            DiffRow::Line(line) => self
+                .render_line(&line, [line.old_ix, line.new_ix], theme, window, cx)
+                .w_full()
+                .into_any_element(),
+            DiffRow::Pair(old_line, new_line) => {
+                let cx: &App = cx;
+                let side =
+                    |line: Option<DiffLine>, number: fn(&DiffLine) -> Option<usize>| match line {
+                        Some(line) => self
+                            .render_line(&line, [number(&line)], theme, window, cx)
+                            .w_1_2(),
+                        None => h_flex()
+                            .h(ROW_HEIGHT)
+                            .w_1_2()
+                            .bg(cx.theme().muted.opacity(0.5)),
+                    };
+
+                h_flex()
+                    .w_full()
+                    .child(side(old_line, |line| line.old_ix))
+                    .child(div().h_full().w(px(1.)).bg(cx.theme().border))
+                    .child(side(new_line, |line| line.new_ix))
+                    .into_any_element()
+            }
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let hunk_count = self.diff.hunks().len();
+        let (added, removed) = self
+            .diff
+            .hunks()
+            .iter()
+            .map(|hunk| hunk.stats())
+            .fold((0, 0), |(a, r), (added, removed)| (a + added, r + removed));
+
+        let on_mode_click = cx.listener(|this, selected: &Vec<usize>, _, cx| {
+            let mode = if selected.contains(&1) {
+                DiffMode::Split
+            } else {
+                DiffMode::Unified
+            };
+            this.set_mode(mode, cx);
+        });
+        let on_previous_click = cx.listener(|this, _, window, cx| {
+            this.previous_hunk(&PreviousHunk, window, cx);
+        });
+        let on_next_click = cx.listener(|this, _, window, cx| {
+            this.next_hunk(&NextHunk, window, cx);
+        });
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .text_xs()
+            .child(
+                ButtonGroup::new("diff-mode")
+                    .outline()
+                    .xsmall()
+                    .child(Button::new("diff-mode-unified").label("Unified"))
+                    .child(Button::new("diff-mode-split").label("Split"))
+                    .selected([match self.mode {
+                        DiffMode::Unified => 0,
+                        DiffMode::Split => 1,
+                    }])
+                    .on_click(on_mode_click),
+            )
+            .child(
+                div()
+                    .text_color(cx.theme().success)
+                    .child(format!("+{}", added)),
+            )
+            .child(
+                div()
+                    .text_color(cx.theme().danger)
+                    .child(format!("-{}", removed)),
+            )
+            .child(div().flex_1())
+            .child(
+                div()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(match self.selected_hunk {
+                        Some(ix) => format!("Hunk {} of {}", ix + 1, hunk_count),
+                        None => format!("{} hunks", hunk_count),
+                    }),
+            )
+            .child(
+                Button::new("previous-hunk")
+                    .icon(IconName::ChevronUp)
+                    .ghost()
+                    .xsmall()
+                    .tooltip_with_action("Previous Hunk", &PreviousHunk, Some(CONTEXT))
+                    .disabled(hunk_count == 0)
+                    .on_click(cx, on_previous_click),
+            )
+            .child(
+                Button::new("next-hunk")
+                    .icon(IconName::ChevronDown)
+                    .ghost()
+                    .xsmall()
+                    .tooltip_with_action("Next Hunk", &NextHunk, Some(CONTEXT))
+                    .disabled(hunk_count == 0)
+                    .on_click(cx, on_next_click),
+            )
+    }
+}
+
+/// Return the rows of the split mode, the removed and added lines of a change are side by side.
+fn split_rows(lines: &[DiffLine]) -> Vec<DiffRow> {
+    let mut rows = vec![];
+    let mut ix = 0;
+    while ix < lines.len() {
+        if lines[ix].kind == DiffLineKind::Unchanged {
+            rows.push(DiffRow::Pair(Some(lines[ix]), Some(lines[ix])));
+            ix += 1;
+            continue;
+        }
+
+        let end = lines[ix..]
+            .iter()
+            .position(|line| line.kind == DiffLineKind::Unchanged)
+            .map_or(lines.len(), |len| ix + len);
+        let (removed, added): (Vec<_>, Vec<_>) = lines[ix..end]
+            .iter()
+            .copied()
+            .partition(|line| line.kind == DiffLineKind::Removed);
+        for row in 0..removed.len().max(added.len()) {
+            rows.push(DiffRow::Pair(
+                removed.get(row).copied(),
+                added.get(row).copied(),
+            ));
+        }
+        ix = end;
+    }
+    rows
+}
+
+impl Focusable for DiffView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DiffView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.highlighters_outdated {
+            self.rebuild_highlighters(cx);
+        }
+        if let Some(highlighter) = self.old_highlighter.as_mut() {
+            highlighter.update(&(0..0), self.diff.old_text().clone(), "", cx);
+        }
+        if let Some(highlighter) = self.new_highlighter.as_mut() {
+            highlighter.update(&(0..0), self.diff.new_text().clone(), "", cx);
+        }
+
+        let theme = LanguageRegistry::global(cx)
+            .theme(cx.theme().is_dark())
+            .clone();
+
+        v_flex()
+            .key_context(CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::next_hunk))
+            .on_action(cx.listener(Self::previous_hunk))
+            .size_full()
+            .text_sm()
+            .text_color(theme.style.foreground.unwrap_or(cx.theme().foreground))
+            .bg(theme.style.background.unwrap_or(cx.theme().background))
+            .child(self.render_toolbar(cx))
+            .child(div().relative().flex_1().w_full().map(|this| {
+                if self.rows.is_empty() {
+                    return this.child(
+                        h_flex()
+                            .size_full()
+                            .justify_center()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("No changes"),
+                    );
+                }
+
+                this.child(
+                    uniform_list(
+                        "diff-rows",
+                        self.rows.len(),
+                        cx.processor(|this, range: Range<usize>, window, cx| {
+                            let theme = LanguageRegistry::global(cx)
+                                .theme(cx.theme().is_dark())
+                                .clone();
+                            range
+                                .map(|ix| this.render_row(ix, &theme, window, cx))
+                                .collect::<Vec<_>>()
+                        }),
+                    )
+                    .track_scroll(self.scroll_handle.clone())
+                    .size_full(),
+                )
+                .child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .right_0()
+                        .bottom_0()
+                        .child(Scrollbar::uniform_scroll(
+                            cx.entity_id(),
+                            self.scrollbar_state.clone(),
+                            self.scroll_handle.clone(),
+                        )),
+                )
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiffRow, split_rows};
+    use crate::diff_view::TextDiff;
+
+    #[test]
+    fn test_split_rows() {
+        let diff = TextDiff::new("a\nb\nc\nd", "a\nB\nC\nX\nd");
+        let rows = split_rows(&diff.hunks()[0].lines);
+        let ixs = rows
+            .iter()
+            .map(|row| match row {
+                DiffRow::Pair(old, new) => (
+                    old.and_then(|line| line.old_ix),
+                    new.and_then(|line| line.new_ix),
+                ),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            ixs,
+            vec![
+                (Some(0), Some(0)),
+                (Some(1), Some(1)),
+                (Some(2), Some(2)),
+                (None, Some(3)),
+                (Some(3), Some(4)),
+            ]
+        );
+    }
+}
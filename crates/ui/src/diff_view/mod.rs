@@ -0,0 +1,11 @@
+mod diff_view;
+mod text_diff;
+
+pub use diff_view::*;
+pub use text_diff::*;
+
+use gpui::App;
+
+pub fn init(cx: &mut App) {
+    diff_view::init(cx);
+}
@@ -0,0 +1,520 @@
+use std::ops::Range;
+
+use gpui::SharedString;
+
+/// The count of unchanged lines to show around the changes in a hunk.
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+/// A line of the diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// The line index (zero based) in the old text, `None` for the added line.
+    pub old_ix: Option<usize>,
+    /// The line index (zero based) in the new text, `None` for the removed line.
+    pub new_ix: Option<usize>,
+}
+
+/// A group of changed lines with the unchanged lines around them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// The line range (zero based) in the old text, including the context lines.
+    pub old_range: Range<usize>,
+    /// The line range (zero based) in the new text, including the context lines.
+    pub new_range: Range<usize>,
+    pub lines: Vec<DiffLine>,
+}
+
+impl DiffHunk {
+    /// Return the unified diff header of the hunk, e.g.: `@@ -1,4 +1,5 @@`.
+    pub fn header(&self) -> String {
+        format!(
+            "@@ -{},{} +{},{} @@",
+            self.old_range.start + 1,
+            self.old_range.len(),
+            self.new_range.start + 1,
+            self.new_range.len()
+        )
+    }
+
+    /// Return the count of the added and removed lines.
+    pub fn stats(&self) -> (usize, usize) {
+        self.lines
+            .iter()
+            .fold((0, 0), |(added, removed), line| match line.kind {
+                DiffLineKind::Added => (added + 1, removed),
+                DiffLineKind::Removed => (added, removed + 1),
+                DiffLineKind::Unchanged => (added, removed),
+            })
+    }
+}
+
+/// The line diff of two texts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextDiff {
+    old_text: SharedString,
+    new_text: SharedString,
+    /// The byte ranges of the lines in the old text, without the `\n`.
+    old_lines: Vec<Range<usize>>,
+    /// The byte ranges of the lines in the new text, without the `\n`.
+    new_lines: Vec<Range<usize>>,
+    hunks: Vec<DiffHunk>,
+}
+
+impl TextDiff {
+    /// Compute the line diff of the old and new text.
+    pub fn new(old_text: impl Into<SharedString>, new_text: impl Into<SharedString>) -> Self {
+        let old_text: SharedString = old_text.into();
+        let new_text: SharedString = new_text.into();
+        let old_lines = line_ranges(&old_text);
+        let new_lines = line_ranges(&new_text);
+
+        let old = old_lines
+            .iter()
+            .map(|range| &old_text[range.clone()])
+            .collect::<Vec<_>>();
+        let new = new_lines
+            .iter()
+            .map(|range| &new_text[range.clone()])
+            .collect::<Vec<_>>();
+        let hunks = build_hunks(&diff_lines(&old, &new));
+
+        Self {
+            old_text,
+            new_text,
+            old_lines,
+            new_lines,
+            hunks,
+        }
+    }
+
+    pub fn old_text(&self) -> &SharedString {
+        &self.old_text
+    }
+
+    pub fn new_text(&self) -> &SharedString {
+        &self.new_text
+    }
+
+    pub fn hunks(&self) -> &[DiffHunk] {
+        &self.hunks
+    }
+
+    /// Return the byte range of the line (zero based) in the old text.
+    pub fn old_line_range(&self, ix: usize) -> Option<Range<usize>> {
+        self.old_lines.get(ix).cloned()
+    }
+
+    /// Return the byte range of the line (zero based) in the new text.
+    pub fn new_line_range(&self, ix: usize) -> Option<Range<usize>> {
+        self.new_lines.get(ix).cloned()
+    }
+
+    /// Return the text of the line, the old text is used for the removed line.
+    pub fn line_text(&self, line: &DiffLine) -> &str {
+        match (line.old_ix, line.new_ix) {
+            (Some(ix), _) => &self.old_text[self.old_lines[ix].clone()],
+            (None, Some(ix)) => &self.new_text[self.new_lines[ix].clone()],
+            (None, None) => "",
+        }
+    }
+
+    /// Return the text that only the accepted hunks are applied to the old text.
+    pub fn apply(&self, accepted: impl Fn(usize) -> bool) -> String {
+        let old_line = |ix: usize| &self.old_text[self.old_lines[ix].clone()];
+        let new_line = |ix: usize| &self.new_text[self.new_lines[ix].clone()];
+
+        let mut lines = vec![];
+        let mut old_ix = 0;
+        for (hunk_ix, hunk) in self.hunks.iter().enumerate() {
+            lines.extend((old_ix..hunk.old_range.start).map(old_line));
+            if accepted(hunk_ix) {
+                lines.extend(hunk.lines.iter().filter_map(|line| match line.kind {
+                    DiffLineKind::Removed => None,
+                    _ => line.new_ix.map(new_line),
+                }));
+            } else {
+                lines.extend(hunk.old_range.clone().map(old_line));
+            }
+            old_ix = hunk.old_range.end;
+        }
+        lines.extend((old_ix..self.old_lines.len()).map(old_line));
+
+        lines.join("\n")
+    }
+}
+
+/// Return the byte ranges of the lines, without the `\n`.
+fn line_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut offset = 0;
+    text.split('\n')
+        .map(|line| {
+            let range = offset..offset + line.len();
+            // +1 for the `\n`
+            offset = range.end + 1;
+            range
+        })
+        .collect()
+}
+
+/// Compute the diff lines of the old and new lines by the Myers algorithm.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    let max_d = max_d(old.len(), new.len());
+    let mut vf = V::new(max_d);
+    let mut vb = V::new(max_d);
+    let mut lines = vec![];
+    conquer(
+        old,
+        0..old.len(),
+        new,
+        0..new.len(),
+        &mut vf,
+        &mut vb,
+        &mut lines,
+    );
+
+    // Show the removed lines before the added lines of a change, like the unified diff.
+    for run in lines.chunk_by_mut(|a, b| {
+        (a.kind == DiffLineKind::Unchanged) == (b.kind == DiffLineKind::Unchanged)
+    }) {
+        run.sort_by_key(|line| line.kind != DiffLineKind::Removed);
+    }
+    lines
+}
+
+/// Return the max count of the edits to search for the middle snake.
+fn max_d(old_len: usize, new_len: usize) -> usize {
+    (old_len + new_len).div_ceil(2) + 1
+}
+
+/// The furthest `x` of the diagonals `k` (`x - y`) in the Myers algorithm.
+struct V {
+    offset: isize,
+    v: Vec<usize>,
+}
+
+impl V {
+    fn new(max_d: usize) -> Self {
+        Self {
+            offset: max_d as isize,
+            v: vec![0; 2 * max_d + 1],
+        }
+    }
+}
+
+impl std::ops::Index<isize> for V {
+    type Output = usize;
+
+    fn index(&self, k: isize) -> &usize {
+        &self.v[(k + self.offset) as usize]
+    }
+}
+
+impl std::ops::IndexMut<isize> for V {
+    fn index_mut(&mut self, k: isize) -> &mut usize {
+        &mut self.v[(k + self.offset) as usize]
+    }
+}
+
+fn common_prefix_len(old: &[&str], new: &[&str]) -> usize {
+    old.iter().zip(new).take_while(|(a, b)| a == b).count()
+}
+
+fn common_suffix_len(old: &[&str], new: &[&str]) -> usize {
+    old.iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+/// Find the start of the middle snake of the shortest edit script, searching forward from the
+/// start and backward from the end at the same time, so only `O(N + M)` memory is used.
+///
+/// http://www.xmailserver.org/diff2.pdf, section 4b.
+fn find_middle_snake(
+    old: &[&str],
+    old_range: Range<usize>,
+    new: &[&str],
+    new_range: Range<usize>,
+    vf: &mut V,
+    vb: &mut V,
+) -> Option<(usize, usize)> {
+    let n = old_range.len();
+    let m = new_range.len();
+    let delta = n as isize - m as isize;
+    let odd = delta & 1 == 1;
+
+    vf[1] = 0;
+    vb[1] = 0;
+    for d in 0..max_d(n, m) as isize {
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && vf[k - 1] < vf[k + 1]) {
+                vf[k + 1]
+            } else {
+                vf[k - 1] + 1
+            };
+            let y = (x as isize - k) as usize;
+            let (x0, y0) = (x, y);
+            if x < n && y < m {
+                x += common_prefix_len(
+                    &old[old_range.start + x..old_range.end],
+                    &new[new_range.start + y..new_range.end],
+                );
+            }
+            vf[k] = x;
+            if odd && (k - delta).abs() < d && vf[k] + vb[-(k - delta)] >= n {
+                return Some((old_range.start + x0, new_range.start + y0));
+            }
+        }
+
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && vb[k - 1] < vb[k + 1]) {
+                vb[k + 1]
+            } else {
+                vb[k - 1] + 1
+            };
+            let mut y = (x as isize - k) as usize;
+            if x < n && y < m {
+                let len = common_suffix_len(
+                    &old[old_range.start..old_range.start + n - x],
+                    &new[new_range.start..new_range.start + m - y],
+                );
+                x += len;
+                y += len;
+            }
+            vb[k] = x;
+            if !odd && (k - delta).abs() <= d && vb[k] + vf[-(k - delta)] >= n {
+                return Some((old_range.start + n - x, new_range.start + m - y));
+            }
+        }
+    }
+
+    None
+}
+
+/// Push the diff lines of the old and new ranges in order, split by the middle snake.
+fn conquer(
+    old: &[&str],
+    mut old_range: Range<usize>,
+    new: &[&str],
+    mut new_range: Range<usize>,
+    vf: &mut V,
+    vb: &mut V,
+    lines: &mut Vec<DiffLine>,
+) {
+    let unchanged = |old_ix: usize, new_ix: usize| DiffLine {
+        kind: DiffLineKind::Unchanged,
+        old_ix: Some(old_ix),
+        new_ix: Some(new_ix),
+    };
+
+    let prefix = common_prefix_len(&old[old_range.clone()], &new[new_range.clone()]);
+    lines.extend((0..prefix).map(|ix| unchanged(old_range.start + ix, new_range.start + ix)));
+    old_range.start += prefix;
+    new_range.start += prefix;
+
+    let suffix = common_suffix_len(&old[old_range.clone()], &new[new_range.clone()]);
+    old_range.end -= suffix;
+    new_range.end -= suffix;
+    let (old_end, new_end) = (old_range.end, new_range.end);
+
+    let removed = |range: Range<usize>| {
+        range.map(|ix| DiffLine {
+            kind: DiffLineKind::Removed,
+            old_ix: Some(ix),
+            new_ix: None,
+        })
+    };
+    let added = |range: Range<usize>| {
+        range.map(|ix| DiffLine {
+            kind: DiffLineKind::Added,
+            old_ix: None,
+            new_ix: Some(ix),
+        })
+    };
+
+    if old_range.is_empty() || new_range.is_empty() {
+        lines.extend(removed(old_range));
+        lines.extend(added(new_range));
+    } else if let Some((x, y)) =
+        find_middle_snake(old, old_range.clone(), new, new_range.clone(), vf, vb)
+    {
+        conquer(
+            old,
+            old_range.start..x,
+            new,
+            new_range.start..y,
+            vf,
+            vb,
+            lines,
+        );
+        conquer(old, x..old_range.end, new, y..new_range.end, vf, vb, lines);
+    } else {
+        lines.extend(removed(old_range));
+        lines.extend(added(new_range));
+    }
+
+    lines.extend((0..suffix).map(|ix| unchanged(old_end + ix, new_end + ix)));
+}
+
+/// Group the changed lines into hunks with the context lines.
+fn build_hunks(lines: &[DiffLine]) -> Vec<DiffHunk> {
+    let mut hunks: Vec<DiffHunk> = vec![];
+    let mut range: Option<Range<usize>> = None;
+
+    let changed_ixs = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.kind != DiffLineKind::Unchanged)
+        .map(|(ix, _)| ix);
+    // The ranges of the lines in the hunks, merge the hunks if the contexts are overlapped.
+    let mut ranges = vec![];
+    for ix in changed_ixs {
+        let start = ix.saturating_sub(CONTEXT_LINES);
+        let end = (ix + 1 + CONTEXT_LINES).min(lines.len());
+        let merged = match &mut range {
+            Some(range) if start <= range.end => {
+                range.end = end;
+                true
+            }
+            _ => false,
+        };
+        if !merged {
+            ranges.extend(range.replace(start..end));
+        }
+    }
+    ranges.extend(range);
+
+    for range in ranges {
+        let lines = lines[range].to_vec();
+        let old_start = lines
+            .iter()
+            .find_map(|line| line.old_ix)
+            .unwrap_or_else(|| old_line_before(&hunks));
+        let new_start = lines
+            .iter()
+            .find_map(|line| line.new_ix)
+            .unwrap_or_else(|| new_line_before(&hunks));
+        let old_len = lines.iter().filter(|line| line.old_ix.is_some()).count();
+        let new_len = lines.iter().filter(|line| line.new_ix.is_some()).count();
+
+        hunks.push(DiffHunk {
+            old_range: old_start..old_start + old_len,
+            new_range: new_start..new_start + new_len,
+            lines,
+        });
+    }
+
+    hunks
+}
+
+fn old_line_before(hunks: &[DiffHunk]) -> usize {
+    hunks.last().map_or(0, |hunk| hunk.old_range.end)
+}
+
+fn new_line_before(hunks: &[DiffHunk]) -> usize {
+    hunks.last().map_or(0, |hunk| hunk.new_range.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiffLineKind, TextDiff};
+
+    #[test]
+    fn test_text_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn";
+        let diff = TextDiff::new(old, new);
+
+        let hunks = diff.hunks();
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].header(), "@@ -1,5 +1,5 @@");
+        assert_eq!(hunks[0].stats(), (1, 1));
+        let kinds = hunks[0]
+            .lines
+            .iter()
+            .map(|line| line.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                DiffLineKind::Unchanged,
+                DiffLineKind::Removed,
+                DiffLineKind::Added,
+                DiffLineKind::Unchanged,
+                DiffLineKind::Unchanged,
+                DiffLineKind::Unchanged,
+            ]
+        );
+        assert_eq!(diff.line_text(&hunks[0].lines[1]), "b");
+        assert_eq!(diff.line_text(&hunks[0].lines[2]), "B");
+        assert_eq!(hunks[1].header(), "@@ -11,3 +11,4 @@");
+
+        assert_eq!(diff.apply(|_| true), new);
+        assert_eq!(diff.apply(|_| false), old);
+        assert_eq!(
+            diff.apply(|ix| ix == 1),
+            "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn"
+        );
+        assert_eq!(
+            diff.apply(|ix| ix == 0),
+            "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm"
+        );
+    }
+
+    #[test]
+    fn test_text_diff_edge_cases() {
+        assert!(TextDiff::new("a\nb", "a\nb").hunks().is_empty());
+
+        let diff = TextDiff::new("", "a\nb");
+        assert_eq!(diff.hunks().len(), 1);
+        assert_eq!(diff.apply(|_| true), "a\nb");
+        assert_eq!(diff.apply(|_| false), "");
+
+        let diff = TextDiff::new("x\ny\nz", "z\ny\nx");
+        assert_eq!(diff.apply(|_| true), "z\ny\nx");
+        assert_eq!(diff.apply(|_| false), "x\ny\nz");
+    }
+
+    #[test]
+    fn test_text_diff_shortest() {
+        // The example of the Myers paper, the shortest edit script has 5 edits.
+        let old = "a\nb\nc\na\nb\nb\na";
+        let new = "c\nb\na\nb\na\nc";
+        let diff = TextDiff::new(old, new);
+        let (added, removed) = diff
+            .hunks()
+            .iter()
+            .map(|hunk| hunk.stats())
+            .fold((0, 0), |(a, r), (added, removed)| (a + added, r + removed));
+        assert_eq!(added + removed, 5);
+        assert_eq!(diff.apply(|_| true), new);
+        assert_eq!(diff.apply(|_| false), old);
+
+        let old = (0..200).map(|ix| ix.to_string()).collect::<Vec<_>>();
+        let new = old
+            .iter()
+            .enumerate()
+            .filter(|(ix, _)| ix % 7 != 0)
+            .map(|(ix, line)| {
+                if ix % 5 == 0 {
+                    format!("{}!", line)
+                } else {
+                    line.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        let (old, new) = (old.join("\n"), new.join("\n"));
+        let diff = TextDiff::new(old.clone(), new.clone());
+        assert_eq!(diff.apply(|_| true), new);
+        assert_eq!(diff.apply(|_| false), old);
+    }
+}
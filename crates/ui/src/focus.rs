@@ -122,6 +122,30 @@ pub fn get_or_create_focus_handle(cx: &mut App, element_id: ElementId) -> FocusH
     handle
 }
 
+/// Get the FocusHandle of a form control, e.g.: a switch, and register its Enter handler.
+/// A disabled control is skipped during tab traversal.
+pub fn register_control(
+    cx: &mut App,
+    element_id: ElementId,
+    disabled: bool,
+    handler: Option<impl Fn(&ClickEvent, &mut Window, &mut App) + 'static>,
+) -> FocusHandle {
+    let handle = get_or_create_focus_handle(cx, element_id.clone());
+    let registry = cx.global_mut::<FocusRegistry>();
+    match handler.filter(|_| !disabled) {
+        Some(handler) => registry
+            .button_handlers
+            .insert(element_id.clone(), Box::new(handler)),
+        None => registry.button_handlers.remove(&element_id),
+    };
+    if disabled {
+        registry.disabled.insert(element_id);
+    } else {
+        registry.disabled.remove(&element_id);
+    }
+    handle
+}
+
 /// Disable a list of focus handles.
 /// Disabled handles will be skipped during tab traversal.
 pub fn disable_focus_handles(cx: &mut App, ids: Vec<ElementId>) {
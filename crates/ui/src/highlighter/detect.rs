@@ -0,0 +1,230 @@
+use std::path::Path;
+
+use gpui::SharedString;
+
+use super::{Language, LanguageRegistry};
+
+/// The well-known file names without (meaningful) extension, `(file_name, language)`.
+const FILE_NAMES: &[(&str, &str)] = &[
+    ("Makefile", "make"),
+    ("makefile", "make"),
+    ("GNUmakefile", "make"),
+    ("CMakeLists.txt", "cmake"),
+    ("Dockerfile", "dockerfile"),
+    ("Containerfile", "dockerfile"),
+    ("Gemfile", "ruby"),
+    ("Rakefile", "ruby"),
+    ("Cargo.lock", "toml"),
+    (".bashrc", "bash"),
+    (".bash_profile", "bash"),
+    (".zshrc", "bash"),
+    (".profile", "bash"),
+];
+
+/// The interpreters in the shebang that are not a language name, `(interpreter, language)`.
+const INTERPRETERS: &[(&str, &str)] = &[
+    ("sh", "bash"),
+    ("dash", "bash"),
+    ("ksh", "bash"),
+    ("zsh", "bash"),
+    ("node", "javascript"),
+    ("nodejs", "javascript"),
+    ("deno", "typescript"),
+    ("bun", "javascript"),
+    ("ts-node", "typescript"),
+    ("tsx", "typescript"),
+];
+
+impl Language {
+    /// Detect the builtin language of the file by the modeline in the first line,
+    /// the well-known file name, the extension and the shebang, in order.
+    ///
+    /// ```ignore
+    /// assert_eq!(Language::detect("src/main.rs", ""), Some(Language::Rust));
+    /// assert_eq!(Language::detect("CMakeLists.txt", ""), Some(Language::CMake));
+    /// assert_eq!(Language::detect("bin/run", "#!/usr/bin/env python3"), Some(Language::Python));
+    /// ```
+    pub fn detect(path: impl AsRef<Path>, first_line: &str) -> Option<Self> {
+        candidates(path.as_ref(), first_line).find_map(|name| Self::from_str(&name))
+    }
+}
+
+impl LanguageRegistry {
+    /// Detect the language name of the file like [`Language::detect`], including the languages
+    /// registered at runtime, e.g.: `kotlin` for `build.gradle.kts` if the grammar is loaded.
+    pub fn detect(&self, path: impl AsRef<Path>, first_line: &str) -> Option<SharedString> {
+        candidates(path.as_ref(), first_line).find_map(|name| {
+            if let Some((name, _)) = self.registered_language(&name) {
+                return Some(name);
+            }
+
+            Language::from_str(&name).map(|language| language.name().into())
+        })
+    }
+}
+
+/// Return the language names (or aliases) of the file in order of precedence.
+fn candidates<'a>(path: &'a Path, first_line: &'a str) -> impl Iterator<Item = String> + 'a {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+    let extension = path.extension().and_then(|ext| ext.to_str());
+
+    let modeline = modeline_language(first_line);
+    let well_known = FILE_NAMES
+        .iter()
+        .find(|(name, _)| *name == file_name)
+        .map(|(_, language)| language.to_string())
+        .or_else(|| {
+            file_name
+                .starts_with("Dockerfile.")
+                .then(|| "dockerfile".into())
+        });
+    let extension = extension.map(|ext| ext.to_lowercase());
+    let shebang = shebang_language(first_line);
+
+    [modeline, well_known, extension, shebang]
+        .into_iter()
+        .flatten()
+}
+
+/// Return the language of the shebang, e.g.: `#!/usr/bin/env python3` is `python`.
+fn shebang_language(line: &str) -> Option<String> {
+    let mut parts = line.strip_prefix("#!")?.split_whitespace();
+    let mut interpreter = parts.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        // Skip the options of env, e.g.: `env -S node --flags`.
+        interpreter = parts.find(|part| !part.starts_with('-'))?;
+    }
+
+    // Strip the version, e.g.: `python3.12`.
+    let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    let language = INTERPRETERS
+        .iter()
+        .find(|(name, _)| *name == interpreter)
+        .map_or(interpreter, |(_, language)| language);
+    (!language.is_empty()).then(|| language.to_string())
+}
+
+/// Return the language of the Vim or Emacs modeline.
+///
+/// - `// vim: set ft=rust:`, `# vi: filetype=python`
+/// - `# -*- mode: ruby -*-`, `// -*- c++ -*-`
+fn modeline_language(line: &str) -> Option<String> {
+    if let Some(start) = line.find("-*-") {
+        let rest = &line[start + 3..];
+        let content = &rest[..rest.find("-*-")?];
+        if !content.contains(':') {
+            return non_empty(content.trim());
+        }
+
+        return content.split(';').find_map(|pair| {
+            let (key, value) = pair.split_once(':')?;
+            if !key.trim().eq_ignore_ascii_case("mode") {
+                return None;
+            }
+            non_empty(value.trim())
+        });
+    }
+
+    let start = ["vim:", "vi:", "ex:"].iter().find_map(|prefix| {
+        line.match_indices(prefix)
+            .find(|(ix, _)| *ix == 0 || line[..*ix].ends_with(char::is_whitespace))
+            .map(|(ix, _)| ix + prefix.len())
+    })?;
+
+    line[start..]
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .find_map(|option| {
+            let (key, value) = option.split_once('=')?;
+            if !matches!(key, "ft" | "filetype" | "syntax" | "syn") {
+                return None;
+            }
+            non_empty(value)
+        })
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Language, LanguageRegistry, modeline_language, shebang_language};
+    use crate::highlighter::LanguageConfig;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Language::detect("src/main.rs", ""), Some(Language::Rust));
+        assert_eq!(Language::detect("include/foo.HPP", ""), Some(Language::Cpp));
+        assert_eq!(Language::detect("Makefile", ""), Some(Language::Make));
+        assert_eq!(
+            Language::detect("a/CMakeLists.txt", ""),
+            Some(Language::CMake)
+        );
+        assert_eq!(Language::detect("notes.txt", ""), None);
+        assert_eq!(
+            Language::detect("bin/run", "#!/usr/bin/env python3"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::detect("script.txt", "# vim: set ft=sh:"),
+            Some(Language::Bash)
+        );
+    }
+
+    #[test]
+    fn test_detect_registered() {
+        let mut registry = LanguageRegistry::new();
+        assert_eq!(registry.detect("Dockerfile", ""), None);
+        assert_eq!(registry.detect("src/main.rs", ""), Some("rust".into()));
+
+        // The dockerfile grammar is not builtin, so it's detected once registered.
+        let config = LanguageConfig::new(
+            tree_sitter::Language::new(tree_sitter_bash::LANGUAGE),
+            "",
+            "",
+            "",
+        );
+        registry.register("dockerfile", &config);
+        assert_eq!(registry.detect("Dockerfile", ""), Some("dockerfile".into()));
+        assert_eq!(
+            registry.detect("docker/Dockerfile.dev", ""),
+            Some("dockerfile".into())
+        );
+        assert_eq!(
+            registry.detect("Containerfile", ""),
+            Some("dockerfile".into())
+        );
+    }
+
+    #[test]
+    fn test_shebang_and_modeline() {
+        assert_eq!(shebang_language("#!/bin/bash").as_deref(), Some("bash"));
+        assert_eq!(
+            shebang_language("#!/usr/bin/env -S node --no-warnings").as_deref(),
+            Some("javascript")
+        );
+        assert_eq!(
+            shebang_language("#!/usr/bin/lua5.4").as_deref(),
+            Some("lua")
+        );
+        assert_eq!(shebang_language("// not a shebang"), None);
+
+        assert_eq!(
+            modeline_language("// vim: set ft=rust:").as_deref(),
+            Some("rust")
+        );
+        assert_eq!(
+            modeline_language("# vi: filetype=python").as_deref(),
+            Some("python")
+        );
+        assert_eq!(
+            modeline_language("# -*- mode: Ruby; coding: utf-8 -*-").as_deref(),
+            Some("ruby")
+        );
+        assert_eq!(modeline_language("// -*- c++ -*-").as_deref(), Some("c++"));
+        assert_eq!(modeline_language("let vim: i32 = 1;"), None);
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use gpui::SharedString;
+use serde::Deserialize;
+use tree_sitter::{LanguageFn, Parser, Query};
+
+use super::LanguageConfig;
+
+/// The default extensions and comment tokens of the grammars that are not builtin,
+/// used when the `grammar.json` omits them.
+const KNOWN_GRAMMARS: &[(&str, &[&str], Option<&str>, Option<(&str, &str)>)] = &[
+    ("kotlin", &["kt", "kts"], Some("//"), Some(("/*", "*/"))),
+    ("lua", &["lua"], Some("--"), Some(("--[[", "]]"))),
+    ("nix", &["nix"], Some("#"), Some(("/*", "*/"))),
+];
+
+/// The optional `grammar.json` in the grammar directory.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GrammarManifest {
+    /// The language name, default is the directory name.
+    name: Option<String>,
+    /// The file extensions, default is the language name.
+    extensions: Vec<String>,
+    /// The shared library file name, default is the first shared library in the directory.
+    library: Option<String>,
+    /// The symbol of the language function, default is `tree_sitter_{name}`.
+    symbol: Option<String>,
+    line_comment: Option<String>,
+    block_comment: Option<(String, String)>,
+}
+
+/// Load the tree-sitter grammars from the sub directories of `dir`, returns the names and
+/// the configs of the languages to [`super::LanguageRegistry::register`].
+///
+/// Each grammar directory contains:
+///
+/// - The grammar compiled as a shared library, e.g.: `libtree-sitter-kotlin.so`
+///   (`.dylib` on macOS, `.dll` on Windows).
+/// - `highlights.scm`, and the optional `injections.scm`, `locals.scm`, `indents.scm`.
+/// - The optional `grammar.json`: `name`, `extensions`, `library`, `symbol`,
+///   `line_comment` and `block_comment`.
+///
+/// The grammars failed to load are logged and skipped.
+///
+/// This reads the files and loads the libraries, run it in the background executor.
+pub fn load_grammars(dir: impl AsRef<Path>) -> Vec<(SharedString, LanguageConfig)> {
+    let Ok(entries) = fs::read_dir(dir.as_ref()) else {
+        return vec![];
+    };
+
+    let mut grammars = vec![];
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        match load_grammar(&path) {
+            Ok((name, config)) => grammars.push((name.into(), config)),
+            Err(err) => {
+                tracing::error!("failed to load grammar {}: {:?}", path.display(), err);
+            }
+        }
+    }
+    grammars
+}
+
+fn load_grammar(dir: &Path) -> Result<(String, LanguageConfig)> {
+    let manifest_path = dir.join("grammar.json");
+    let manifest: GrammarManifest = if manifest_path.exists() {
+        serde_json::from_str(&fs::read_to_string(&manifest_path)?)
+            .with_context(|| format!("invalid {}", manifest_path.display()))?
+    } else {
+        GrammarManifest::default()
+    };
+
+    let name = match manifest.name {
+        Some(name) => name,
+        None => dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .context("invalid grammar directory name")?
+            .to_string(),
+    };
+
+    let library = match &manifest.library {
+        Some(library) => dir.join(library),
+        None => find_library(dir)?,
+    };
+    let symbol = manifest
+        .symbol
+        .unwrap_or_else(|| format!("tree_sitter_{}", name.replace('-', "_")));
+    let language = load_language(&library, &symbol)?;
+
+    let read_query = |file_name: &str| -> Result<String> {
+        let path = dir.join(file_name);
+        if !path.exists() {
+            return Ok(String::new());
+        }
+
+        let source = fs::read_to_string(&path)?;
+        if file_name != "indents.scm" {
+            Query::new(&language, &source)
+                .map_err(|err| anyhow!("invalid {}: {}", file_name, err))?;
+        }
+        Ok(source)
+    };
+    let highlights = read_query("highlights.scm")?;
+    let injections = read_query("injections.scm")?;
+    let locals = read_query("locals.scm")?;
+    let indents = read_query("indents.scm")?;
+
+    let known = KNOWN_GRAMMARS.iter().find(|(known, ..)| *known == name);
+    let extensions = if !manifest.extensions.is_empty() {
+        manifest.extensions
+    } else if let Some((_, extensions, ..)) = known {
+        extensions.iter().map(|ext| ext.to_string()).collect()
+    } else {
+        vec![name.clone()]
+    };
+    let line_comment = manifest
+        .line_comment
+        .or_else(|| known.and_then(|(_, _, token, _)| token.map(Into::into)));
+    let block_comment = manifest.block_comment.or_else(|| {
+        known.and_then(|(_, _, _, tokens)| tokens.map(|(start, end)| (start.into(), end.into())))
+    });
+
+    let mut config = LanguageConfig::new(language, &highlights, &injections, &locals)
+        .indents(&indents)
+        .extensions(extensions);
+    if let Some(token) = line_comment {
+        config = config.line_comment(token);
+    }
+    if let Some((start, end)) = block_comment {
+        config = config.block_comment(start, end);
+    }
+
+    Ok((name, config))
+}
+
+/// Return the first shared library in the directory.
+fn find_library(dir: &Path) -> Result<PathBuf> {
+    let files = fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    let has_extension = |path: &PathBuf, extension: &str| {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+    };
+
+    if let Some(path) = files
+        .iter()
+        .find(|path| has_extension(path, std::env::consts::DLL_EXTENSION))
+    {
+        return Ok(path.clone());
+    }
+
+    if files.iter().any(|path| has_extension(path, "wasm")) {
+        bail!("wasm grammars are not supported, build the grammar as a shared library");
+    }
+    bail!("no shared library found")
+}
+
+/// Load the tree-sitter language from the shared library.
+///
+/// The library is never unloaded, the language is used for the lifetime of the app.
+fn load_language(path: &Path, symbol: &str) -> Result<tree_sitter::Language> {
+    // SAFETY: The grammar libraries are trusted, as the user put them in the grammars directory.
+    let library = unsafe { libloading::Library::new(path) }
+        .with_context(|| format!("failed to load {}", path.display()))?;
+    let language_fn = unsafe {
+        let language_fn = library
+            .get::<unsafe extern "C" fn() -> *const ()>(symbol.as_bytes())
+            .with_context(|| format!("symbol {} not found", symbol))?;
+        LanguageFn::from_raw(*language_fn)
+    };
+    std::mem::forget(library);
+
+    let language = tree_sitter::Language::new(language_fn);
+    Parser::new()
+        .set_language(&language)
+        .context("incompatible grammar version")?;
+    Ok(language)
+}
@@ -9,19 +9,34 @@ use tree_sitter::{
 };
 use tree_sitter_highlight::{HighlightConfiguration, Highlighter};
 
-use super::{HighlightTheme, Language};
+use super::{
+    HighlightTheme, Language, LanguageConfig, LanguageRegistry, SemanticToken,
+    semantic::overlay_styles,
+};
 
 /// A syntax highlighter that supports incremental parsing, multiline text,
 /// and caching of highlight results.
 #[allow(unused)]
 pub struct SyntaxHighlighter {
-    language_name: &'static str,
+    language_name: SharedString,
     language: Option<Language>,
-    query: Option<Query>,
-    injection_queries: HashMap<&'static str, Query>,
-    parser: Parser,
+    ts_language: Option<tree_sitter::Language>,
+    queries: Option<Arc<HighlightQueries>>,
+    /// The last parsed tree, the edits after parsing are applied to keep the offsets in sync
+    /// with the text.
     old_tree: Option<Tree>,
     text: SharedString,
+    /// Increased on every change of the text, to discard the outdated [`HighlightResult`].
+    version: usize,
+    /// The version of the text that the styles are built for.
+    highlighted_version: usize,
+    /// The version of the text that the `old_tree` is parsed for, the tree is only shifted by
+    /// the edits after it.
+    parsed_version: usize,
+    /// The version of the last [`HighlightSnapshot`], to avoid highlighting it twice.
+    snapshot_version: usize,
+    /// The range of the text that edited since the last complete highlighting.
+    edited_range: Option<Range<usize>>,
     highlighter: Highlighter,
     config: Option<Arc<HighlightConfiguration>>,
 
@@ -29,8 +44,6 @@ pub struct SyntaxHighlighter {
     highlights_pattern_index: usize,
     // highlight_indices: Vec<Option<Highlight>>,
     non_local_variable_patterns: Vec<bool>,
-    injection_content_capture_index: Option<u32>,
-    injection_language_capture_index: Option<u32>,
     local_scope_capture_index: Option<u32>,
     local_def_capture_index: Option<u32>,
     local_def_value_capture_index: Option<u32>,
@@ -38,31 +51,136 @@ pub struct SyntaxHighlighter {
 
     /// Cache of highlight, the range is offset of the token in the tree.
     ///
+    /// The styles are kept (shifted by the edits) until they are rebuilt, so the stale
+    /// highlights are shown while highlighting in the background.
+    ///
     /// The BTreeMap is ordered by the range in the entire text.
     ///
     /// - The `key` is the `start` of the range.
     /// -The `value` is a tuple of the range (in the entire text) and the highlight name.
     cache: BTreeMap<usize, (Range<usize>, String)>,
+    /// The semantic tokens layered on top of the highlights, sorted by the range.
+    semantic_tokens: Vec<SemanticToken>,
+    /// Cache of the foldable line ranges, cleared when the tree changes.
+    foldable_ranges: Option<Vec<Range<usize>>>,
+    /// The query to calculate the indent level of lines.
+    indent_query: Option<Query>,
+    line_comment: Option<SharedString>,
+    block_comment: Option<(SharedString, SharedString)>,
+}
+
+/// The indent patterns for all languages, the brackets that not in the grammar are ignored.
+const BRACKET_INDENT_PATTERNS: &[&str] = &[
+    r#"(_ "{" "}" @end) @indent"#,
+    r#"(_ "[" "]" @end) @indent"#,
+    r#"(_ "(" ")" @end) @indent"#,
+];
+
+/// The node kinds (suffix) that can be folded without bracket delimiters.
+const FOLDABLE_KIND_SUFFIXES: &[&str] = &[
+    "_definition",
+    "_statement",
+    "class",
+    "comment",
+    "do_block",
+    "element",
+    "method",
+    "module",
+    "pair",
+    "section",
+    "table",
+];
+
+/// The queries to build the highlight styles, shared with the background tasks.
+struct HighlightQueries {
+    language_name: SharedString,
+    query: Query,
+    injection_queries: HashMap<&'static str, Query>,
+    injection_content_capture_index: Option<u32>,
+    injection_language_capture_index: Option<u32>,
+}
+
+/// A snapshot of the [`SyntaxHighlighter`] to parse the text and build the highlight styles,
+/// that can be sent to the background executor.
+///
+/// See [`SyntaxHighlighter::snapshot`].
+pub struct HighlightSnapshot {
+    version: usize,
+    text: SharedString,
+    language: tree_sitter::Language,
+    queries: Option<Arc<HighlightQueries>>,
+    /// The last parsed tree with the edits applied.
+    old_tree: Option<Tree>,
+    edited_range: Option<Range<usize>>,
+    /// The parsed tree and the range of the text that the styles need to rebuild.
+    parsed: Option<(Tree, Range<usize>)>,
+}
+
+/// The highlight styles built by [`HighlightSnapshot::highlight`], apply it by
+/// [`SyntaxHighlighter::apply`].
+pub struct HighlightResult {
+    version: usize,
+    tree: Tree,
+    /// The range that the styles need to rebuild for the changes.
+    changed_range: Range<usize>,
+    /// The range that the styles are rebuilt.
+    range: Range<usize>,
+    styles: Vec<(Range<usize>, String)>,
 }
 
 impl SyntaxHighlighter {
-    /// Create a new SyntaxHighlighter for HTML.
+    /// Create a new SyntaxHighlighter for the builtin language, the text is not highlighted if
+    /// the language is not supported, e.g.: `text`.
     pub fn new(lang: &str) -> Self {
-        Self::build_combined_injections_query(&lang).unwrap()
+        Self::build_combined_injections_query(&lang, None).unwrap_or_else(Self::plain)
+    }
+
+    /// Create a SyntaxHighlighter without a language, that highlights nothing.
+    fn plain() -> Self {
+        Self {
+            language_name: SharedString::default(),
+            language: None,
+            ts_language: None,
+            queries: None,
+            old_tree: None,
+            text: SharedString::new(""),
+            version: 0,
+            highlighted_version: 0,
+            parsed_version: 0,
+            snapshot_version: 0,
+            edited_range: None,
+            highlighter: Highlighter::new(),
+            config: None,
+            cache: BTreeMap::new(),
+            semantic_tokens: vec![],
+            foldable_ranges: None,
+            indent_query: None,
+            line_comment: None,
+            block_comment: None,
+            locals_pattern_index: 0,
+            highlights_pattern_index: 0,
+            non_local_variable_patterns: vec![],
+            local_scope_capture_index: None,
+            local_def_capture_index: None,
+            local_def_value_capture_index: None,
+            local_ref_capture_index: None,
+        }
+    }
+
+    /// Create a new SyntaxHighlighter for the language, including the languages registered in
+    /// the [`LanguageRegistry`], `None` if the language is not supported.
+    pub fn for_language(lang: &str, cx: &App) -> Option<Self> {
+        Self::build_combined_injections_query(lang, Some(LanguageRegistry::global(cx)))
     }
 
     /// Build the combined injections query for the given language.
     ///
     /// https://github.com/tree-sitter/tree-sitter/blob/v0.25.5/highlight/src/lib.rs#L336
-    fn build_combined_injections_query(lang: &str) -> Option<Self> {
-        let language = Language::from_str(&lang);
-        let Some(language) = language else {
-            return None;
-        };
-        let config = language.config();
-
-        let mut parser = Parser::new();
-        _ = parser.set_language(&config.language);
+    fn build_combined_injections_query(
+        lang: &str,
+        registry: Option<&LanguageRegistry>,
+    ) -> Option<Self> {
+        let (language_name, language, config) = language_config(lang, registry)?;
 
         // Concatenate the query strings, keeping track of the start offset of each section.
         let mut query_source = String::new();
@@ -126,8 +244,6 @@ impl SyntaxHighlighter {
             .collect();
 
         // Store the numeric ids for all of the special captures.
-        let mut injection_content_capture_index = None;
-        let mut injection_language_capture_index = None;
         let mut local_def_capture_index = None;
         let mut local_def_value_capture_index = None;
         let mut local_ref_capture_index = None;
@@ -135,8 +251,6 @@ impl SyntaxHighlighter {
         for (i, name) in query.capture_names().iter().enumerate() {
             let i = Some(i as u32);
             match *name {
-                "injection.content" => injection_content_capture_index = i,
-                "injection.language" => injection_language_capture_index = i,
                 "local.definition" => local_def_capture_index = i,
                 "local.definition-value" => local_def_value_capture_index = i,
                 "local.reference" => local_ref_capture_index = i,
@@ -146,7 +260,10 @@ impl SyntaxHighlighter {
         }
 
         let mut injection_queries = HashMap::new();
-        for inj_language in language.injection_languages() {
+        let injection_languages = language
+            .map(|language| language.injection_languages())
+            .unwrap_or_default();
+        for inj_language in injection_languages {
             let inj_config = inj_language.config();
 
             match Query::new(&inj_config.language, &inj_config.highlights) {
@@ -166,21 +283,32 @@ impl SyntaxHighlighter {
         // let highlight_indices = vec![None; query.capture_names().len()];
 
         Some(Self {
-            language_name: language.name(),
-            language: Some(language),
-            query: Some(query),
-            injection_queries,
-            parser,
+            language_name: language_name.clone(),
+            language,
+            ts_language: Some(config.language.clone()),
+            queries: Some(Arc::new(HighlightQueries::new(
+                language_name,
+                query,
+                injection_queries,
+            ))),
             old_tree: None,
             text: SharedString::new(""),
+            version: 0,
+            highlighted_version: 0,
+            parsed_version: 0,
+            snapshot_version: 0,
+            edited_range: None,
             highlighter: Highlighter::new(),
             config: None,
             cache: BTreeMap::new(),
+            semantic_tokens: vec![],
+            foldable_ranges: None,
+            indent_query: build_indent_query(&config),
+            line_comment: config.line_comment.clone(),
+            block_comment: config.block_comment.clone(),
             locals_pattern_index,
             highlights_pattern_index,
             non_local_variable_patterns,
-            injection_content_capture_index,
-            injection_language_capture_index,
             local_scope_capture_index,
             local_def_capture_index,
             local_def_value_capture_index,
@@ -188,172 +316,579 @@ impl SyntaxHighlighter {
         })
     }
 
-    pub fn set_language(&mut self, lang: impl Into<SharedString>) {
+    /// Set the language, including the languages registered in the [`LanguageRegistry`].
+    pub fn set_language(&mut self, lang: impl Into<SharedString>, cx: &App) {
         let lang = lang.into();
-        let language = Language::from_str(&lang);
-        if self.language == language {
+        let resolved = language_config(&lang, Some(LanguageRegistry::global(cx)));
+        let language_name = resolved
+            .as_ref()
+            .map_or(SharedString::default(), |(name, _, _)| name.clone());
+        if self.language_name == language_name {
             return;
         }
 
         // FIXME: use build_combined_injections_query to build the query.
 
-        if let Some(language) = language {
-            _ = self.parser.set_language(&language.config().language);
-        }
+        let language = resolved.as_ref().and_then(|(_, language, _)| *language);
+        let config = resolved.map(|(_, _, config)| config);
+        self.indent_query = config.as_ref().and_then(build_indent_query);
+        self.line_comment = config.as_ref().and_then(|c| c.line_comment.clone());
+        self.block_comment = config.as_ref().and_then(|c| c.block_comment.clone());
 
+        self.language_name = language_name.clone();
         self.language = language;
-        self.query = language.map(|l| l.query());
+        self.ts_language = config.as_ref().map(|config| config.language.clone());
+        self.queries = config
+            .as_ref()
+            .and_then(|config| Query::new(&config.language, &config.highlights).ok())
+            .map(|query| Arc::new(HighlightQueries::new(language_name, query, HashMap::new())));
         self.old_tree = None;
         self.text = SharedString::new("");
+        // Discard the highlighting in progress for the previous language.
+        self.version += 1;
+        self.highlighted_version = self.version;
+        self.edited_range = None;
         self.highlighter = Highlighter::new();
         self.config = None;
         self.cache.clear();
+        self.semantic_tokens.clear();
+        self.foldable_ranges = None;
     }
 
     pub fn is_empty(&self) -> bool {
         self.text.is_empty()
     }
 
+    /// Return the language of the highlighter, `None` if the language is not supported
+    /// or is registered at runtime.
+    pub fn language(&self) -> Option<Language> {
+        self.language
+    }
+
     /// Highlight the given text, returning a map from byte ranges to highlight captures.
     /// Uses incremental parsing, detects changed ranges, and caches unchanged results.
+    ///
+    /// This parses and builds the styles synchronously, use [`Self::edit`] and
+    /// [`Self::snapshot`] to highlight the large text in the background.
     pub fn update(
         &mut self,
         selected_range: &Range<usize>,
         full_text: SharedString,
         new_text: &str,
-        cx: &mut App,
+        _: &mut App,
     ) {
+        self.edit(selected_range, full_text, new_text);
+        if let Some(mut snapshot) = self.snapshot() {
+            if let Some(result) = snapshot.highlight(None) {
+                self.apply(result);
+            }
+        }
+    }
+
+    /// Apply the edit of the text without highlighting, the `selected_range` is replaced by the
+    /// `new_text` to get the `full_text`.
+    ///
+    /// The styles of the unchanged text are kept and shifted by the edit, until the new styles
+    /// are applied by [`Self::apply`].
+    pub fn edit(&mut self, selected_range: &Range<usize>, full_text: SharedString, new_text: &str) {
         if self.text == full_text {
             return;
         }
 
+        self.version += 1;
+        self.foldable_ranges = None;
+
         // If insert a chart, this is 1.
         // If backspace or delete, this is -1.
         // If selected to delete, this is the length of the selected text.
         let changed_len = new_text.len() as isize - selected_range.len() as isize;
 
-        let new_tree = match &self.old_tree {
-            None => self.parser.parse(full_text.as_ref(), None),
-            Some(old) => {
-                let edit = InputEdit {
-                    start_byte: selected_range.start,
-                    old_end_byte: selected_range.end,
-                    new_end_byte: (selected_range.end as isize + changed_len) as usize,
-                    start_position: Point::new(0, 0),
-                    old_end_position: Point::new(0, 0),
-                    new_end_position: Point::new(0, 0),
-                };
-                let mut old_cloned = old.clone();
-                old_cloned.edit(&edit);
-                // NOTE: 10K lines, about 4.5ms
-                self.parser.parse(full_text.as_ref(), Some(&old_cloned))
-            }
+        // The edit not matches the text, e.g.: the text is replaced, so highlight it from scratch.
+        if self.text.len() as isize + changed_len != full_text.len() as isize
+            || selected_range.end > self.text.len()
+        {
+            self.old_tree = None;
+            self.cache.clear();
+            self.semantic_tokens.clear();
+            self.edited_range = None;
+            self.text = full_text;
+            return;
+        }
+
+        let edit = InputEdit {
+            start_byte: selected_range.start,
+            old_end_byte: selected_range.end,
+            new_end_byte: selected_range.start + new_text.len(),
+            start_position: Point::new(0, 0),
+            old_end_position: Point::new(0, 0),
+            new_end_position: Point::new(0, 0),
         };
+        if let Some(tree) = &mut self.old_tree {
+            tree.edit(&edit);
+        }
 
-        let Some(new_tree) = new_tree else {
-            return;
+        let shift = |offset: usize| {
+            if offset >= edit.old_end_byte {
+                (offset as isize + changed_len) as usize
+            } else {
+                offset.min(edit.new_end_byte)
+            }
         };
 
-        let mut changed_ranges = None;
-        if let Some(old_tree) = &self.old_tree {
-            changed_ranges = Some(new_tree.changed_ranges(old_tree));
-        }
+        // Remove the styles of the edited tokens, and move the styles after the edit.
+        // NOTE: 10K lines, about 35ms
+        self.cache = std::mem::take(&mut self.cache)
+            .into_iter()
+            .filter_map(|(start, (range, highlight_name))| {
+                if range.end <= edit.start_byte {
+                    Some((start, (range, highlight_name)))
+                } else if range.start >= edit.old_end_byte {
+                    let range = shift(range.start)..shift(range.end);
+                    Some((range.start, (range, highlight_name)))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        self.semantic_tokens.retain_mut(|token| {
+            if token.range.end <= edit.start_byte {
+                true
+            } else if token.range.start >= edit.old_end_byte {
+                token.range = shift(token.range.start)..shift(token.range.end);
+                true
+            } else {
+                false
+            }
+        });
 
-        // Update state
-        self.old_tree = Some(new_tree);
+        self.edited_range = Some(match self.edited_range.take() {
+            Some(range) => {
+                shift(range.start).min(edit.start_byte)..shift(range.end).max(edit.new_end_byte)
+            }
+            None => edit.start_byte..edit.new_end_byte,
+        });
         self.text = full_text;
+    }
+
+    /// Returns a snapshot to parse the text and build the styles, that can be highlighted
+    /// in the background.
+    ///
+    /// Returns `None` if the styles are up to date, or the snapshot of the current text
+    /// is already taken.
+    pub fn snapshot(&mut self) -> Option<HighlightSnapshot> {
+        if self.highlighted_version == self.version || self.snapshot_version == self.version {
+            return None;
+        }
 
-        // let measure = Measure::new("build_styles");
-        self.build_styles(changed_ranges, changed_len, cx);
-        // measure.end();
+        let language = self.ts_language.clone()?;
+        self.snapshot_version = self.version;
+        Some(HighlightSnapshot {
+            version: self.version,
+            text: self.text.clone(),
+            language,
+            queries: self.queries.clone(),
+            old_tree: self.old_tree.clone(),
+            edited_range: self.edited_range.clone(),
+            parsed: None,
+        })
     }
 
-    /// NOTE: 10K lines, about 180ms
-    fn build_styles(
-        &mut self,
-        changed_ranges: Option<impl ExactSizeIterator<Item = tree_sitter::Range>>,
-        changed_len: isize,
-        _: &mut App,
-    ) {
+    /// Apply the result of [`HighlightSnapshot::highlight`].
+    ///
+    /// Returns false if the result is outdated, the text has been changed after the snapshot.
+    pub fn apply(&mut self, result: HighlightResult) -> bool {
+        if result.version != self.version {
+            return false;
+        }
+
+        let range = &result.range;
+        if !range.is_empty() {
+            self.cache.retain(|_, (node_range, _)| {
+                node_range.start >= range.end || node_range.end <= range.start
+            });
+        }
+        for (node_range, highlight_name) in result.styles {
+            self.cache
+                .insert(node_range.start, (node_range, highlight_name));
+        }
+
+        if result.is_complete() {
+            self.highlighted_version = self.version;
+            self.edited_range = None;
+        } else {
+            // Keep the rest of the changes to rebuild, if the text is edited before it is done.
+            self.edited_range = Some(result.changed_range);
+        }
+        self.old_tree = Some(result.tree);
+        self.parsed_version = result.version;
+        self.foldable_ranges = None;
+        true
+    }
+
+    /// Returns true if the syntax tree is parsed for the current text.
+    ///
+    /// The large text is parsed in the background after [`Self::edit`], so the tree is out of
+    /// date until the result is applied by [`Self::apply`].
+    pub fn is_parsed(&self) -> bool {
+        self.old_tree.is_some() && self.parsed_version == self.version
+    }
+
+    /// Returns the syntax tree if it is parsed for the current text, see [`Self::is_parsed`].
+    fn parsed_tree(&self) -> Option<&Tree> {
+        if self.parsed_version != self.version {
+            return None;
+        }
+        self.old_tree.as_ref()
+    }
+
+    /// Set the semantic tokens to layer on top of the tree-sitter highlights, e.g.: the
+    /// semantic tokens from the language server.
+    ///
+    /// The tokens are moved by the edits of the text, and the edited tokens are removed,
+    /// until the tokens are set again.
+    pub fn set_semantic_tokens(&mut self, mut tokens: Vec<SemanticToken>) {
+        tokens.retain(|token| !token.range.is_empty() && token.range.end <= self.text.len());
+        tokens.sort_by_key(|token| token.range.start);
+        self.semantic_tokens = tokens;
+    }
+
+    /// Returns the semantic tokens, sorted by the range.
+    pub fn semantic_tokens(&self) -> &[SemanticToken] {
+        &self.semantic_tokens
+    }
+
+    /// Returns the foldable line ranges (zero based) from the syntax tree, sorted by start line.
+    ///
+    /// The `start` of the range is the line of the fold header, that keeps visible when folded,
+    /// and the lines `start + 1..end` can be hidden. Such as functions, blocks, objects.
+    ///
+    /// Until the tree is parsed for the edited text (see [`Self::is_parsed`]), the ranges are
+    /// from the tree shifted by the edits, to keep the folds while parsing in the background.
+    pub fn foldable_ranges(&mut self) -> Vec<Range<usize>> {
+        if let Some(ranges) = &self.foldable_ranges {
+            return ranges.clone();
+        }
+
         let Some(tree) = &self.old_tree else {
-            return;
+            return vec![];
         };
 
-        let Some(query) = &self.query else {
-            return;
+        // NOTE: Not use the `Node::start_position`, the position is not kept by the `InputEdit`.
+        let line_starts = std::iter::once(0)
+            .chain(self.text.match_indices('\n').map(|(ix, _)| ix + 1))
+            .collect::<Vec<_>>();
+        let line_for_offset =
+            |offset: usize| line_starts.partition_point(|start| *start <= offset) - 1;
+
+        // The key is the start line, keep the biggest range for each start line.
+        let mut ranges: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut cursor = tree.walk();
+        'walk: loop {
+            let node = cursor.node();
+            if node.is_named()
+                && node.end_byte() > node.start_byte()
+                && (is_bracket_delimited(&node)
+                    || FOLDABLE_KIND_SUFFIXES
+                        .iter()
+                        .any(|suffix| node.kind().ends_with(suffix)))
+            {
+                let start = line_for_offset(node.start_byte());
+                let last_offset = node.end_byte() - 1;
+                let last_line = line_for_offset(last_offset);
+                // Keep the line visible if it only has the closing bracket, e.g.: `}`.
+                let closing_line = matches!(
+                    self.text.as_bytes().get(last_offset),
+                    Some(b'}' | b']' | b')')
+                ) && self.text[line_starts[last_line]..last_offset]
+                    .trim()
+                    .is_empty();
+                let end = if closing_line { last_line } else { last_line + 1 };
+                if end > start + 1 {
+                    let entry = ranges.entry(start).or_insert(end);
+                    *entry = (*entry).max(end);
+                }
+            }
+
+            if cursor.goto_first_child() || cursor.goto_next_sibling() {
+                continue;
+            }
+            loop {
+                if !cursor.goto_parent() {
+                    break 'walk;
+                }
+                if cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+
+        let ranges = ranges
+            .into_iter()
+            .map(|(start, end)| start..end)
+            .collect::<Vec<_>>();
+        self.foldable_ranges = Some(ranges.clone());
+        ranges
+    }
+
+    /// Returns the line comment token of the language, e.g.: `//`.
+    pub fn line_comment(&self) -> Option<&SharedString> {
+        self.line_comment.as_ref()
+    }
+
+    /// Returns the block comment tokens of the language, e.g.: `/*` and `*/`.
+    pub fn block_comment(&self) -> Option<&(SharedString, SharedString)> {
+        self.block_comment.as_ref()
+    }
+
+    /// Returns the indent regions of the syntax tree, `None` if the tree is not parsed for the
+    /// current text, see [`Self::is_parsed`].
+    ///
+    /// The query runs once, so build them once to get the levels of many lines.
+    pub fn indent_regions(&self) -> Option<IndentRegions> {
+        let tree = self.parsed_tree()?;
+        let query = self.indent_query.as_ref()?;
+        indent_regions(query, tree, &self.text)
+    }
+
+    /// Returns the offset of the bracket that matches the bracket at the given offset.
+    ///
+    /// The brackets are matched by the syntax tree, so the brackets in strings or comments
+    /// are ignored. Returns `None` if the offset is not a bracket token, or the tree is not
+    /// parsed for the current text, see [`Self::is_parsed`].
+    pub fn matching_bracket(&self, offset: usize) -> Option<usize> {
+        let tree = self.parsed_tree()?;
+        let node = tree
+            .root_node()
+            .descendant_for_byte_range(offset, offset + 1)?;
+        if node.is_named() || node.start_byte() != offset || node.end_byte() != offset + 1 {
+            return None;
+        }
+
+        let kind = node.kind();
+        let (open, close, forward) = match kind {
+            "(" => ("(", ")", true),
+            "[" => ("[", "]", true),
+            "{" => ("{", "}", true),
+            ")" => ("(", ")", false),
+            "]" => ("[", "]", false),
+            "}" => ("{", "}", false),
+            _ => return None,
         };
 
-        let source = self.text.as_bytes();
-        let mut query_cursor = QueryCursor::new();
-        let mut root_node = tree.root_node();
+        let parent = node.parent()?;
+        let mut cursor = parent.walk();
+        let siblings = parent.children(&mut cursor).collect::<Vec<_>>();
+        let ix = siblings
+            .iter()
+            .position(|sibling| sibling.id() == node.id())?;
+        let candidates: Box<dyn Iterator<Item = &Node>> = if forward {
+            Box::new(siblings[ix..].iter())
+        } else {
+            Box::new(siblings[..=ix].iter().rev())
+        };
 
-        // Incremental parsing to only update changed ranges.
-        let mut last_end = 0;
-        if let Some(changed_ranges) = changed_ranges {
-            let mut total_range = 0..0;
-            for change_range in changed_ranges {
-                if total_range.start == 0 {
-                    total_range.start = change_range.start_byte;
-                }
-                if total_range.end == 0 {
-                    total_range.end = change_range.end_byte;
+        let (same, other) = if forward {
+            (open, close)
+        } else {
+            (close, open)
+        };
+        let mut depth = 0;
+        for sibling in candidates {
+            if sibling.kind() == same {
+                depth += 1;
+            } else if sibling.kind() == other {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(sibling.start_byte());
                 }
             }
+        }
+
+        None
+    }
+
+    /// The argument `range` is the range of the line in the text.
+    ///
+    /// Returns `range` is the range in the line.
+    pub fn styles(
+        &self,
+        range: &Range<usize>,
+        theme: &HighlightTheme,
+    ) -> Vec<(Range<usize>, HighlightStyle)> {
+        let mut styles = vec![];
+        let start_offset = range.start;
+        let mut last_range = start_offset..start_offset;
 
-            if total_range.len() == 0 {
-                return;
+        // NOTE: the ranges in the cache may have duplicates, so we need to merge them.
+        for (_, (node_range, name)) in self.cache.range(range.start..) {
+            // TODO: If break, the `comment.doc` will not work.
+            // Ref: https://github.com/longbridge/gpui-component/pull/904/commits/d8f886939d3b472f228c1ce72154a951e98f32c5
+            if node_range.end > range.end {
+                break;
             }
 
-            if let Some(node) =
-                root_node.descendant_for_byte_range(total_range.start, total_range.end)
-            {
-                root_node = node;
+            // let range_in_line = node_range.start..node_range.end;
+
+            // Ensure every range is connected.
+            if last_range.end < node_range.start {
+                styles.push((last_range.end..node_range.start, HighlightStyle::default()));
             }
 
-            let byte_range = root_node.byte_range();
+            let style = theme.style(&name).unwrap_or_default();
 
-            // let measure = Measure::new("update cache to change range offset");
+            styles.push((node_range.clone(), style));
+            last_range = node_range.clone();
+        }
 
-            // FIXME: If we delete 1 char in a node, that node will not highlighted.
+        // If the matched styles is empty, return a default range.
+        if styles.len() == 0 {
+            styles.push((start_offset..range.end, HighlightStyle::default()));
+        } else if last_range.end < range.end {
+            // Ensure the last range is connected to the end of the line.
+            styles.push((last_range.end..range.end, HighlightStyle::default()));
+        }
 
-            // Remove the cache entries that are range is intersecting with the byte_range.
-            self.cache.retain(|_, (range, _)| {
-                if range.start < byte_range.end && range.end > byte_range.start {
-                    // Remove the item if it is intersecting with the byte_range.
-                    false
-                } else {
-                    // Keep the item if it is not intersecting with the byte_range.
-                    true
+        let ix = self
+            .semantic_tokens
+            .partition_point(|token| token.range.end <= range.start);
+        let semantic_styles = self.semantic_tokens[ix..]
+            .iter()
+            .take_while(|token| token.range.start < range.end)
+            .filter_map(|token| Some((token.range.clone(), theme.semantic_style(token)?)))
+            .collect::<Vec<_>>();
+
+        overlay_styles(styles, &semantic_styles)
+    }
+}
+
+impl HighlightResult {
+    /// Returns true if all the changed text is highlighted.
+    pub fn is_complete(&self) -> bool {
+        self.range == self.changed_range
+    }
+}
+
+impl HighlightSnapshot {
+    /// Parse the text (only once) and build the styles of the changed text in the `range`,
+    /// the entire changed text if `range` is `None`.
+    ///
+    /// This is slow for the large text, run it in the background executor, and highlight the
+    /// visible range first to show the styles as soon as possible.
+    ///
+    /// Returns `None` if failed to parse the text.
+    pub fn highlight(&mut self, range: Option<Range<usize>>) -> Option<HighlightResult> {
+        let (tree, changed_range) = match &self.parsed {
+            Some(parsed) => parsed.clone(),
+            None => {
+                let parsed = self.parse()?;
+                self.parsed = Some(parsed.clone());
+                parsed
+            }
+        };
+
+        let range = match range {
+            Some(range) => {
+                let start = range.start.max(changed_range.start);
+                start..range.end.min(changed_range.end).max(start)
+            }
+            None => changed_range.clone(),
+        };
+
+        // NOTE: 10K lines, about 180ms
+        let styles = match &self.queries {
+            Some(queries) if !range.is_empty() => {
+                queries.build_styles(&tree, self.text.as_bytes(), &range)
+            }
+            _ => vec![],
+        };
+
+        Some(HighlightResult {
+            version: self.version,
+            tree,
+            changed_range,
+            range,
+            styles,
+        })
+    }
+
+    /// Parse the text, returns the tree and the range that the styles need to rebuild.
+    fn parse(&self) -> Option<(Tree, Range<usize>)> {
+        let mut parser = Parser::new();
+        parser.set_language(&self.language).ok()?;
+        // NOTE: 10K lines, about 4.5ms
+        let tree = parser.parse(self.text.as_bytes(), self.old_tree.as_ref())?;
+
+        let Some(old_tree) = &self.old_tree else {
+            return Some((tree, 0..self.text.len()));
+        };
+
+        // The changed ranges only contain the changes of the syntax, so merge the edited range
+        // to rebuild the edited tokens, e.g.: delete 1 char of an identifier.
+        let mut changed_range = self.edited_range.clone();
+        for range in tree.changed_ranges(old_tree) {
+            changed_range = Some(match changed_range {
+                Some(changed) => {
+                    changed.start.min(range.start_byte)..changed.end.max(range.end_byte)
                 }
+                None => range.start_byte..range.end_byte,
             });
+        }
+        let Some(changed_range) = changed_range else {
+            return Some((tree, 0..0));
+        };
 
-            // Apply changed_len to reorder the cache to move the range offset
-            let mut old_cache: BTreeMap<usize, (Range<usize>, String)> = BTreeMap::new();
-            std::mem::swap(&mut self.cache, &mut old_cache);
+        // Rebuild the styles of the entire node that contains the changes.
+        let changed_range = tree
+            .root_node()
+            .descendant_for_byte_range(changed_range.start, changed_range.end)
+            .map_or(changed_range, |node| node.byte_range());
 
-            // NOTE: 10K lines, about 35ms
-            for (start, (old_range, highlight_name)) in old_cache.into_iter() {
-                if old_range.end >= byte_range.start {
-                    let new_range = Range {
-                        start: (old_range.start as isize + changed_len) as usize,
-                        end: (old_range.end as isize + changed_len) as usize,
-                    };
+        Some((tree, changed_range))
+    }
+}
 
-                    self.cache
-                        .insert(new_range.start, (new_range, highlight_name));
-                } else {
-                    self.cache.insert(start, (old_range, highlight_name));
-                }
+impl HighlightQueries {
+    fn new(
+        language_name: SharedString,
+        query: Query,
+        injection_queries: HashMap<&'static str, Query>,
+    ) -> Self {
+        let mut injection_content_capture_index = None;
+        let mut injection_language_capture_index = None;
+        for (i, name) in query.capture_names().iter().enumerate() {
+            match *name {
+                "injection.content" => injection_content_capture_index = Some(i as u32),
+                "injection.language" => injection_language_capture_index = Some(i as u32),
+                _ => {}
             }
-            // measure.end();
-        } else {
-            self.cache.clear();
         }
 
-        let mut matches = query_cursor.matches(&query, root_node, source);
+        Self {
+            language_name,
+            query,
+            injection_queries,
+            injection_content_capture_index,
+            injection_language_capture_index,
+        }
+    }
+
+    /// Build the styles of the tokens in the `range`, the range of the style is the offset
+    /// in the entire text.
+    fn build_styles(
+        &self,
+        tree: &Tree,
+        source: &[u8],
+        range: &Range<usize>,
+    ) -> Vec<(Range<usize>, String)> {
+        let query = &self.query;
+        let mut styles = vec![];
+        let mut query_cursor = QueryCursor::new();
+        query_cursor.set_byte_range(range.clone());
+        let mut matches = query_cursor.matches(query, tree.root_node(), source);
 
         // TODO: Merge duplicate ranges.
 
+        let mut last_end = 0;
         while let Some(m) = matches.next() {
             // Ref:
             // https://github.com/tree-sitter/tree-sitter/blob/460118b4c82318b083b4d527c9c750426730f9c0/highlight/src/lib.rs#L556
@@ -364,11 +899,7 @@ impl SyntaxHighlighter {
                         continue;
                     }
 
-                    let styles = self.handle_injection(&language_name, content_node, source);
-                    for (node_range, highlight_name) in styles {
-                        self.cache
-                            .insert(node_range.start, (node_range, highlight_name.to_string()));
-                    }
+                    styles.extend(self.handle_injection(&language_name, content_node, source));
                     last_end = content_node.end_byte();
                 }
 
@@ -384,17 +915,12 @@ impl SyntaxHighlighter {
                 let highlight_name = query.capture_names()[cap.index as usize];
                 let node_range: Range<usize> = node.start_byte()..node.end_byte();
 
-                self.cache.insert(
-                    node_range.start,
-                    (node_range.clone(), highlight_name.to_string()),
-                );
+                styles.push((node_range.clone(), highlight_name.to_string()));
                 last_end = node_range.end;
             }
         }
 
-        // for item in self.cache.iter() {
-        //     println!("---------- item: {:?}", item);
-        // }
+        styles
     }
 
     /// TODO: Use incremental parsing to handle the injection.
@@ -416,10 +942,10 @@ impl SyntaxHighlighter {
         if content.is_empty() {
             return cache;
         };
-        let Some(lang) = super::Language::from_str(injection_language) else {
+        let Some(lang_config) = Language::from_str(injection_language).map(|lang| lang.config())
+        else {
             return cache;
         };
-        let lang_config = lang.config();
         let mut parser = Parser::new();
         if parser.set_language(&lang_config.language).is_err() {
             return cache;
@@ -463,7 +989,7 @@ impl SyntaxHighlighter {
     /// - `content_node`: The content node of the injection.
     /// - `include_children`: Whether to include the children of the content node.
     fn injection_for_match<'a>(
-        &self,
+        &'a self,
         parent_name: Option<&'a str>,
         query: &'a Query,
         query_match: &QueryMatch<'a, 'a>,
@@ -505,7 +1031,7 @@ impl SyntaxHighlighter {
                 // layer.
                 "injection.self" => {
                     if language_name.is_none() {
-                        language_name = Some(self.language_name);
+                        language_name = Some(self.language_name.as_ref());
                     }
                 }
 
@@ -529,50 +1055,262 @@ impl SyntaxHighlighter {
 
         (language_name, content_node, include_children)
     }
+}
 
-    /// The argument `range` is the range of the line in the text.
-    ///
-    /// Returns `range` is the range in the line.
-    pub fn styles(
-        &self,
-        range: &Range<usize>,
-        theme: &HighlightTheme,
-    ) -> Vec<(Range<usize>, HighlightStyle)> {
-        let mut styles = vec![];
-        let start_offset = range.start;
-        let mut last_range = start_offset..start_offset;
+/// Returns true if the node is wrapped by brackets, e.g.: `{ ... }`, `[ ... ]`, `( ... )`.
+fn is_bracket_delimited(node: &Node) -> bool {
+    let count = node.child_count();
+    if count < 2 {
+        return false;
+    }
 
-        // NOTE: the ranges in the cache may have duplicates, so we need to merge them.
-        for (_, (node_range, name)) in self.cache.range(range.start..) {
-            // TODO: If break, the `comment.doc` will not work.
-            // Ref: https://github.com/longbridge/gpui-component/pull/904/commits/d8f886939d3b472f228c1ce72154a951e98f32c5
-            if node_range.end > range.end {
-                break;
-            }
+    let (Some(first), Some(last)) = (node.child(0), node.child(count - 1)) else {
+        return false;
+    };
 
-            // let range_in_line = node_range.start..node_range.end;
+    matches!((first.kind(), last.kind()), ("{", "}") | ("[", "]") | ("(", ")"))
+}
 
-            // Ensure every range is connected.
-            if last_range.end < node_range.start {
-                styles.push((last_range.end..node_range.start, HighlightStyle::default()));
-            }
+/// Return the name, the builtin language and the config of the language by name.
+///
+/// The languages registered in the `registry` take precedence over the builtin languages.
+fn language_config(
+    name: &str,
+    registry: Option<&LanguageRegistry>,
+) -> Option<(SharedString, Option<Language>, LanguageConfig)> {
+    let language = Language::from_str(name);
+    let name = language.map_or(name, |language| language.name());
+    if let Some((name, config)) = registry.and_then(|registry| registry.registered_language(name)) {
+        return Some((name, language, config));
+    }
 
-            let style = theme.style(&name).unwrap_or_default();
+    language.map(|language| (language.name().into(), Some(language), language.config()))
+}
 
-            styles.push((node_range.clone(), style));
-            last_range = node_range.clone();
+/// Build the indent query with the bracket patterns and the indents query of the language.
+///
+/// Only the bracket patterns are used if the indents query of the language is invalid.
+fn build_indent_query(config: &LanguageConfig) -> Option<Query> {
+    let mut source = String::new();
+    for pattern in BRACKET_INDENT_PATTERNS {
+        if Query::new(&config.language, pattern).is_ok() {
+            source.push_str(pattern);
+            source.push('\n');
         }
+    }
 
-        // If the matched styles is empty, return a default range.
-        if styles.len() == 0 {
-            return vec![(start_offset..range.end, HighlightStyle::default())];
+    match Query::new(&config.language, &format!("{}{}", source, config.indents)) {
+        Ok(query) => Some(query),
+        Err(err) => {
+            tracing::error!("failed to build indents query: {:?}", err);
+            Query::new(&config.language, &source).ok()
         }
+    }
+}
 
-        // Ensure the last range is connected to the end of the line.
-        if last_range.end < range.end {
-            styles.push((last_range.end..range.end, HighlightStyle::default()));
+/// The regions of the text indented by the indents query, see
+/// [`SyntaxHighlighter::indent_regions`].
+pub struct IndentRegions {
+    /// The `(start line, start, end, inclusive end)` of the regions, sorted by the start.
+    regions: Vec<(usize, usize, usize, bool)>,
+}
+
+impl IndentRegions {
+    /// Returns the indent level of a line.
+    ///
+    /// - `line_start` is the offset of the line start.
+    /// - `content_start` is the offset of the first non-whitespace char in the line.
+    ///
+    /// The level is the count of the lines that have a region starts and contains the line.
+    /// The line starts with the `@end` token (e.g.: `}`) is not indented by the region.
+    pub fn level(&self, line_start: usize, content_start: usize) -> usize {
+        let mut level = 0;
+        let mut last_line = None;
+        for &(line, start, end, inclusive) in &self.regions {
+            if start >= line_start {
+                break;
+            }
+            let contains = end > content_start || (inclusive && end == content_start);
+            if contains && last_line != Some(line) {
+                level += 1;
+                last_line = Some(line);
+            }
         }
+        level
+    }
+}
 
-        styles
+/// Build the indent regions by the matches of the indents query.
+///
+/// A region is from the `@start` capture (or the `@indent` node) to the `@end` capture (or the
+/// end of the `@indent` node). The first `@end` wins if a node has more than one, e.g.: the
+/// `else` of an `if` that ends with `end`.
+///
+/// The region without an `@end` includes its end, to keep the indent when typing a new line
+/// right after the last statement of a block.
+fn indent_regions(query: &Query, tree: &Tree, text: &str) -> Option<IndentRegions> {
+    let indent_ix = query.capture_index_for_name("indent")?;
+    let start_ix = query.capture_index_for_name("start");
+    let end_ix = query.capture_index_for_name("end");
+
+    // The `(start, end)` of the regions by the range of the `@indent` node.
+    let mut nodes: BTreeMap<(usize, usize), (usize, Option<usize>)> = BTreeMap::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, tree.root_node(), text.as_bytes());
+    while let Some(query_match) = matches.next() {
+        let mut node = None;
+        let mut start = None;
+        let mut end = None;
+        for capture in query_match.captures {
+            if capture.index == indent_ix {
+                node = Some(capture.node.byte_range());
+            } else if Some(capture.index) == start_ix {
+                start = Some(capture.node.start_byte());
+            } else if Some(capture.index) == end_ix {
+                end = Some(capture.node.start_byte());
+            }
+        }
+
+        let Some(node) = node else {
+            continue;
+        };
+        let start = start.unwrap_or(node.start);
+        let region = nodes.entry((node.start, node.end)).or_insert((start, end));
+        region.0 = region.0.min(start);
+        region.1 = match (region.1, end) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    let mut regions = nodes
+        .into_iter()
+        .map(|((_, node_end), (start, end))| (start, end.unwrap_or(node_end), end.is_none()))
+        .collect::<Vec<_>>();
+    regions.sort();
+
+    let mut line = 0;
+    let mut offset = 0;
+    let regions = regions
+        .into_iter()
+        .map(|(start, end, inclusive)| {
+            line += text[offset..start].matches('\n').count();
+            offset = start;
+            (line, start, end, inclusive)
+        })
+        .collect();
+    Some(IndentRegions { regions })
+}
+
+#[cfg(test)]
+mod tests {
+    use tree_sitter::Parser;
+
+    use super::{SyntaxHighlighter, build_indent_query, indent_regions};
+    use crate::highlighter::Language;
+
+    /// Return the highlight name of the style at the range.
+    fn style_at(highlighter: &SyntaxHighlighter, range: std::ops::Range<usize>) -> Option<&str> {
+        highlighter
+            .cache
+            .get(&range.start)
+            .filter(|(node_range, _)| *node_range == range)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Return the indent levels of the non-blank lines.
+    fn levels(language: Language, text: &str) -> Vec<usize> {
+        let config = language.config();
+        let mut parser = Parser::new();
+        parser.set_language(&config.language).unwrap();
+        let tree = parser.parse(text, None).unwrap();
+        let query = build_indent_query(&config).unwrap();
+        let regions = indent_regions(&query, &tree, text).unwrap();
+
+        let mut line_start = 0;
+        let mut levels = vec![];
+        for line in text.split('\n') {
+            if !line.trim().is_empty() {
+                let content_start = line_start + line.len() - line.trim_start().len();
+                levels.push(regions.level(line_start, content_start));
+            }
+            line_start += line.len() + 1;
+        }
+        levels
+    }
+
+    #[test]
+    fn test_indent_brackets() {
+        let text = "fn main() {\n    let a = [\n        1,\n    ];\n}\n";
+        assert_eq!(levels(Language::Rust, text), vec![0, 1, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_indent_python() {
+        let text = "def f(x):\n    if x:\n        return 1\n    else:\n        return 2\ny = 3\n";
+        assert_eq!(levels(Language::Python, text), vec![0, 1, 2, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_highlight_snapshot() {
+        let mut highlighter = SyntaxHighlighter::new("rust");
+        let text = "fn main() {}";
+        highlighter.edit(&(0..0), text.into(), text);
+        assert!(!highlighter.is_parsed());
+
+        let mut snapshot = highlighter.snapshot().unwrap();
+        // The snapshot of the current text is already taken.
+        assert!(highlighter.snapshot().is_none());
+        let result = snapshot.highlight(None).unwrap();
+        assert!(result.is_complete());
+        assert!(highlighter.apply(result));
+        assert!(highlighter.is_parsed());
+        assert_eq!(style_at(&highlighter, 0..2), Some("keyword"));
+        // The styles are up to date.
+        assert!(highlighter.snapshot().is_none());
+    }
+
+    #[test]
+    fn test_highlight_snapshot_outdated() {
+        let mut highlighter = SyntaxHighlighter::new("rust");
+        let text = "fn main() {}";
+        highlighter.edit(&(0..0), text.into(), text);
+        let result = highlighter.snapshot().unwrap().highlight(None).unwrap();
+        assert!(highlighter.apply(result));
+
+        // Insert `let a = 1;` into the braces.
+        let text = "fn main() { let a = 1; }";
+        highlighter.edit(&(11..11), text.into(), " let a = 1; ");
+        assert_eq!(highlighter.version, 2);
+        // The tree is only shifted by the edit, so it's not used for the indents and brackets.
+        assert!(!highlighter.is_parsed());
+        assert!(highlighter.indent_regions().is_none());
+        assert_eq!(highlighter.matching_bracket(10), None);
+        assert_eq!(style_at(&highlighter, 0..2), Some("keyword"));
+
+        // Edit again before the result of the snapshot is applied.
+        let mut snapshot = highlighter.snapshot().unwrap();
+        let text = "fn main() { let a = 12; }";
+        highlighter.edit(&(21..21), text.into(), "2");
+        let result = snapshot.highlight(None).unwrap();
+        assert!(!highlighter.apply(result));
+        assert!(!highlighter.is_parsed());
+        assert_eq!(style_at(&highlighter, 12..15), None);
+
+        // Highlight the visible range first, then the rest by the same snapshot.
+        let mut snapshot = highlighter.snapshot().unwrap();
+        let result = snapshot.highlight(Some(0..5)).unwrap();
+        assert!(!result.is_complete());
+        assert!(highlighter.apply(result));
+        assert!(highlighter.snapshot().is_none());
+        let result = snapshot.highlight(None).unwrap();
+        assert!(result.is_complete());
+        assert!(highlighter.apply(result));
+
+        assert!(highlighter.is_parsed());
+        assert_eq!(style_at(&highlighter, 12..15), Some("keyword"));
+        assert_eq!(style_at(&highlighter, 20..22), Some("constant.builtin"));
+        assert_eq!(highlighter.matching_bracket(10), Some(24));
+        assert!(highlighter.snapshot().is_none());
     }
 }
@@ -1,5 +1,4 @@
 use gpui::SharedString;
-use tree_sitter::Query;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, enum_iterator::Sequence)]
 pub enum Language {
@@ -42,6 +41,19 @@ pub struct LanguageConfig {
     pub highlights: SharedString,
     pub injections: SharedString,
     pub locals: SharedString,
+    /// The indents query, the `@indent` capture is the node to indent the lines inside,
+    /// the optional `@start` capture is the node the indent starts at, e.g.: the `:` before a
+    /// Python block, and the optional `@end` capture is the token that ends the indent,
+    /// e.g.: `}`.
+    ///
+    /// The brackets `{}`, `[]`, `()` are always indented.
+    pub indents: SharedString,
+    /// The line comment token, e.g.: `//`.
+    pub line_comment: Option<SharedString>,
+    /// The block comment tokens, e.g.: `/*` and `*/`.
+    pub block_comment: Option<(SharedString, SharedString)>,
+    /// The file extensions of the language, e.g.: `kt`, `kts`.
+    pub extensions: Vec<SharedString>,
 }
 
 impl LanguageConfig {
@@ -56,8 +68,43 @@ impl LanguageConfig {
             highlights: SharedString::from(highlights.to_string()),
             injections: SharedString::from(injections.to_string()),
             locals: SharedString::from(locals.to_string()),
+            indents: SharedString::default(),
+            line_comment: None,
+            block_comment: None,
+            extensions: vec![],
         }
     }
+
+    /// Set the indents query of the language.
+    pub fn indents(mut self, indents: &str) -> Self {
+        self.indents = SharedString::from(indents.to_string());
+        self
+    }
+
+    /// Set the line comment token of the language, e.g.: `//`.
+    pub fn line_comment(mut self, token: impl Into<SharedString>) -> Self {
+        self.line_comment = Some(token.into());
+        self
+    }
+
+    /// Set the block comment tokens of the language, e.g.: `/*` and `*/`.
+    pub fn block_comment(
+        mut self,
+        start: impl Into<SharedString>,
+        end: impl Into<SharedString>,
+    ) -> Self {
+        self.block_comment = Some((start.into(), end.into()));
+        self
+    }
+
+    /// Set the file extensions of the language, without the leading `.`.
+    pub fn extensions(
+        mut self,
+        extensions: impl IntoIterator<Item = impl Into<SharedString>>,
+    ) -> Self {
+        self.extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
 }
 impl From<Language> for SharedString {
     fn from(language: Language) -> Self {
@@ -115,34 +162,106 @@ impl Language {
             "yaml" | "yml" => Some(Self::Yaml),
             "rust" | "rs" => Some(Self::Rust),
             "go" => Some(Self::Go),
-            "c" => Some(Self::C),
-            "cpp" | "c++" => Some(Self::Cpp),
-            "javascript" | "js" => Some(Self::JavaScript),
+            "c" | "h" => Some(Self::C),
+            "cpp" | "c++" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => Some(Self::Cpp),
+            "javascript" | "js" | "mjs" | "cjs" | "jsx" => Some(Self::JavaScript),
             "zig" => Some(Self::Zig),
             "java" => Some(Self::Java),
-            "python" | "py" => Some(Self::Python),
-            "ruby" | "rb" => Some(Self::Ruby),
-            "bash" | "sh" => Some(Self::Bash),
-            "html" => Some(Self::Html),
+            "python" | "py" | "pyi" => Some(Self::Python),
+            "ruby" | "rb" | "rake" | "gemspec" => Some(Self::Ruby),
+            "bash" | "sh" | "zsh" => Some(Self::Bash),
+            "html" | "htm" => Some(Self::Html),
             "css" | "scss" => Some(Self::Css),
             "swift" => Some(Self::Swift),
             "scala" => Some(Self::Scala),
             "sql" => Some(Self::Sql),
             "csharp" | "cs" => Some(Self::CSharp),
-            "graphql" => Some(Self::GraphQL),
+            "graphql" | "gql" => Some(Self::GraphQL),
             "proto" | "protobuf" => Some(Self::Proto),
-            "make" | "makefile" => Some(Self::Make),
+            "make" | "makefile" | "mk" => Some(Self::Make),
             "cmake" => Some(Self::CMake),
-            "typescript" | "ts" => Some(Self::TypeScript),
+            "typescript" | "ts" | "mts" | "cts" => Some(Self::TypeScript),
             "tsx" => Some(Self::Tsx),
-            "diff" => Some(Self::Diff),
-            "elixir" | "ex" => Some(Self::Elixir),
+            "diff" | "patch" => Some(Self::Diff),
+            "elixir" | "ex" | "exs" => Some(Self::Elixir),
             "erb" => Some(Self::Erb),
             "ejs" => Some(Self::Ejs),
             _ => None,
         }
     }
 
+    /// Return the `(open, close)` pairs to auto-close on typing in the editor.
+    pub fn auto_close_pairs(&self) -> &'static [(char, char)] {
+        const BRACKETS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"')];
+        const QUOTES: &[(char, char)] =
+            &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"'), ('\'', '\'')];
+        const BACKTICKS: &[(char, char)] = &[
+            ('(', ')'),
+            ('[', ']'),
+            ('{', '}'),
+            ('"', '"'),
+            ('\'', '\''),
+            ('`', '`'),
+        ];
+        const MARKDOWN: &[(char, char)] =
+            &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"'), ('`', '`')];
+
+        match self {
+            Self::Diff => &[],
+            // The `'` is used for lifetimes in Rust.
+            Self::Rust | Self::Json | Self::Proto | Self::GraphQL | Self::Make | Self::CMake => {
+                BRACKETS
+            }
+            Self::Markdown | Self::MarkdownInline => MARKDOWN,
+            Self::JavaScript
+            | Self::TypeScript
+            | Self::Tsx
+            | Self::Go
+            | Self::Bash
+            | Self::Sql
+            | Self::Ejs => BACKTICKS,
+            _ => QUOTES,
+        }
+    }
+
+    /// Return true to indent the next line after a line ends with `:`, e.g.: Python, YAML.
+    pub fn indents_after_colon(&self) -> bool {
+        matches!(self, Self::Python | Self::Yaml)
+    }
+
+    /// Return the line comment token and the block comment tokens.
+    fn comment_tokens(&self) -> (Option<&'static str>, Option<(&'static str, &'static str)>) {
+        match self {
+            Self::Rust
+            | Self::Go
+            | Self::C
+            | Self::Cpp
+            | Self::JavaScript
+            | Self::Java
+            | Self::Swift
+            | Self::Scala
+            | Self::CSharp
+            | Self::Proto
+            | Self::TypeScript
+            | Self::Tsx => (Some("//"), Some(("/*", "*/"))),
+            Self::Zig => (Some("//"), None),
+            Self::Python
+            | Self::Ruby
+            | Self::Bash
+            | Self::Toml
+            | Self::Yaml
+            | Self::GraphQL
+            | Self::Make
+            | Self::CMake
+            | Self::Elixir => (Some("#"), None),
+            Self::Sql => (Some("--"), Some(("/*", "*/"))),
+            Self::Css => (None, Some(("/*", "*/"))),
+            Self::Html | Self::Markdown | Self::MarkdownInline => (None, Some(("<!--", "-->"))),
+            Self::Erb | Self::Ejs => (None, Some(("<%#", "%>"))),
+            Self::Json | Self::Diff => (None, None),
+        }
+    }
+
     #[allow(unused)]
     pub(super) fn injection_languages(&self) -> Vec<Self> {
         match self {
@@ -154,11 +273,6 @@ impl Language {
         }
     }
 
-    pub(super) fn query(&self) -> Query {
-        let config = self.config();
-        Query::new(&config.language, &config.highlights).unwrap()
-    }
-
     /// Return the language info for the language.
     ///
     /// (language, query, injection, locals)
@@ -329,7 +443,35 @@ impl Language {
 
         let language = tree_sitter::Language::new(language);
 
-        LanguageConfig::new(language, query, injection, locals)
+        let mut config = LanguageConfig::new(language, query, injection, locals);
+        let (line_comment, block_comment) = self.comment_tokens();
+        if let Some(token) = line_comment {
+            config = config.line_comment(token);
+        }
+        if let Some((start, end)) = block_comment {
+            config = config.block_comment(start, end);
+        }
+        config.indents(self.indents_query())
+    }
+
+    /// Return the indents query, the brackets are indented without it.
+    fn indents_query(&self) -> &'static str {
+        match self {
+            Self::Yaml => include_str!("languages/yaml/indents.scm"),
+            Self::Rust => include_str!("languages/rust/indents.scm"),
+            Self::Go => include_str!("languages/go/indents.scm"),
+            Self::C | Self::Cpp => include_str!("languages/c/indents.scm"),
+            Self::JavaScript | Self::TypeScript | Self::Tsx => {
+                include_str!("languages/javascript/indents.scm")
+            }
+            Self::Java => include_str!("languages/java/indents.scm"),
+            Self::Python => include_str!("languages/python/indents.scm"),
+            Self::Ruby => include_str!("languages/ruby/indents.scm"),
+            Self::Bash => include_str!("languages/bash/indents.scm"),
+            Self::Html => include_str!("languages/html/indents.scm"),
+            Self::Elixir => include_str!("languages/elixir/indents.scm"),
+            _ => "",
+        }
     }
 }
 
@@ -358,4 +500,17 @@ mod tests {
         assert_eq!(Language::Erb.name(), "erb");
         assert_eq!(Language::Ejs.name(), "ejs");
     }
+
+    #[test]
+    fn test_indents_query() {
+        for language in Language::all() {
+            let config = language.config();
+            if config.indents.is_empty() {
+                continue;
+            }
+            if let Err(err) = tree_sitter::Query::new(&config.language, &config.indents) {
+                panic!("invalid indents query of {}: {:?}", language.name(), err);
+            }
+        }
+    }
 }
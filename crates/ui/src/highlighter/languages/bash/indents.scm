@@ -0,0 +1,9 @@
+; The `elif` and `else` are at the level of the `if`.
+(if_statement [(elif_clause) (else_clause) "fi"] @end) @indent
+(elif_clause) @indent
+(else_clause) @indent
+
+(do_group "done" @end) @indent
+
+(case_statement "esac" @end) @indent
+(case_item) @indent
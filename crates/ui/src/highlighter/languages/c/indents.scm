@@ -0,0 +1,13 @@
+; The statement without braces on the next line, e.g.: `if (x)\n  return;`.
+(if_statement
+  consequence: [
+    (expression_statement)
+    (return_statement)
+    (break_statement)
+    (continue_statement)
+  ] @indent) @start
+(for_statement body: (expression_statement) @indent) @start
+(while_statement body: (expression_statement) @indent) @start
+
+; The cases are at the level of the `switch`.
+(case_statement) @indent
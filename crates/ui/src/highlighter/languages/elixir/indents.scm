@@ -0,0 +1,9 @@
+; The `else`, `rescue`, `catch` and `after` are at the level of the `do`.
+(do_block [(else_block) (rescue_block) (catch_block) (after_block) "end"] @end) @indent
+(else_block) @indent
+(rescue_block) @indent
+(catch_block) @indent
+(after_block) @indent
+
+; The body of a clause on the next lines, e.g.: `x ->` in a `case`.
+(stab_clause) @indent
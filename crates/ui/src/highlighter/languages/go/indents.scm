@@ -0,0 +1,8 @@
+; The chained calls on the next lines.
+(selector_expression) @indent
+
+; The body of a case on the next lines.
+(expression_case) @indent
+(default_case) @indent
+(type_case) @indent
+(communication_case) @indent
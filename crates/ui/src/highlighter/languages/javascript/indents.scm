@@ -0,0 +1,14 @@
+; The chained calls on the next lines.
+(member_expression) @indent
+
+; The statement without braces on the next line, e.g.: `if (x)\n  return;`.
+(if_statement
+  consequence: [
+    (expression_statement)
+    (return_statement)
+    (break_statement)
+    (continue_statement)
+    (throw_statement)
+  ] @indent) @start
+(for_statement body: (expression_statement) @indent) @start
+(while_statement body: (expression_statement) @indent) @start
@@ -0,0 +1,2 @@
+; The body of a compound statement, from the `:` of its header, e.g.: `def`, `if`, `else`.
+(_ ":" @start (block) @indent)
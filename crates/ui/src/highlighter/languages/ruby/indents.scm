@@ -0,0 +1,18 @@
+(method "end" @end) @indent
+(singleton_method "end" @end) @indent
+(class "end" @end) @indent
+(singleton_class "end" @end) @indent
+(module "end" @end) @indent
+(begin "end" @end) @indent
+(do_block "end" @end) @indent
+(do "end" @end) @indent
+
+; The `elsif` and `else` are at the level of the `if`.
+(if [(elsif) (else) "end"] @end) @indent
+(unless [(elsif) (else) "end"] @end) @indent
+(elsif [(elsif) (else)] @end) @indent
+(elsif) @indent
+(else) @indent
+
+; The `when` are at the level of the `case`.
+(when) @indent
@@ -0,0 +1,6 @@
+; The chained calls on the next lines.
+(field_expression) @indent
+
+; The `where` clause and the expression of an arm on the next lines.
+(where_clause) @indent
+(match_arm value: (_) @indent) @start
@@ -0,0 +1,5 @@
+; The value of a key on the next lines.
+(block_mapping_pair ":" @start value: (block_node) @indent)
+
+; The content of a list item on the next lines.
+(block_sequence_item "-" @start (block_node) @indent)
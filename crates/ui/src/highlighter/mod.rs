@@ -1,10 +1,15 @@
+mod detect;
+mod grammars;
 mod highlighter;
 mod languages;
 mod registry;
+mod semantic;
 
+pub use grammars::load_grammars;
 pub use highlighter::*;
 pub use languages::*;
 pub use registry::*;
+pub use semantic::*;
 
 use gpui::App;
 
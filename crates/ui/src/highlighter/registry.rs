@@ -1,8 +1,8 @@
-use gpui::{App, FontWeight, HighlightStyle, Hsla};
+use gpui::{App, FontWeight, HighlightStyle, Hsla, SharedString};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     ops::Deref,
     sync::{Arc, LazyLock},
 };
@@ -232,6 +232,23 @@ impl SyntaxColors {
     }
 }
 
+/// Theme for the semantic tokens, see [`super::SemanticToken`].
+///
+/// The key is the token type with the optional modifiers, e.g.: `parameter`,
+/// `variable.readonly`, or `*.deprecated` for any token type. The most specific key matched
+/// by the token is used.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, JsonSchema, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SemanticTokenColors(BTreeMap<String, ThemeStyle>);
+
+impl Deref for SemanticTokenColors {
+    type Target = BTreeMap<String, ThemeStyle>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, JsonSchema, Serialize, Deserialize)]
 pub struct StatusColors {
     #[serde(rename = "error")]
@@ -254,6 +271,14 @@ pub struct StatusColors {
     hint: Option<Hsla>,
     #[serde(rename = "hint.background")]
     hint_background: Option<Hsla>,
+    #[serde(rename = "created")]
+    created: Option<Hsla>,
+    #[serde(rename = "created.background")]
+    created_background: Option<Hsla>,
+    #[serde(rename = "deleted")]
+    deleted: Option<Hsla>,
+    #[serde(rename = "deleted.background")]
+    deleted_background: Option<Hsla>,
 }
 
 impl StatusColors {
@@ -306,6 +331,28 @@ impl StatusColors {
     pub fn hint_background(&self) -> Hsla {
         self.hint_background.unwrap_or(self.hint())
     }
+
+    #[inline]
+    pub fn created(&self) -> Hsla {
+        self.created.unwrap_or(crate::green_500())
+    }
+
+    #[inline]
+    pub fn created_background(&self) -> Hsla {
+        self.created_background
+            .unwrap_or(self.created().opacity(0.15))
+    }
+
+    #[inline]
+    pub fn deleted(&self) -> Hsla {
+        self.deleted.unwrap_or(crate::red_500())
+    }
+
+    #[inline]
+    pub fn deleted_background(&self) -> Hsla {
+        self.deleted_background
+            .unwrap_or(self.deleted().opacity(0.15))
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, JsonSchema, Serialize, Deserialize)]
@@ -320,10 +367,14 @@ pub struct HighlightThemeStyle {
     pub line_number: Option<Hsla>,
     #[serde(rename = "editor.active_line_number")]
     pub active_line_number: Option<Hsla>,
+    #[serde(rename = "editor.document_highlight.bracket_background")]
+    pub bracket_match: Option<Hsla>,
     #[serde(flatten)]
     pub status: StatusColors,
     #[serde(rename = "syntax")]
     pub syntax: SyntaxColors,
+    #[serde(rename = "semantic_tokens", default)]
+    pub semantic_tokens: SemanticTokenColors,
 }
 
 /// Theme for Tree-sitter Highlight from JSON theme file.
@@ -366,7 +417,8 @@ pub fn init(cx: &mut App) {
 /// Registry for code highlighter languages.
 #[derive(Clone)]
 pub struct LanguageRegistry {
-    languages: HashMap<String, LanguageConfig>,
+    /// The languages registered at runtime, the key is the language name.
+    languages: HashMap<SharedString, LanguageConfig>,
     pub(crate) light_theme: Arc<HighlightTheme>,
     pub(crate) dark_theme: Arc<HighlightTheme>,
 }
@@ -390,8 +442,23 @@ impl LanguageRegistry {
         }
     }
 
+    /// Register a language, the registered language takes precedence over the builtin one
+    /// with the same name.
     pub fn register(&mut self, lang: &str, config: &LanguageConfig) {
-        self.languages.insert(lang.to_string(), config.clone());
+        self.languages
+            .insert(SharedString::from(lang.to_string()), config.clone());
+    }
+
+    /// Return the registered language by the name or the file extension.
+    pub(super) fn registered_language(&self, name: &str) -> Option<(SharedString, LanguageConfig)> {
+        if let Some((name, config)) = self.languages.get_key_value(name) {
+            return Some((name.clone(), config.clone()));
+        }
+
+        self.languages
+            .iter()
+            .find(|(_, config)| config.extensions.iter().any(|ext| ext == name))
+            .map(|(name, config)| (name.clone(), config.clone()))
     }
 
     #[allow(unused)]
@@ -426,4 +493,29 @@ mod tests {
         assert_eq!(syntax.style("keyword.repeat"), Some(rgb(0x0433ff).into()));
         assert_eq!(syntax.style("foo"), None);
     }
+
+    #[test]
+    fn test_register_language() {
+        use super::{LanguageConfig, LanguageRegistry};
+
+        let mut registry = LanguageRegistry::new();
+        let config = LanguageConfig::new(
+            tree_sitter::Language::new(tree_sitter_json::LANGUAGE),
+            tree_sitter_json::HIGHLIGHTS_QUERY,
+            "",
+            "",
+        )
+        .extensions(["json5", "jsonl"]);
+        registry.register("json5", &config);
+
+        let name = |name: &str| registry.registered_language(name).map(|(name, _)| name);
+        assert_eq!(name("json5"), Some("json5".into()));
+        assert_eq!(name("jsonl"), Some("json5".into()));
+        assert_eq!(name("rust"), None);
+        assert!(
+            LanguageRegistry::new()
+                .registered_language("json5")
+                .is_none()
+        );
+    }
 }
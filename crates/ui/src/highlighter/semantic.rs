@@ -0,0 +1,215 @@
+use std::ops::Range;
+
+use gpui::{HighlightStyle, SharedString};
+
+use super::HighlightTheme;
+
+/// The highlight names for the standard semantic token types of the LSP, used to style the
+/// tokens that have no style in the theme.
+///
+/// The keys are matched like the keys of [`super::SemanticTokenColors`].
+const FALLBACK_HIGHLIGHTS: &[(&str, &str)] = &[
+    ("namespace", "type"),
+    ("type", "type"),
+    ("class", "type"),
+    ("enum", "enum"),
+    ("interface", "type"),
+    ("struct", "type"),
+    ("typeParameter", "type"),
+    ("parameter", "variable"),
+    ("variable", "variable"),
+    ("variable.readonly", "constant"),
+    ("property", "property"),
+    ("enumMember", "variant"),
+    ("event", "property"),
+    ("function", "function"),
+    ("method", "function"),
+    ("macro", "preproc"),
+    ("keyword", "keyword"),
+    ("modifier", "keyword"),
+    ("comment", "comment"),
+    ("comment.documentation", "comment.doc"),
+    ("string", "string"),
+    ("number", "number"),
+    ("regexp", "string.regex"),
+    ("operator", "operator"),
+    ("decorator", "attribute"),
+    ("label", "label"),
+];
+
+/// A semantic token provided externally, e.g.: by the language server, that is layered on top
+/// of the tree-sitter highlights.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticToken {
+    /// The byte range of the token in the text.
+    pub range: Range<usize>,
+    /// The token type, e.g.: `parameter`, `enumMember`.
+    pub token_type: SharedString,
+    /// The token modifiers, e.g.: `readonly`, `deprecated`.
+    pub modifiers: Vec<SharedString>,
+}
+
+impl SemanticToken {
+    pub fn new(range: Range<usize>, token_type: impl Into<SharedString>) -> Self {
+        Self {
+            range,
+            token_type: token_type.into(),
+            modifiers: vec![],
+        }
+    }
+
+    /// Set the modifiers of the token.
+    pub fn modifiers(
+        mut self,
+        modifiers: impl IntoIterator<Item = impl Into<SharedString>>,
+    ) -> Self {
+        self.modifiers = modifiers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the specificity of the key matched by the token, `None` if not matched.
+    ///
+    /// The key is the token type (or `*` for any type) followed by the modifiers, e.g.:
+    /// `variable.readonly` matches the `variable` tokens with the `readonly` modifier.
+    fn specificity(&self, key: &str) -> Option<usize> {
+        let mut parts = key.split('.');
+        let token_type = parts.next()?;
+        if token_type != "*" && token_type != &*self.token_type {
+            return None;
+        }
+
+        let mut specificity = (token_type != "*") as usize;
+        for modifier in parts {
+            if !self.modifiers.iter().any(|m| &**m == modifier) {
+                return None;
+            }
+            specificity += 2;
+        }
+        Some(specificity)
+    }
+}
+
+impl HighlightTheme {
+    /// Returns the style of the semantic token by the most specific key in the
+    /// `semantic_tokens` of the theme.
+    ///
+    /// Fallback to the syntax style of the token type, e.g.: `enumMember` to `variant`.
+    pub fn semantic_style(&self, token: &SemanticToken) -> Option<HighlightStyle> {
+        let themed = self
+            .style
+            .semantic_tokens
+            .iter()
+            .filter_map(|(key, style)| Some((token.specificity(key)?, *style)))
+            .max_by_key(|(specificity, _)| *specificity);
+        if let Some((_, style)) = themed {
+            return Some(style.into());
+        }
+
+        FALLBACK_HIGHLIGHTS
+            .iter()
+            .filter_map(|(key, name)| Some((token.specificity(key)?, *name)))
+            .max_by_key(|(specificity, _)| *specificity)
+            .and_then(|(_, name)| self.style(name))
+    }
+}
+
+/// Layer the `overlay` styles on top of the `styles`, the properties of the overlay take
+/// precedence, and the rest are kept from the underlying style.
+///
+/// Both the `styles` and the `overlay` are sorted by the range.
+pub(super) fn overlay_styles(
+    styles: Vec<(Range<usize>, HighlightStyle)>,
+    overlay: &[(Range<usize>, HighlightStyle)],
+) -> Vec<(Range<usize>, HighlightStyle)> {
+    if overlay.is_empty() {
+        return styles;
+    }
+
+    let mut result = Vec::with_capacity(styles.len() + overlay.len() * 2);
+    let mut overlay = overlay.iter().peekable();
+    for (range, style) in styles {
+        let mut start = range.start;
+        while start < range.end {
+            while overlay.next_if(|(over, _)| over.end <= start).is_some() {}
+
+            match overlay.peek() {
+                Some((over, over_style)) if over.start <= start => {
+                    let end = over.end.min(range.end);
+                    result.push((start..end, merge_style(style, *over_style)));
+                    start = end;
+                }
+                Some((over, _)) if over.start < range.end => {
+                    result.push((start..over.start, style));
+                    start = over.start;
+                }
+                _ => {
+                    result.push((start..range.end, style));
+                    start = range.end;
+                }
+            }
+        }
+    }
+    result
+}
+
+fn merge_style(style: HighlightStyle, overlay: HighlightStyle) -> HighlightStyle {
+    HighlightStyle {
+        color: overlay.color.or(style.color),
+        font_weight: overlay.font_weight.or(style.font_weight),
+        font_style: overlay.font_style.or(style.font_style),
+        background_color: overlay.background_color.or(style.background_color),
+        underline: overlay.underline.or(style.underline),
+        strikethrough: overlay.strikethrough.or(style.strikethrough),
+        fade_out: overlay.fade_out.or(style.fade_out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{FontWeight, HighlightStyle};
+
+    use super::{SemanticToken, overlay_styles};
+
+    #[test]
+    fn test_specificity() {
+        let token = SemanticToken::new(0..3, "variable").modifiers(["readonly", "static"]);
+        assert_eq!(token.specificity("variable"), Some(1));
+        assert_eq!(token.specificity("variable.readonly"), Some(3));
+        assert_eq!(token.specificity("*.static"), Some(2));
+        assert_eq!(token.specificity("variable.readonly.static"), Some(5));
+        assert_eq!(token.specificity("variable.deprecated"), None);
+        assert_eq!(token.specificity("parameter"), None);
+    }
+
+    #[test]
+    fn test_overlay_styles() {
+        let bold = HighlightStyle {
+            font_weight: Some(FontWeight::BOLD),
+            ..Default::default()
+        };
+        let italic = HighlightStyle {
+            font_style: Some(gpui::FontStyle::Italic),
+            ..Default::default()
+        };
+
+        let styles = vec![(0..4, bold), (4..10, HighlightStyle::default())];
+        let overlay = vec![(2..6, italic), (8..9, italic)];
+        let bold_italic = HighlightStyle {
+            font_weight: Some(FontWeight::BOLD),
+            font_style: Some(gpui::FontStyle::Italic),
+            ..Default::default()
+        };
+        assert_eq!(
+            overlay_styles(styles.clone(), &overlay),
+            vec![
+                (0..2, bold),
+                (2..4, bold_italic),
+                (4..6, italic),
+                (6..8, HighlightStyle::default()),
+                (8..9, italic),
+                (9..10, HighlightStyle::default()),
+            ]
+        );
+        assert_eq!(overlay_styles(styles.clone(), &[]), styles);
+    }
+}
@@ -0,0 +1,84 @@
+/// The brackets that can be matched, the `(open, close)` chars.
+pub(super) const BRACKETS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}')];
+
+/// The auto-close pairs for the input without language.
+pub(super) const DEFAULT_PAIRS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"')];
+
+/// Return the offset of the bracket next to the cursor.
+///
+/// The bracket after the cursor is preferred, e.g.: `foo|()` is `(`, `foo()|` is `)`.
+pub(super) fn bracket_near(text: &str, offset: usize) -> Option<usize> {
+    let is_bracket = |ix: usize| {
+        text.as_bytes().get(ix).is_some_and(|b| {
+            BRACKETS
+                .iter()
+                .any(|(open, close)| *b as char == *open || *b as char == *close)
+        })
+    };
+
+    if is_bracket(offset) {
+        Some(offset)
+    } else if offset > 0 && is_bracket(offset - 1) {
+        Some(offset - 1)
+    } else {
+        None
+    }
+}
+
+/// Return the offset of the matching bracket by scanning the text.
+///
+/// This is the fallback when there is no syntax tree, the brackets in strings or comments are
+/// not ignored.
+pub(super) fn find_matching_bracket(text: &str, offset: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let c = *bytes.get(offset)? as char;
+
+    if let Some((open, close)) = BRACKETS.iter().find(|(open, _)| *open == c) {
+        let mut depth = 0;
+        for (ix, b) in bytes.iter().enumerate().skip(offset) {
+            if *b as char == *open {
+                depth += 1;
+            } else if *b as char == *close {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(ix);
+                }
+            }
+        }
+    } else if let Some((open, close)) = BRACKETS.iter().find(|(_, close)| *close == c) {
+        let mut depth = 0;
+        for ix in (0..=offset).rev() {
+            if bytes[ix] as char == *close {
+                depth += 1;
+            } else if bytes[ix] as char == *open {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(ix);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bracket_near, find_matching_bracket};
+
+    #[test]
+    fn test_find_matching_bracket() {
+        let text = "fn foo(a: [u8; 2]) { (a) }";
+        assert_eq!(bracket_near(text, 6), Some(6));
+        assert_eq!(bracket_near(text, 18), Some(17));
+        assert_eq!(bracket_near(text, 3), None);
+
+        assert_eq!(find_matching_bracket(text, 6), Some(17));
+        assert_eq!(find_matching_bracket(text, 17), Some(6));
+        assert_eq!(find_matching_bracket(text, 10), Some(16));
+        assert_eq!(find_matching_bracket(text, 19), Some(25));
+        assert_eq!(find_matching_bracket(text, 25), Some(19));
+        assert_eq!(find_matching_bracket(text, 0), None);
+        assert_eq!(find_matching_bracket("(()", 0), None);
+    }
+}
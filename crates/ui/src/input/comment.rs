@@ -0,0 +1,122 @@
+/// Toggle the line comment of the lines.
+///
+/// If all the non-blank lines are commented, the comment tokens are removed, otherwise the
+/// token is inserted at the minimum indent of the lines.
+pub(super) fn toggle_line_comment(text: &str, token: &str) -> String {
+    let lines = text.split('\n').collect::<Vec<_>>();
+    let non_blank_lines = lines.iter().filter(|line| !line.trim().is_empty());
+
+    if non_blank_lines.clone().count() == 0 {
+        return format!("{}{} ", text, token);
+    }
+
+    let commented = non_blank_lines
+        .clone()
+        .all(|line| line[indent_len(line)..].starts_with(token));
+    if commented {
+        return lines
+            .iter()
+            .map(|line| {
+                let indent_len = indent_len(line);
+                match line[indent_len..].strip_prefix(token) {
+                    Some(rest) => {
+                        let rest = rest.strip_prefix(' ').unwrap_or(rest);
+                        format!("{}{}", &line[..indent_len], rest)
+                    }
+                    None => line.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    let min_indent = non_blank_lines
+        .map(|line| indent_len(line))
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{} {}", &line[..min_indent], token, &line[min_indent..])
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Return the byte length of the leading spaces and tabs of the line.
+///
+/// The other whitespaces (e.g.: `U+3000`) are multi-byte, so they are kept as the content.
+fn indent_len(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+/// Toggle the block comment around the text, the leading and trailing whitespaces are kept.
+pub(super) fn toggle_block_comment(text: &str, start: &str, end: &str) -> String {
+    let trimmed = text.trim();
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+
+    if trimmed.is_empty() {
+        return format!("{}{}  {}", text, start, end);
+    }
+
+    if trimmed.len() >= start.len() + end.len()
+        && trimmed.starts_with(start)
+        && trimmed.ends_with(end)
+    {
+        let inner = &trimmed[start.len()..trimmed.len() - end.len()];
+        let inner = inner.strip_prefix(' ').unwrap_or(inner);
+        let inner = inner.strip_suffix(' ').unwrap_or(inner);
+        return format!("{}{}{}", leading, inner, trailing);
+    }
+
+    format!("{}{} {} {}{}", leading, start, trimmed, end, trailing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{toggle_block_comment, toggle_line_comment};
+
+    #[test]
+    fn test_toggle_line_comment() {
+        let text = "    fn foo() {\n\n        bar();\n    }";
+        let commented = toggle_line_comment(text, "//");
+        assert_eq!(
+            commented,
+            "    // fn foo() {\n\n    //     bar();\n    // }"
+        );
+        assert_eq!(toggle_line_comment(&commented, "//"), text);
+
+        // Partially commented lines are commented again.
+        assert_eq!(toggle_line_comment("# a\nb", "#"), "# # a\n# b");
+        assert_eq!(toggle_line_comment("#a", "#"), "a");
+        assert_eq!(toggle_line_comment("  ", "#"), "  # ");
+    }
+
+    #[test]
+    fn test_toggle_line_comment_mixed_indent() {
+        let text = "\u{3000}a\n  b";
+        let commented = toggle_line_comment(text, "//");
+        assert_eq!(commented, "// \u{3000}a\n//   b");
+        assert_eq!(toggle_line_comment(&commented, "//"), text);
+
+        let text = "\t\ta\n\tb";
+        let commented = toggle_line_comment(text, "#");
+        assert_eq!(commented, "\t# \ta\n\t# b");
+        assert_eq!(toggle_line_comment(&commented, "#"), text);
+    }
+
+    #[test]
+    fn test_toggle_block_comment() {
+        let text = "  <div></div>\n";
+        let commented = toggle_block_comment(text, "<!--", "-->");
+        assert_eq!(commented, "  <!-- <div></div> -->\n");
+        assert_eq!(toggle_block_comment(&commented, "<!--", "-->"), text);
+        assert_eq!(toggle_block_comment("/*a*/", "/*", "*/"), "a");
+        assert_eq!(toggle_block_comment("", "/*", "*/"), "/*  */");
+    }
+}
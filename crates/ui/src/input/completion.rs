@@ -0,0 +1,306 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use gpui::{
+    App, Context, KeyBinding, ScrollStrategy, SharedString, Task, UniformListScrollHandle, Window,
+};
+
+use super::InputState;
+use crate::actions::{Cancel, Confirm, SelectNext, SelectPrev};
+
+/// The key context of the input when the completion menu is shown.
+pub(super) const CONTEXT: &str = "CompletionMenu";
+/// The maximum number of the visible items of the completion menu.
+pub(super) const MAX_VISIBLE_ITEMS: usize = 8;
+
+pub(super) fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("up", SelectPrev, Some(CONTEXT)),
+        KeyBinding::new("down", SelectNext, Some(CONTEXT)),
+        KeyBinding::new("ctrl-p", SelectPrev, Some(CONTEXT)),
+        KeyBinding::new("ctrl-n", SelectNext, Some(CONTEXT)),
+        KeyBinding::new("enter", Confirm { secondary: false }, Some(CONTEXT)),
+        KeyBinding::new("tab", Confirm { secondary: false }, Some(CONTEXT)),
+        KeyBinding::new("escape", Cancel, Some(CONTEXT)),
+    ]);
+}
+
+/// An item of the completion menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionItem {
+    /// The label shown in the menu, and inserted if no `insert_text`.
+    pub label: SharedString,
+    /// The short detail shown after the label, e.g.: the type of the symbol.
+    pub detail: Option<SharedString>,
+    /// The documentation shown in the side panel when the item is selected.
+    pub documentation: Option<SharedString>,
+    /// The text to insert, default is the `label`.
+    pub insert_text: Option<SharedString>,
+    /// The text to match the query, default is the `label`.
+    pub filter_text: Option<SharedString>,
+}
+
+impl CompletionItem {
+    pub fn new(label: impl Into<SharedString>) -> Self {
+        Self {
+            label: label.into(),
+            detail: None,
+            documentation: None,
+            insert_text: None,
+            filter_text: None,
+        }
+    }
+
+    pub fn detail(mut self, detail: impl Into<SharedString>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn documentation(mut self, documentation: impl Into<SharedString>) -> Self {
+        self.documentation = Some(documentation.into());
+        self
+    }
+
+    pub fn insert_text(mut self, insert_text: impl Into<SharedString>) -> Self {
+        self.insert_text = Some(insert_text.into());
+        self
+    }
+
+    pub fn filter_text(mut self, filter_text: impl Into<SharedString>) -> Self {
+        self.filter_text = Some(filter_text.into());
+        self
+    }
+
+    pub(super) fn text_to_insert(&self) -> &SharedString {
+        self.insert_text.as_ref().unwrap_or(&self.label)
+    }
+
+    fn text_to_filter(&self) -> &SharedString {
+        self.filter_text.as_ref().unwrap_or(&self.label)
+    }
+}
+
+/// Provide the completion items of the [`InputState`], e.g.: the slash commands, the file
+/// mentions or the code completion from the language server.
+pub trait CompletionProvider {
+    /// Return the byte range of the query before the cursor `offset`, the range is replaced by
+    /// the confirmed item. Return `None` to hide the completion menu.
+    ///
+    /// Default is the word before the cursor.
+    fn query_range(&self, text: &str, offset: usize) -> Option<Range<usize>> {
+        Some(word_before(text, offset))
+    }
+
+    /// Return the completion items of the `query_range` in the text, the items are filtered
+    /// with the query by fuzzy matching.
+    ///
+    /// Return [`Task::ready`] for the sync completions, or spawn a task to load the items in
+    /// the background.
+    fn completions(
+        &self,
+        text: &str,
+        query_range: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<InputState>,
+    ) -> Task<Result<Vec<CompletionItem>>>;
+}
+
+/// Return the range of the word (alphanumeric or `_`) before the offset, may be empty.
+pub(super) fn word_before(text: &str, offset: usize) -> Range<usize> {
+    let start = text[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+        .last()
+        .map_or(offset, |(ix, _)| ix);
+    start..offset
+}
+
+/// The result of [`fuzzy_match`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FuzzyMatch {
+    pub(crate) score: usize,
+    /// The byte offsets of the matched chars in the candidate.
+    pub(crate) positions: Vec<usize>,
+}
+
+/// Match the chars of the query in order in the candidate, ignoring case.
+///
+/// The consecutive matches and the matches at the start of the words score higher.
+pub(crate) fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let mut score = 0;
+    let mut positions = Vec::with_capacity(query.len());
+    let mut candidate_chars = candidate.char_indices();
+    let mut prev: Option<char> = None;
+    let mut last_matched: Option<usize> = None;
+
+    for q in query.chars() {
+        loop {
+            let (ix, c) = candidate_chars.next()?;
+            let prev_char = prev.replace(c);
+            if !c.to_lowercase().eq(q.to_lowercase()) {
+                continue;
+            }
+
+            score += 1;
+            let word_start = match prev_char {
+                None => true,
+                Some(p) => !p.is_alphanumeric() || (p.is_lowercase() && c.is_uppercase()),
+            };
+            if word_start {
+                score += 8;
+            }
+            if last_matched.is_some_and(|last| candidate[last..ix].chars().count() == 1) {
+                score += 10;
+            }
+            if c == q {
+                score += 1;
+            }
+            positions.push(ix);
+            last_matched = Some(ix);
+            break;
+        }
+    }
+
+    Some(FuzzyMatch { score, positions })
+}
+
+/// The state of the completion menu of the [`InputState`].
+pub(super) struct CompletionMenu {
+    /// The byte range of the query to replace by the confirmed item.
+    pub(super) query_range: Range<usize>,
+    items: Vec<CompletionItem>,
+    /// The matched items sorted by the score, `(item_ix, match)`.
+    matches: Vec<(usize, FuzzyMatch)>,
+    selected_ix: usize,
+    pub(super) scroll_handle: UniformListScrollHandle,
+}
+
+impl CompletionMenu {
+    pub(super) fn new(query_range: Range<usize>, items: Vec<CompletionItem>) -> Self {
+        Self {
+            query_range,
+            items,
+            matches: vec![],
+            selected_ix: 0,
+            scroll_handle: UniformListScrollHandle::new(),
+        }
+    }
+
+    /// Filter the items with the query, and select the best match.
+    pub(super) fn filter(&mut self, query: &str) {
+        let mut matches = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, item)| Some((ix, fuzzy_match(query, item.text_to_filter())?)))
+            .collect::<Vec<_>>();
+        let label_len = |ix: &usize| self.items[*ix].label.len();
+        matches.sort_by(|(a_ix, a), (b_ix, b)| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| label_len(a_ix).cmp(&label_len(b_ix)))
+                .then_with(|| a_ix.cmp(b_ix))
+        });
+
+        self.matches = matches;
+        self.selected_ix = 0;
+        self.scroll_handle.scroll_to_item(0, ScrollStrategy::Top);
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+
+    pub(super) fn selected_ix(&self) -> usize {
+        self.selected_ix
+    }
+
+    /// Return the item and the match of the `ix`th matched item.
+    pub(super) fn item(&self, ix: usize) -> Option<(&CompletionItem, &FuzzyMatch)> {
+        let (item_ix, fuzzy_match) = self.matches.get(ix)?;
+        Some((&self.items[*item_ix], fuzzy_match))
+    }
+
+    pub(super) fn selected_item(&self) -> Option<&CompletionItem> {
+        self.item(self.selected_ix).map(|(item, _)| item)
+    }
+
+    pub(super) fn select_prev(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        self.selected_ix = self
+            .selected_ix
+            .checked_sub(1)
+            .unwrap_or(self.matches.len() - 1);
+        self.scroll_handle
+            .scroll_to_item(self.selected_ix, ScrollStrategy::Top);
+    }
+
+    pub(super) fn select_next(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        self.selected_ix = (self.selected_ix + 1) % self.matches.len();
+        self.scroll_handle
+            .scroll_to_item(self.selected_ix, ScrollStrategy::Top);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompletionItem, CompletionMenu, fuzzy_match, word_before};
+
+    #[test]
+    fn test_fuzzy_match() {
+        let m = fuzzy_match("cl", "clear").unwrap();
+        assert_eq!(m.positions, vec![0, 1]);
+        assert!(fuzzy_match("clr", "clear").is_some());
+        assert!(fuzzy_match("rc", "clear").is_none());
+        assert_eq!(fuzzy_match("", "clear").unwrap().score, 0);
+
+        // Word starts and consecutive matches score higher.
+        let camel = fuzzy_match("gd", "goToDefinition").unwrap();
+        let plain = fuzzy_match("gd", "guard").unwrap();
+        assert!(camel.score > plain.score);
+        let prefix = fuzzy_match("mod", "model").unwrap();
+        let scattered = fuzzy_match("mod", "my_old_dir").unwrap();
+        assert!(prefix.score > scattered.score);
+        assert_eq!(scattered.positions, vec![0, 3, 5]);
+    }
+
+    #[test]
+    fn test_menu() {
+        let items = ["export", "exit", "retry", "model"]
+            .into_iter()
+            .map(CompletionItem::new)
+            .collect();
+        let mut menu = CompletionMenu::new(0..1, items);
+        menu.filter("ex");
+        assert_eq!(menu.len(), 2);
+        assert_eq!(&*menu.selected_item().unwrap().label, "exit");
+
+        menu.select_next();
+        assert_eq!(&*menu.selected_item().unwrap().label, "export");
+        menu.select_next();
+        assert_eq!(menu.selected_ix(), 0);
+        menu.select_prev();
+        assert_eq!(menu.selected_ix(), 1);
+
+        menu.filter("zzz");
+        assert!(menu.is_empty());
+        assert_eq!(menu.selected_item(), None);
+    }
+
+    #[test]
+    fn test_word_before() {
+        assert_eq!(word_before("let foo_bar", 11), 4..11);
+        assert_eq!(word_before("a.b", 2), 2..2);
+        assert_eq!(word_before("你好", 6), 0..6);
+    }
+}
@@ -1,20 +1,25 @@
-use std::{ops::Range, rc::Rc};
+use std::{collections::HashMap, ops::Range, rc::Rc};
 
 use gpui::{
     fill, point, px, relative, size, App, Bounds, Corners, Element, ElementId, ElementInputHandler,
-    Entity, GlobalElementId, HighlightStyle, IntoElement, LayoutId, MouseButton, MouseMoveEvent,
-    Path, Pixels, Point, SharedString, Size, Style, TextAlign, TextRun, UnderlineStyle, Window,
-    WrappedLine,
+    Entity, GlobalElementId, HighlightStyle, IntoElement, LayoutId, MouseButton, MouseDownEvent,
+    MouseMoveEvent, Path, Pixels, Point, SharedString, Size, Style, TextAlign, TextRun,
+    TransformationMatrix, UnderlineStyle, Window, WrappedLine,
 };
 use smallvec::SmallVec;
 
-use crate::{highlighter::LanguageRegistry, ActiveTheme as _, Root};
+use crate::{highlighter::LanguageRegistry, ActiveTheme as _, IconName, Root};
 
-use super::{mode::InputMode, InputState, LastLayout};
+use super::{mode::InputMode, InputState, LastLayout, MarkerSeverity};
 
 const RIGHT_MARGIN: Pixels = px(5.);
 const BOTTOM_MARGIN_ROWS: usize = 1;
 const LINE_NUMBER_MARGIN_RIGHT: Pixels = px(10.);
+/// The width of the marker icons column at the left of the line numbers.
+const GUTTER_ICON_WIDTH: Pixels = px(18.);
+const GUTTER_ICON_SIZE: Pixels = px(12.);
+/// The width of the fold toggles column at the right of the line numbers.
+const FOLD_ICON_WIDTH: Pixels = px(16.);
 
 pub(super) struct TextElement {
     input: Entity<InputState>,
@@ -44,11 +49,48 @@ impl TextElement {
                     input.update(cx, |input, cx| {
                         input.on_drag_move(event, window, cx);
                     });
+                } else {
+                    input.update(cx, |input, cx| {
+                        input.on_hover_move(event, window, cx);
+                    });
                 }
             }
         });
     }
 
+    /// Register the click listener of the fold toggles in the gutter.
+    fn paint_fold_listeners(
+        &mut self,
+        fold_toggles: Vec<(usize, Bounds<Pixels>)>,
+        window: &mut Window,
+    ) {
+        if fold_toggles.is_empty() {
+            return;
+        }
+
+        window.on_mouse_event({
+            let input = self.input.clone();
+
+            move |event: &MouseDownEvent, phase, window, cx| {
+                if !phase.bubble() || event.button != MouseButton::Left {
+                    return;
+                }
+
+                let Some((line_ix, _)) = fold_toggles
+                    .iter()
+                    .find(|(_, bounds)| bounds.contains(&event.position))
+                else {
+                    return;
+                };
+
+                cx.stop_propagation();
+                input.update(cx, |input, cx| {
+                    input.toggle_fold(*line_ix, window, cx);
+                });
+            }
+        });
+    }
+
     /// Returns the:
     ///
     /// - cursor bounds
@@ -93,6 +135,12 @@ impl TextElement {
                 break;
             }
 
+            if input.is_line_hidden(line_ix) {
+                // +1 for skip the last `\n`
+                prev_lines_offset += line.len() + 1;
+                continue;
+            }
+
             let line_origin = point(px(0.), offset_y);
             if cursor_pos.is_none() {
                 let offset = cursor_offset.saturating_sub(prev_lines_offset);
@@ -218,7 +266,13 @@ impl TextElement {
         let mut line_corners = vec![];
 
         let mut offset_y = px(0.);
-        for line in lines.iter() {
+        for (line_ix, line) in lines.iter().enumerate() {
+            if input.is_line_hidden(line_ix) {
+                // +1 for skip the last `\n`
+                prev_lines_offset += line.len() + 1;
+                continue;
+            }
+
             let line_size = line.size(line_height);
             let line_wrap_width = line_size.width;
 
@@ -340,6 +394,10 @@ impl TextElement {
         let mut visible_range = 0..state.text_wrapper.lines.len();
         let mut line_top = px(0.);
         for (ix, line) in state.text_wrapper.lines.iter().enumerate() {
+            if state.is_line_hidden(ix) {
+                continue;
+            }
+
             line_top += line.height(line_height);
 
             if line_top < scroll_top {
@@ -412,9 +470,55 @@ impl TextElement {
 
                 styles = gpui::combine_highlights(marker_styles, styles).collect();
 
+                if let Some((offset, matching)) = state.matching_brackets() {
+                    let style = match theme.style.bracket_match {
+                        Some(color) => HighlightStyle {
+                            background_color: Some(color),
+                            ..Default::default()
+                        },
+                        None => HighlightStyle {
+                            underline: Some(UnderlineStyle {
+                                thickness: px(1.),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        },
+                    };
+                    let mut bracket_styles = [offset, matching]
+                        .into_iter()
+                        .filter(|ix| *ix >= skipped_offset)
+                        .map(|ix| (ix..ix + 1, style))
+                        .collect::<Vec<_>>();
+                    bracket_styles.sort_by_key(|(range, _)| range.start);
+                    styles = gpui::combine_highlights(bracket_styles, styles).collect();
+                }
+
                 Some((skipped_offset, styles))
             }
-            _ => None,
+            _ => {
+                if state.misspellings.is_empty() {
+                    return None;
+                }
+
+                // Underline the misspelled words like the error markers, except the word being
+                // typed at the cursor.
+                let cursor = state.cursor_offset();
+                let style = MarkerSeverity::Error.highlight_style(&theme);
+                let mut offset = 0;
+                let mut styles = vec![];
+                for range in state
+                    .misspellings
+                    .iter()
+                    .filter(|range| !(range.start..=range.end).contains(&cursor))
+                {
+                    styles.push((offset..range.start, HighlightStyle::default()));
+                    styles.push((range.clone(), style));
+                    offset = range.end;
+                }
+                styles.push((offset..state.text.len(), HighlightStyle::default()));
+
+                Some((0, styles))
+            }
         })
     }
 }
@@ -427,6 +531,10 @@ pub(super) struct PrepaintState {
     /// The lines only contains the visible lines in the viewport, based on `visible_range`.
     line_numbers: Option<Vec<SmallVec<[WrappedLine; 1]>>>,
     line_number_width: Pixels,
+    /// The most severe marker of each line (zero based), to paint the gutter icons.
+    line_markers: HashMap<usize, MarkerSeverity>,
+    /// The foldable lines (zero based) in the viewport, and whether each is folded.
+    line_folds: HashMap<usize, bool>,
     /// Size of the scrollable area by entire lines.
     scroll_size: Size<Pixels>,
     cursor_bounds: Option<Bounds<Pixels>>,
@@ -570,7 +678,10 @@ impl Element for TextElement {
             )
             .unwrap();
         let line_number_width = if input.mode.line_number() {
-            empty_line_number.last().unwrap().width() + LINE_NUMBER_MARGIN_RIGHT
+            GUTTER_ICON_WIDTH
+                + empty_line_number.last().unwrap().width()
+                + FOLD_ICON_WIDTH
+                + LINE_NUMBER_MARGIN_RIGHT
         } else {
             px(0.)
         };
@@ -663,7 +774,9 @@ impl Element for TextElement {
 
         let total_wrapped_lines = lines
             .iter()
-            .map(|line| {
+            .enumerate()
+            .filter(|(ix, _)| !input.is_line_hidden(*ix))
+            .map(|(_, line)| {
                 // +1 is the first line, `wrap_boundaries` is the wrapped lines after the `\n`.
                 1 + line.wrap_boundaries.len()
             })
@@ -757,6 +870,11 @@ impl Element for TextElement {
                 .enumerate()
             {
                 let ix = ix + visible_range.start;
+                if input.is_line_hidden(ix) {
+                    line_numbers.push(SmallVec::new());
+                    continue;
+                }
+
                 let line_no = ix + 1;
 
                 let mut line_no_text = format!("{:>4}", line_no);
@@ -781,6 +899,26 @@ impl Element for TextElement {
             None
         };
 
+        let mut line_markers: HashMap<usize, MarkerSeverity> = HashMap::new();
+        if input.mode.line_number() {
+            for marker in input.mode.markers().into_iter().flatten() {
+                let line_ix = marker.start.line.saturating_sub(1);
+                let severity = line_markers.entry(line_ix).or_insert(marker.severity);
+                if marker.severity.priority() > severity.priority() {
+                    *severity = marker.severity;
+                }
+            }
+        }
+
+        let mut line_folds: HashMap<usize, bool> = HashMap::new();
+        if input.mode.line_number() {
+            for line_ix in visible_range.clone() {
+                if input.folds.foldable_at(line_ix).is_some() && !input.is_line_hidden(line_ix) {
+                    line_folds.insert(line_ix, input.folds.is_folded(line_ix));
+                }
+            }
+        }
+
         PrepaintState {
             bounds,
             last_layout: LastLayout {
@@ -789,6 +927,8 @@ impl Element for TextElement {
             scroll_size,
             line_numbers,
             line_number_width,
+            line_markers,
+            line_folds,
             cursor_bounds,
             cursor_scroll_offset,
             current_line_index,
@@ -847,9 +987,18 @@ impl Element for TextElement {
         let line_height = window.line_height();
         let origin = bounds.origin;
 
+        let folds = self.input.read(cx).folds.clone();
         let mut invisible_top_padding = px(0.);
-        for line in prepaint.last_layout.lines.iter().take(visible_range.start) {
-            invisible_top_padding += line.size(line_height).height;
+        for (ix, line) in prepaint
+            .last_layout
+            .lines
+            .iter()
+            .take(visible_range.start)
+            .enumerate()
+        {
+            if !folds.is_hidden(ix) {
+                invisible_top_padding += line.size(line_height).height;
+            }
         }
 
         let mut offset_y = px(0.);
@@ -862,17 +1011,21 @@ impl Element for TextElement {
             }
         }
 
-        let active_line_color = LanguageRegistry::global(cx)
+        let highlight_theme = LanguageRegistry::global(cx)
             .theme(cx.theme().is_dark())
-            .style
-            .active_line;
+            .clone();
+        let active_line_color = highlight_theme.style.active_line;
+        let mut fold_toggles = vec![];
 
         if let Some(line_numbers) = prepaint.line_numbers.as_ref() {
             offset_y += invisible_top_padding;
 
             // Each item is the normal lines.
             for (ix, lines) in line_numbers.iter().enumerate() {
-                let is_active = prepaint.current_line_index == Some(visible_range.start + ix);
+                let line_ix = visible_range.start + ix;
+                let is_active = prepaint.current_line_index == Some(line_ix);
+
+                let line_top = offset_y;
                 for line in lines {
                     let p = point(origin.x, origin.y + offset_y);
                     let line_size = line.size(line_height);
@@ -885,9 +1038,69 @@ impl Element for TextElement {
                             ));
                         }
                     }
-                    _ = line.paint(p, line_height, TextAlign::Left, None, window, cx);
+                    _ = line.paint(
+                        p + point(GUTTER_ICON_WIDTH, px(0.)),
+                        line_height,
+                        TextAlign::Left,
+                        None,
+                        window,
+                        cx,
+                    );
                     offset_y += line_size.height;
                 }
+
+                // The line is hidden by a fold
+                if lines.is_empty() {
+                    continue;
+                }
+
+                // Paint the fold toggle over the first row of the line
+                if let Some(folded) = prepaint.line_folds.get(&line_ix) {
+                    let toggle_bounds = Bounds::new(
+                        point(
+                            origin.x + prepaint.line_number_width
+                                - LINE_NUMBER_MARGIN_RIGHT
+                                - FOLD_ICON_WIDTH,
+                            origin.y + line_top,
+                        ),
+                        size(FOLD_ICON_WIDTH, line_height),
+                    );
+                    let icon = if *folded {
+                        IconName::ChevronRight
+                    } else {
+                        IconName::ChevronDown
+                    };
+                    _ = window.paint_svg(
+                        Bounds::new(
+                            toggle_bounds.center()
+                                - point(GUTTER_ICON_SIZE / 2., GUTTER_ICON_SIZE / 2.),
+                            size(GUTTER_ICON_SIZE, GUTTER_ICON_SIZE),
+                        ),
+                        icon.path(),
+                        TransformationMatrix::unit(),
+                        cx.theme().muted_foreground,
+                        cx,
+                    );
+                    fold_toggles.push((line_ix, toggle_bounds));
+                }
+
+                // Paint the marker icon over the first row of the line
+                if let Some(severity) = prepaint.line_markers.get(&line_ix) {
+                    let icon_bounds = Bounds::new(
+                        point(
+                            origin.x + (GUTTER_ICON_WIDTH - GUTTER_ICON_SIZE) / 2.,
+                            origin.y + line_top + (line_height - GUTTER_ICON_SIZE) / 2.,
+                        ),
+                        size(GUTTER_ICON_SIZE, GUTTER_ICON_SIZE),
+                    );
+                    _ = window.paint_svg(
+                        icon_bounds,
+                        severity.icon().path(),
+                        TransformationMatrix::unit(),
+                        severity.color(&highlight_theme),
+                        cx,
+                    );
+                }
             }
         }
 
@@ -898,12 +1111,17 @@ impl Element for TextElement {
 
         // Paint text
         let mut offset_y = invisible_top_padding;
-        for line in prepaint
+        for (ix, line) in prepaint
             .last_layout
             .iter()
+            .enumerate()
             .skip(visible_range.start)
             .take(visible_range.len())
         {
+            if folds.is_hidden(ix) {
+                continue;
+            }
+
             let p = point(origin.x + prepaint.line_number_width, origin.y + offset_y);
             _ = line.paint(p, line_height, TextAlign::Left, None, window, cx);
             offset_y += line.size(line_height).height;
@@ -919,6 +1137,7 @@ impl Element for TextElement {
         self.input.update(cx, |input, cx| {
             input.last_layout = Some(prepaint.last_layout.clone());
             input.last_bounds = Some(bounds);
+            input.last_visible_range = Some(visible_range.clone());
             input.last_cursor_offset = Some(input.cursor_offset());
             input.last_line_height = line_height;
             input.set_input_bounds(input_bounds, cx);
@@ -932,5 +1151,6 @@ impl Element for TextElement {
         });
 
         self.paint_mouse_listeners(window, cx);
+        self.paint_fold_listeners(fold_toggles, window);
     }
 }
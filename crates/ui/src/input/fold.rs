@@ -0,0 +1,227 @@
+use std::ops::Range;
+
+/// Keep the foldable and folded line ranges of the code editor.
+///
+/// A fold range is based on lines (zero based), the `start` line is the fold header that
+/// keeps visible when folded, and the lines `start + 1..end` are hidden.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct FoldMap {
+    /// The foldable ranges from the syntax tree, sorted by start line.
+    foldable: Vec<Range<usize>>,
+    /// The folded ranges, sorted by start line.
+    folded: Vec<Range<usize>>,
+}
+
+impl FoldMap {
+    /// Set the foldable ranges.
+    ///
+    /// The folded ranges that are no longer foldable will be unfolded.
+    pub(super) fn set_foldable(&mut self, mut foldable: Vec<Range<usize>>) {
+        foldable.sort_by_key(|range| range.start);
+        self.folded.retain_mut(|folded| {
+            match foldable.iter().find(|range| range.start == folded.start) {
+                Some(range) => {
+                    folded.end = range.end;
+                    true
+                }
+                None => false,
+            }
+        });
+        self.foldable = foldable;
+    }
+
+    /// Return the foldable range that the header is at the given line.
+    pub(super) fn foldable_at(&self, line: usize) -> Option<&Range<usize>> {
+        self.foldable.iter().find(|range| range.start == line)
+    }
+
+    /// Return true if the fold with the header at the given line is folded.
+    pub(super) fn is_folded(&self, line: usize) -> bool {
+        self.folded.iter().any(|range| range.start == line)
+    }
+
+    /// Return true if the line is hidden by a folded range.
+    pub(super) fn is_hidden(&self, line: usize) -> bool {
+        self.folded
+            .iter()
+            .take_while(|range| range.start < line)
+            .any(|range| line < range.end)
+    }
+
+    /// Return the folded range that hides the given line, the outermost one if nested.
+    pub(super) fn folded_range_for(&self, line: usize) -> Option<&Range<usize>> {
+        self.folded
+            .iter()
+            .find(|range| range.start < line && line < range.end)
+    }
+
+    /// Return true if there is no folded range.
+    pub(super) fn is_empty(&self) -> bool {
+        self.folded.is_empty()
+    }
+
+    /// Fold the range with the header at the given line, or the innermost foldable range that
+    /// contains the line.
+    ///
+    /// Returns the folded range.
+    pub(super) fn fold(&mut self, line: usize) -> Option<Range<usize>> {
+        let range = self.foldable_at(line).cloned().or_else(|| {
+            self.foldable
+                .iter()
+                .filter(|range| {
+                    range.start < line && line < range.end && !self.is_folded(range.start)
+                })
+                .min_by_key(|range| range.len())
+                .cloned()
+        })?;
+
+        if !self.is_folded(range.start) {
+            let ix = self
+                .folded
+                .partition_point(|folded| folded.start < range.start);
+            self.folded.insert(ix, range.clone());
+        }
+
+        Some(range)
+    }
+
+    /// Unfold the range with the header at the given line, or the innermost folded range that
+    /// contains the line.
+    ///
+    /// Returns the unfolded range.
+    pub(super) fn unfold(&mut self, line: usize) -> Option<Range<usize>> {
+        let ix = self
+            .folded
+            .iter()
+            .position(|range| range.start == line)
+            .or_else(|| {
+                self.folded
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, range)| range.start < line && line < range.end)
+                    .min_by_key(|(_, range)| range.len())
+                    .map(|(ix, _)| ix)
+            })?;
+
+        Some(self.folded.remove(ix))
+    }
+
+    /// Toggle the fold with the header at the given line.
+    pub(super) fn toggle(&mut self, line: usize) -> Option<Range<usize>> {
+        if self.is_folded(line) {
+            self.unfold(line)
+        } else {
+            self.fold(line)
+        }
+    }
+
+    /// Fold all the foldable ranges.
+    pub(super) fn fold_all(&mut self) {
+        self.folded = self.foldable.clone();
+    }
+
+    /// Unfold all the folded ranges.
+    pub(super) fn unfold_all(&mut self) {
+        self.folded.clear();
+    }
+
+    /// Unfold all the folded ranges that hide the given line.
+    pub(super) fn reveal(&mut self, line: usize) {
+        self.folded
+            .retain(|range| !(range.start < line && line < range.end));
+    }
+
+    /// Update the folded ranges after the lines `start..=old_end` have been replaced by the
+    /// lines `start..=new_end`.
+    ///
+    /// The folds after the edit are moved, the folds touched by the edit are unfolded, except
+    /// for an edit in a single header line.
+    pub(super) fn edit(&mut self, start: usize, old_end: usize, new_end: usize) {
+        let delta = new_end as isize - old_end as isize;
+        let single_line = start == old_end && start == new_end;
+
+        self.folded.retain_mut(|range| {
+            if range.end <= start {
+                true
+            } else if range.start > old_end {
+                range.start = (range.start as isize + delta) as usize;
+                range.end = (range.end as isize + delta) as usize;
+                true
+            } else {
+                single_line && range.start == start
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FoldMap;
+
+    fn fold_map() -> FoldMap {
+        let mut folds = FoldMap::default();
+        folds.set_foldable(vec![0..10, 2..5, 6..9]);
+        folds
+    }
+
+    #[test]
+    fn test_fold_and_unfold() {
+        let mut folds = fold_map();
+        assert_eq!(folds.fold(2), Some(2..5));
+        assert!(folds.is_folded(2));
+        assert!(!folds.is_hidden(2));
+        assert!(folds.is_hidden(3));
+        assert!(folds.is_hidden(4));
+        assert!(!folds.is_hidden(5));
+
+        // Fold the innermost range contains the line.
+        assert_eq!(folds.fold(7), Some(6..9));
+        assert_eq!(folds.fold(1), Some(0..10));
+        assert!(folds.is_hidden(2));
+        assert_eq!(folds.folded_range_for(3), Some(&(0..10)));
+
+        assert_eq!(folds.unfold(0), Some(0..10));
+        assert!(!folds.is_hidden(2));
+        assert!(folds.is_hidden(3));
+
+        assert_eq!(folds.toggle(2), Some(2..5));
+        assert!(!folds.is_hidden(3));
+        assert_eq!(folds.fold(11), None);
+
+        folds.fold_all();
+        assert!(folds.is_hidden(9));
+        folds.reveal(7);
+        assert!(!folds.is_hidden(7));
+        assert!(folds.is_hidden(3));
+        folds.unfold_all();
+        assert!(folds.is_empty());
+    }
+
+    #[test]
+    fn test_edit() {
+        let mut folds = fold_map();
+        folds.fold(2);
+        folds.fold(6);
+
+        // Edit in the fold header line keeps the fold.
+        folds.edit(2, 2, 2);
+        assert!(folds.is_folded(2));
+
+        // Insert 2 lines before the folds.
+        folds.edit(1, 1, 3);
+        assert!(folds.is_folded(4));
+        assert!(folds.is_folded(8));
+        assert!(folds.is_hidden(10));
+
+        // Remove a line in the folded range.
+        folds.edit(5, 6, 5);
+        assert!(!folds.is_folded(4));
+        assert!(folds.is_folded(7));
+
+        // Foldable ranges updated, the folded range is kept with new end.
+        folds.set_foldable(vec![7..12]);
+        assert!(folds.is_hidden(11));
+        folds.set_foldable(vec![0..3]);
+        assert!(folds.is_empty());
+    }
+}
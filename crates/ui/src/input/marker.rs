@@ -1,5 +1,5 @@
-use crate::{highlighter::HighlightTheme, input::InputState};
-use gpui::{px, HighlightStyle, SharedString, UnderlineStyle};
+use crate::{highlighter::HighlightTheme, input::InputState, IconName};
+use gpui::{px, HighlightStyle, Hsla, SharedString, UnderlineStyle};
 use itertools::Itertools;
 use std::ops::Range;
 
@@ -104,18 +104,42 @@ impl From<&str> for MarkerSeverity {
 }
 
 impl MarkerSeverity {
+    /// Returns the color of the marker severity with the given theme style.
+    pub(super) fn color(&self, theme: &HighlightTheme) -> Hsla {
+        match self {
+            Self::Error => theme.style.status.error(),
+            Self::Warning => theme.style.status.warning(),
+            Self::Info => theme.style.status.info(),
+            Self::Hint => theme.style.status.hint(),
+        }
+    }
+
+    /// Returns the icon to display in the gutter for the marker severity.
+    pub(super) fn icon(&self) -> IconName {
+        match self {
+            Self::Error => IconName::CircleX,
+            Self::Warning => IconName::TriangleAlert,
+            Self::Info | Self::Hint => IconName::Info,
+        }
+    }
+
+    /// Returns the priority of the severity, higher is more severe.
+    ///
+    /// Used to pick the gutter icon when a line has more than one marker.
+    pub(super) fn priority(&self) -> usize {
+        match self {
+            Self::Error => 3,
+            Self::Warning => 2,
+            Self::Info => 1,
+            Self::Hint => 0,
+        }
+    }
+
     /// Returns the [`HighlightStyle`] for the marker severity with the given theme style.
     pub(super) fn highlight_style(&self, theme: &HighlightTheme) -> HighlightStyle {
-        let color = match self {
-            Self::Error => Some(theme.style.status.error()),
-            Self::Warning => Some(theme.style.status.warning()),
-            Self::Info => Some(theme.style.status.info()),
-            Self::Hint => Some(theme.style.status.hint()),
-        };
-
         let mut style = HighlightStyle::default();
         style.underline = Some(UnderlineStyle {
-            color: color,
+            color: Some(self.color(theme)),
             thickness: px(1.),
             wavy: true,
         });
@@ -123,3 +147,89 @@ impl MarkerSeverity {
         style
     }
 }
+
+/// Return the sorted and deduplicated starts of the marker ranges.
+pub(super) fn sorted_starts(ranges: impl IntoIterator<Item = Range<usize>>) -> Vec<usize> {
+    let mut starts = ranges
+        .into_iter()
+        .map(|range| range.start)
+        .collect::<Vec<_>>();
+    starts.sort();
+    starts.dedup();
+    starts
+}
+
+/// Return the first start after the cursor, wrapping around to the first one.
+pub(super) fn next_start(starts: &[usize], cursor: usize) -> Option<usize> {
+    starts
+        .iter()
+        .find(|start| **start > cursor)
+        .or(starts.first())
+        .copied()
+}
+
+/// Return the last start before the cursor, wrapping around to the last one.
+pub(super) fn previous_start(starts: &[usize], cursor: usize) -> Option<usize> {
+    starts
+        .iter()
+        .rev()
+        .find(|start| **start < cursor)
+        .or(starts.last())
+        .copied()
+}
+
+/// Return the index of the most severe marker that contains the offset, the end of the range is
+/// inclusive.
+pub(super) fn most_severe_at(
+    markers: impl IntoIterator<Item = (Option<Range<usize>>, MarkerSeverity)>,
+    offset: usize,
+) -> Option<usize> {
+    markers
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (range, _))| {
+            range
+                .as_ref()
+                .is_some_and(|range| range.start <= offset && offset <= range.end)
+        })
+        .max_by_key(|(_, (_, severity))| severity.priority())
+        .map(|(ix, _)| ix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MarkerSeverity, most_severe_at, next_start, previous_start, sorted_starts};
+
+    #[test]
+    fn test_sorted_starts() {
+        assert_eq!(sorted_starts([8..10, 2..4, 8..9, 0..1]), vec![0, 2, 8]);
+        assert_eq!(sorted_starts([]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_next_and_previous_start() {
+        let starts = [2, 8, 15];
+        assert_eq!(next_start(&starts, 0), Some(2));
+        assert_eq!(next_start(&starts, 2), Some(8));
+        assert_eq!(next_start(&starts, 15), Some(2));
+        assert_eq!(previous_start(&starts, 9), Some(8));
+        assert_eq!(previous_start(&starts, 8), Some(2));
+        assert_eq!(previous_start(&starts, 2), Some(15));
+        assert_eq!(next_start(&[], 3), None);
+        assert_eq!(previous_start(&[], 3), None);
+    }
+
+    #[test]
+    fn test_most_severe_at() {
+        let markers = [
+            (Some(0..5), MarkerSeverity::Warning),
+            (Some(3..8), MarkerSeverity::Error),
+            (None, MarkerSeverity::Error),
+            (Some(10..12), MarkerSeverity::Hint),
+        ];
+        assert_eq!(most_severe_at(markers.clone(), 1), Some(0));
+        assert_eq!(most_severe_at(markers.clone(), 4), Some(1));
+        assert_eq!(most_severe_at(markers.clone(), 12), Some(3));
+        assert_eq!(most_severe_at(markers, 9), None);
+    }
+}
@@ -1,7 +1,11 @@
 mod blink_cursor;
+mod bracket;
 mod change;
 mod clear_button;
+mod comment;
+mod completion;
 mod element;
+mod fold;
 mod marker;
 mod mask_pattern;
 mod mode;
@@ -12,6 +16,8 @@ mod text_input;
 mod text_wrapper;
 
 pub(crate) use clear_button::*;
+pub use completion::{CompletionItem, CompletionProvider};
+pub(crate) use completion::{FuzzyMatch, fuzzy_match};
 pub use marker::*;
 pub use mask_pattern::MaskPattern;
 pub use mode::TabSize;
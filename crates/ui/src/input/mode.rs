@@ -32,6 +32,23 @@ impl TabSize {
             " ".repeat(self.tab_size).into()
         }
     }
+
+    /// Return the width (in columns) of the indent, a `\t` is `tab_size` columns.
+    pub(super) fn indent_width(&self, indent: &str) -> usize {
+        indent
+            .chars()
+            .map(|c| if c == '\t' { self.tab_size } else { 1 })
+            .sum()
+    }
+
+    /// Return the indent string with the width (in columns).
+    pub(super) fn indent_string(&self, width: usize) -> String {
+        if self.hard_tabs && self.tab_size > 0 {
+            "\t".repeat(width / self.tab_size) + &" ".repeat(width % self.tab_size)
+        } else {
+            " ".repeat(width)
+        }
+    }
 }
 
 #[derive(Default, Clone)]
@@ -209,4 +226,20 @@ mod tests {
         };
         assert_eq!(tab.to_string(), "\t");
     }
+
+    #[test]
+    fn test_indent_width() {
+        let tab = TabSize {
+            tab_size: 4,
+            hard_tabs: false,
+        };
+        assert_eq!(tab.indent_width("  \t"), 6);
+        assert_eq!(tab.indent_string(6), "      ");
+        let tab = TabSize {
+            tab_size: 4,
+            hard_tabs: true,
+        };
+        assert_eq!(tab.indent_string(6), "\t  ");
+        assert_eq!(tab.indent_string(0), "");
+    }
 }
@@ -6,16 +6,18 @@ use serde::Deserialize;
 use smallvec::SmallVec;
 use std::cell::{Cell, RefCell};
 use std::ops::{Deref, Range};
+use std::path::Path;
 use std::rc::Rc;
 use unicode_segmentation::*;
 
 use gpui::{
-    App, AppContext, Bounds, ClipboardItem, Context, Entity, EntityInputHandler, EventEmitter,
-    FocusHandle, Focusable, InteractiveElement as _, IntoElement, KeyBinding, KeyDownEvent,
-    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement as _, Pixels, Point,
-    Render, ScrollHandle, ScrollWheelEvent, SharedString, Styled as _, Subscription,
-    UTF16Selection, Window, WrappedLine, actions, div, impl_internal_actions, point,
-    prelude::FluentBuilder as _, px, relative,
+    AnyView, App, AppContext, AsyncApp, Bounds, ClipboardItem, Context, Entity, EntityInputHandler,
+    EventEmitter, FocusHandle, Focusable, FontWeight, HighlightStyle, InteractiveElement as _,
+    IntoElement, KeyBinding, KeyDownEvent, MouseButton, MouseDownEvent, MouseMoveEvent,
+    MouseUpEvent, ParentElement as _, Pixels, Point, Render, ScrollHandle, ScrollWheelEvent,
+    SharedString, StatefulInteractiveElement as _, Styled as _, StyledText, Subscription, Task,
+    UTF16Selection, WeakEntity, Window, WrappedLine, actions, anchored, deferred, div,
+    impl_internal_actions, point, prelude::FluentBuilder as _, px, relative, uniform_list,
 };
 
 // TODO:
@@ -23,16 +25,25 @@ use gpui::{
 
 use super::{
     blink_cursor::BlinkCursor,
+    bracket,
     change::Change,
+    comment,
+    completion::{self, CompletionMenu, CompletionProvider},
     element::TextElement,
+    fold::FoldMap,
     mask_pattern::MaskPattern,
     mode::{InputMode, TabSize},
     number_input,
     text_wrapper::TextWrapper,
 };
-use crate::highlighter::SyntaxHighlighter;
-use crate::input::marker::Marker;
-use crate::{Root, history::History, scroll::ScrollbarState};
+use crate::actions::{Cancel, Confirm, SelectNext, SelectPrev};
+use crate::highlighter::{HighlightResult, LanguageRegistry, SemanticToken, SyntaxHighlighter};
+use crate::input::marker::{self, Marker};
+use crate::spell_check::SpellCheck;
+use crate::tooltip::Tooltip;
+use crate::{
+    ActiveTheme as _, Icon, Root, Sizable as _, h_flex, history::History, scroll::ScrollbarState,
+};
 
 #[derive(Clone, PartialEq, Eq, Deserialize)]
 pub struct Enter {
@@ -82,6 +93,18 @@ actions!(
         MoveToEnd,
         MoveToPreviousWord,
         MoveToNextWord,
+        MoveToNextMarker,
+        MoveToPreviousMarker,
+        Fold,
+        Unfold,
+        FoldAll,
+        UnfoldAll,
+        MoveToMatchingBracket,
+        ToggleComment,
+        Reindent,
+        Hover,
+        GoToDefinition,
+        ShowCompletions,
         TextChanged,
         Escape
     ]
@@ -95,10 +118,17 @@ pub enum InputEvent {
     Focus,
     Blur,
     EmptyTextUp,
+    /// Request the hover at the offset, see [`InputState::show_hover`].
+    Hover { offset: usize },
+    /// Request to go to the definition of the symbol at the offset.
+    GoToDefinition { offset: usize },
 }
 
 pub(super) const CONTEXT: &str = "Input";
 
+/// The text longer than this (in bytes) is highlighted in the background executor.
+const BACKGROUND_HIGHLIGHT_LEN: usize = 32 * 1024;
+
 pub fn init(cx: &mut App) {
     cx.bind_keys([
         KeyBinding::new("backspace", Backspace, Some(CONTEXT)),
@@ -198,9 +228,47 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("ctrl-z", Undo, Some(CONTEXT)),
         #[cfg(not(target_os = "macos"))]
         KeyBinding::new("ctrl-y", Redo, Some(CONTEXT)),
+        KeyBinding::new("f8", MoveToNextMarker, Some(CONTEXT)),
+        KeyBinding::new("shift-f8", MoveToPreviousMarker, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("alt-cmd-[", Fold, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-[", Fold, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("alt-cmd-]", Unfold, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-]", Unfold, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-k cmd-0", FoldAll, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-k ctrl-0", FoldAll, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-k cmd-j", UnfoldAll, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-k ctrl-j", UnfoldAll, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-shift-\\", MoveToMatchingBracket, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-\\", MoveToMatchingBracket, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-/", ToggleComment, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-/", ToggleComment, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-k cmd-f", Reindent, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-k ctrl-f", Reindent, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-k cmd-i", Hover, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-k ctrl-i", Hover, Some(CONTEXT)),
+        KeyBinding::new("f12", GoToDefinition, Some(CONTEXT)),
+        KeyBinding::new("ctrl-space", ShowCompletions, Some(CONTEXT)),
     ]);
 
     number_input::init(cx);
+    // Bind after the input keys to take precedence when the completion menu is shown.
+    completion::init(cx);
 }
 
 #[derive(Clone)]
@@ -244,6 +312,8 @@ pub struct InputState {
     pub(super) input_bounds: Bounds<Pixels>,
     /// The text bounds
     pub(super) last_bounds: Option<Bounds<Pixels>>,
+    /// The visible line range of the last paint.
+    pub(super) last_visible_range: Option<Range<usize>>,
     pub(super) last_selected_range: Option<Range<usize>>,
     pub(super) selecting: bool,
     pub(super) disabled: bool,
@@ -260,8 +330,33 @@ pub struct InputState {
     pub(crate) mask_pattern: MaskPattern,
     pub(super) placeholder: SharedString,
 
+    /// The index of the marker under the mouse, only for [`InputMode::CodeEditor`] mode.
+    pub(super) hovered_marker: Option<usize>,
+    /// The tooltip of the last shown marker, to avoid rebuilding it on every render.
+    marker_tooltip: Option<(Marker, AnyView)>,
+    /// The offset and the tooltip of the hover, see [`Self::show_hover`].
+    hover: Option<(usize, AnyView)>,
+    completion_provider: Option<Rc<dyn CompletionProvider>>,
+    /// The shown completion menu, see [`Self::completion_provider`].
+    pub(super) completion: Option<CompletionMenu>,
+    /// The epoch of the completion request, to ignore the outdated completions.
+    completion_epoch: usize,
+    /// The folded lines, only for [`InputMode::CodeEditor`] mode.
+    pub(super) folds: FoldMap,
+    /// Whether to check the spelling, only for [`InputMode::MultiLine`] and
+    /// [`InputMode::AutoGrow`] modes.
+    spell_check: bool,
+    /// The byte ranges of the misspelled words, see [`Self::check_spelling`].
+    pub(super) misspellings: Vec<Range<usize>>,
+    /// The version of the spell checker and the text of the last check.
+    last_spell_check: Option<(usize, SharedString)>,
+    /// The suggestions of the misspelled word, shown by the right click.
+    spelling_menu: Option<SpellingMenu>,
+
     /// To remember the horizontal column (x-coordinate) of the cursor position.
     preferred_x_offset: Option<Pixels>,
+    /// The background highlighting of the large text, replaced on every edit.
+    _highlight_task: Option<Task<()>>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -320,6 +415,7 @@ impl InputState {
             mode: InputMode::SingleLine,
             last_layout: None,
             last_bounds: None,
+            last_visible_range: None,
             last_selected_range: None,
             last_line_height: px(20.),
             last_cursor_offset: None,
@@ -330,6 +426,18 @@ impl InputState {
             line_number_width: px(0.),
             placeholder: SharedString::default(),
             mask_pattern: MaskPattern::default(),
+            hovered_marker: None,
+            marker_tooltip: None,
+            hover: None,
+            completion_provider: None,
+            completion: None,
+            completion_epoch: 0,
+            folds: FoldMap::default(),
+            spell_check: true,
+            misspellings: vec![],
+            last_spell_check: None,
+            spelling_menu: None,
+            _highlight_task: None,
             _subscriptions,
         }
     }
@@ -446,13 +554,25 @@ impl InputState {
     pub fn set_highlighter(&mut self, language: impl Into<SharedString>, cx: &mut Context<Self>) {
         match &mut self.mode {
             InputMode::CodeEditor { highlighter, .. } => {
-                highlighter.borrow_mut().set_language(language);
+                highlighter.borrow_mut().set_language(language, cx);
             }
             _ => {}
         }
         cx.notify();
     }
 
+    /// Set highlighter by the language detected from the file path and the first line of the
+    /// text, only for [`InputMode::CodeEditor`] mode.
+    ///
+    /// The highlighting is disabled if no language is detected, see [`LanguageRegistry::detect`].
+    pub fn set_highlighter_for_path(&mut self, path: impl AsRef<Path>, cx: &mut Context<Self>) {
+        let first_line = self.text.split('\n').next().unwrap_or_default();
+        let language = LanguageRegistry::global(cx)
+            .detect(path, first_line)
+            .unwrap_or_default();
+        self.set_highlighter(language, cx);
+    }
+
     /// Set markers, only for [`InputMode::CodeEditor`] mode.
     ///
     /// For example to set the diagnostic markers in the code editor.
@@ -464,6 +584,24 @@ impl InputState {
     ) {
         if let InputMode::CodeEditor { markers, .. } = &mut self.mode {
             *markers = new_markers;
+            self.hovered_marker = None;
+            cx.notify();
+        }
+    }
+
+    /// Return the markers, empty if not [`InputMode::CodeEditor`] mode.
+    pub fn markers(&self) -> &[Marker] {
+        self.mode.markers().map_or(&[], |markers| markers.as_slice())
+    }
+
+    /// Set the semantic tokens to layer on top of the syntax highlights, only for
+    /// [`InputMode::CodeEditor`] mode.
+    ///
+    /// For example to set the semantic tokens from the language server, the style of the
+    /// tokens is from the `semantic_tokens` of the [`crate::highlighter::HighlightTheme`].
+    pub fn set_semantic_tokens(&mut self, tokens: Vec<SemanticToken>, cx: &mut Context<Self>) {
+        if let Some(highlighter) = self.mode.highlighter() {
+            highlighter.borrow_mut().set_semantic_tokens(tokens);
             cx.notify();
         }
     }
@@ -507,6 +645,11 @@ impl InputState {
         let mut prev_lines_offset = 0;
         let mut y_offset = px(0.);
         for (line_index, line) in lines.iter().enumerate() {
+            if self.is_line_hidden(line_index) {
+                prev_lines_offset += line.len() + 1;
+                continue;
+            }
+
             let local_offset = offset.saturating_sub(prev_lines_offset);
             if let Some(pos) = line.position_for_index(local_offset, line_height) {
                 let sub_line_index = (pos.y.0 / line_height.0) as usize;
@@ -575,6 +718,19 @@ impl InputState {
             }
         }
 
+        // Skip the lines hidden by folds, the fold header line is always visible.
+        while self.is_line_hidden(new_line_index) {
+            if direction > 0 && new_line_index < lines.len() - 1 {
+                new_line_index += 1;
+                new_sub_line = 0;
+            } else if direction < 0 && new_line_index > 0 {
+                new_line_index -= 1;
+                new_sub_line = lines[new_line_index].wrap_boundaries.len() as i32;
+            } else {
+                return;
+            }
+        }
+
         // If after adjustment, still at the same position, do not proceed
         if new_line_index == current_line_index && new_sub_line == current_sub_line as i32 {
             return;
@@ -654,6 +810,79 @@ impl InputState {
         self.move_to(end, window, cx);
     }
 
+    /// Move the cursor to the UTF-8 offset, e.g.: the location of the definition.
+    pub fn move_cursor_to(&mut self, offset: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let offset = if offset >= self.text.len() {
+            self.text.len()
+        } else {
+            self.previous_boundary(offset + 1)
+        };
+        self.move_to(offset, window, cx);
+    }
+
+    /// Return the selected UTF-8 range, the `start` is the anchor and the `end` is the cursor,
+    /// so `start > end` if the selection is reversed.
+    pub fn selection(&self) -> Range<usize> {
+        if self.selection_reversed {
+            self.selected_range.end..self.selected_range.start
+        } else {
+            self.selected_range.clone()
+        }
+    }
+
+    /// Select the UTF-8 range, see [`InputState::selection`], e.g.: to restore a draft.
+    ///
+    /// The offsets are clipped to the text and the char boundaries.
+    pub fn set_selection(
+        &mut self,
+        selection: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let clip = |offset: usize| {
+            let mut offset = offset.min(self.text.len());
+            while !self.text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+            offset
+        };
+        let (anchor, cursor) = (clip(selection.start), clip(selection.end));
+
+        self.move_to(cursor, window, cx);
+        self.selection_reversed = anchor > cursor;
+        self.selected_range = anchor.min(cursor)..anchor.max(cursor);
+        cx.notify();
+    }
+
+    /// Show the hover text (e.g.: from the language server) below the offset, only for
+    /// [`InputMode::CodeEditor`] mode.
+    ///
+    /// The hover is hidden when the cursor moved or the text changed.
+    pub fn show_hover(
+        &mut self,
+        offset: usize,
+        text: impl Into<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode.is_code_editor() || offset > self.text.len() {
+            return;
+        }
+
+        let text: SharedString = text.into();
+        // Strip the code fences of the markdown.
+        let text: SharedString = text
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("```"))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .into();
+        let view = Tooltip::element(move |_, _| div().max_w(px(480.)).child(text.clone()))
+            .build(window, cx);
+        self.hover = Some((offset, view));
+        cx.notify();
+    }
+
     /// Insert text at the current cursor position.
     ///
     /// And the cursor will be moved to the end of inserted text.
@@ -727,6 +956,38 @@ impl InputState {
         cx.notify();
     }
 
+    /// Set the completion provider to show the completion menu when typing.
+    pub fn completion_provider(mut self, provider: Rc<dyn CompletionProvider>) -> Self {
+        self.completion_provider = Some(provider);
+        self
+    }
+
+    /// Set the completion provider, `None` to disable the completion.
+    pub fn set_completion_provider(
+        &mut self,
+        provider: Option<Rc<dyn CompletionProvider>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.completion_provider = provider;
+        self.hide_completion(cx);
+    }
+
+    /// Set false to disable the spell checking, default is true.
+    ///
+    /// Only the [`InputMode::MultiLine`] and [`InputMode::AutoGrow`] modes are checked, with the
+    /// checker of the [`SpellCheck`] global.
+    pub fn spell_check(mut self, spell_check: bool) -> Self {
+        self.spell_check = spell_check;
+        self
+    }
+
+    /// Set whether to check the spelling.
+    pub fn set_spell_check(&mut self, spell_check: bool, cx: &mut Context<Self>) {
+        self.spell_check = spell_check;
+        self.last_spell_check = None;
+        cx.notify();
+    }
+
     /// Set true to clear the input by pressing Escape key.
     pub fn clean_on_escape(mut self) -> Self {
         self.clean_on_escape = true;
@@ -770,7 +1031,8 @@ impl InputState {
     pub(super) fn left(&mut self, _: &Left, window: &mut Window, cx: &mut Context<Self>) {
         self.pause_blink_cursor(cx);
         if self.selected_range.is_empty() {
-            self.move_to(self.previous_boundary(self.cursor_offset()), window, cx);
+            let offset = self.skip_folded(self.previous_boundary(self.cursor_offset()), false);
+            self.move_to(offset, window, cx);
         } else {
             self.move_to(self.selected_range.start, window, cx)
         }
@@ -779,7 +1041,8 @@ impl InputState {
     pub(super) fn right(&mut self, _: &Right, window: &mut Window, cx: &mut Context<Self>) {
         self.pause_blink_cursor(cx);
         if self.selected_range.is_empty() {
-            self.move_to(self.next_boundary(self.selected_range.end), window, cx);
+            let offset = self.skip_folded(self.next_boundary(self.selected_range.end), true);
+            self.move_to(offset, window, cx);
         } else {
             self.move_to(self.selected_range.end, window, cx)
         }
@@ -883,43 +1146,582 @@ impl InputState {
         self.move_to(offset, window, cx);
     }
 
-    pub(super) fn move_to_start(
-        &mut self,
-        _: &MoveToStart,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        self.move_to(0, window, cx);
+    pub(super) fn move_to_start(
+        &mut self,
+        _: &MoveToStart,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_to(0, window, cx);
+    }
+
+    pub(super) fn move_to_end(
+        &mut self,
+        _: &MoveToEnd,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let end = self.text.len();
+        self.move_to(end, window, cx);
+    }
+
+    pub(super) fn move_to_previous_word(
+        &mut self,
+        _: &MoveToPreviousWord,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let offset = self.previous_start_of_word();
+        self.move_to(offset, window, cx);
+    }
+
+    pub(super) fn move_to_next_word(
+        &mut self,
+        _: &MoveToNextWord,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let offset = self.next_end_of_word();
+        self.move_to(offset, window, cx);
+    }
+
+    pub(super) fn move_to_next_marker(
+        &mut self,
+        _: &MoveToNextMarker,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(offset) = marker::next_start(&self.marker_starts(), self.cursor_offset()) else {
+            return;
+        };
+
+        self.move_to(offset, window, cx);
+    }
+
+    pub(super) fn move_to_previous_marker(
+        &mut self,
+        _: &MoveToPreviousMarker,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(offset) = marker::previous_start(&self.marker_starts(), self.cursor_offset())
+        else {
+            return;
+        };
+
+        self.move_to(offset, window, cx);
+    }
+
+    /// Return the sorted and deduplicated start offsets of the markers.
+    fn marker_starts(&self) -> Vec<usize> {
+        let Some(markers) = self.mode.markers() else {
+            return vec![];
+        };
+
+        marker::sorted_starts(markers.iter().filter_map(|marker| marker.byte_range(self)))
+    }
+
+    /// Return the index of the most severe marker that contains the given offset.
+    ///
+    /// The end of the marker range is inclusive, so the cursor placed right after the
+    /// marked text still matches it.
+    fn marker_at(&self, offset: usize) -> Option<usize> {
+        let markers = self.mode.markers()?;
+        marker::most_severe_at(
+            markers
+                .iter()
+                .map(|marker| (marker.byte_range(self), marker.severity)),
+            offset,
+        )
+    }
+
+    /// Return the index of the most severe marker that starts at the given line (zero based).
+    fn marker_on_line(&self, line_ix: usize) -> Option<usize> {
+        self.mode
+            .markers()?
+            .iter()
+            .enumerate()
+            .filter(|(_, marker)| marker.start.line.saturating_sub(1) == line_ix)
+            .max_by_key(|(_, marker)| marker.severity.priority())
+            .map(|(ix, _)| ix)
+    }
+
+    /// Return the line index (zero based, no wrap) of the given offset.
+    pub(super) fn line_index_for_offset(&self, offset: usize) -> usize {
+        self.text_wrapper
+            .lines
+            .iter()
+            .position(|line| line.range.end >= offset)
+            .unwrap_or(self.text_wrapper.lines.len().saturating_sub(1))
+    }
+
+    pub(super) fn fold(&mut self, _: &Fold, window: &mut Window, cx: &mut Context<Self>) {
+        let line_ix = self.line_index_for_offset(self.cursor_offset());
+        if self.folds.fold(line_ix).is_some() {
+            self.move_out_of_folds(window, cx);
+            cx.notify();
+        }
+    }
+
+    pub(super) fn unfold(&mut self, _: &Unfold, _: &mut Window, cx: &mut Context<Self>) {
+        let line_ix = self.line_index_for_offset(self.cursor_offset());
+        if self.folds.unfold(line_ix).is_some() {
+            cx.notify();
+        }
+    }
+
+    pub(super) fn fold_all(&mut self, _: &FoldAll, window: &mut Window, cx: &mut Context<Self>) {
+        self.folds.fold_all();
+        self.move_out_of_folds(window, cx);
+        cx.notify();
+    }
+
+    pub(super) fn unfold_all(&mut self, _: &UnfoldAll, _: &mut Window, cx: &mut Context<Self>) {
+        self.folds.unfold_all();
+        cx.notify();
+    }
+
+    /// Toggle the fold with the header at the given line (zero based), used by the gutter.
+    pub(super) fn toggle_fold(
+        &mut self,
+        line_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.folds.toggle(line_ix).is_some() {
+            self.move_out_of_folds(window, cx);
+            cx.notify();
+        }
+    }
+
+    /// Return true if the line (zero based, no wrap) is hidden by a fold.
+    #[inline]
+    pub(super) fn is_line_hidden(&self, line_ix: usize) -> bool {
+        self.folds.is_hidden(line_ix)
+    }
+
+    /// Move the cursor to the end of the fold header line, if the cursor is in hidden lines.
+    fn move_out_of_folds(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.skip_folded(self.cursor_offset(), false);
+        if offset != self.cursor_offset() {
+            self.move_to(offset, window, cx);
+        }
+    }
+
+    /// Move the offset out of the hidden lines.
+    ///
+    /// - `forward` is true: to the start of the line after the fold.
+    /// - `forward` is false: to the end of the fold header line.
+    fn skip_folded(&self, offset: usize, forward: bool) -> usize {
+        let line_ix = self.line_index_for_offset(offset);
+        let Some(range) = self.folds.folded_range_for(line_ix) else {
+            return offset;
+        };
+
+        let lines = &self.text_wrapper.lines;
+        if forward {
+            lines
+                .get(range.end)
+                .map_or(self.text.len(), |line| line.range.start)
+        } else {
+            lines.get(range.start).map_or(0, |line| line.range.end)
+        }
+    }
+
+    /// Unfold the folds that hide the offset, to make sure the cursor is visible.
+    fn reveal_offset(&mut self, offset: usize) {
+        if self.folds.is_empty() {
+            return;
+        }
+
+        let line_ix = self.line_index_for_offset(offset);
+        self.folds.reveal(line_ix);
+    }
+
+    /// Highlight the edited text, only for [`InputMode::CodeEditor`] mode.
+    ///
+    /// The large text is highlighted in the background executor, the visible lines first,
+    /// and the stale highlights are shown until the new ones arrive.
+    fn update_highlights(&mut self, cx: &mut Context<Self>) {
+        let Some(highlighter) = self.mode.highlighter() else {
+            return;
+        };
+        let Some(mut snapshot) = highlighter.borrow_mut().snapshot() else {
+            return;
+        };
+
+        if self.text.len() < BACKGROUND_HIGHLIGHT_LEN {
+            self._highlight_task = None;
+            if let Some(result) = snapshot.highlight(None) {
+                highlighter.borrow_mut().apply(result);
+            }
+            return;
+        }
+
+        let visible_range = self.last_visible_range.as_ref().and_then(|range| {
+            let lines = &self.text_wrapper.lines;
+            let start = lines.get(range.start)?.range.start;
+            let end = lines
+                .get(range.end.min(lines.len().saturating_sub(1)))?
+                .range
+                .end;
+            Some(start..end)
+        });
+
+        let background = cx.background_executor().clone();
+        self._highlight_task = Some(cx.spawn(async move |this, cx| {
+            let mut done = false;
+            if let Some(visible_range) = visible_range {
+                let (visible_snapshot, result) = background
+                    .spawn(async move {
+                        let result = snapshot.highlight(Some(visible_range));
+                        (snapshot, result)
+                    })
+                    .await;
+                snapshot = visible_snapshot;
+
+                let Some(result) = result else {
+                    return;
+                };
+                done = result.is_complete();
+                if !Self::apply_highlights(&this, result, cx) {
+                    return;
+                }
+            }
+
+            if !done {
+                let result = background
+                    .spawn(async move { snapshot.highlight(None) })
+                    .await;
+                if let Some(result) = result {
+                    Self::apply_highlights(&this, result, cx);
+                }
+            }
+        }));
+    }
+
+    /// Apply the highlight result of the background task, returns false if it is outdated.
+    fn apply_highlights(
+        this: &WeakEntity<Self>,
+        result: HighlightResult,
+        cx: &mut AsyncApp,
+    ) -> bool {
+        this.update(cx, |state, cx| {
+            let Some(highlighter) = state.mode.highlighter() else {
+                return false;
+            };
+            if !highlighter.borrow_mut().apply(result) {
+                return false;
+            }
+            cx.notify();
+            true
+        })
+        .unwrap_or(false)
+    }
+
+    /// Move or unfold the folded ranges before replacing the `range` with `new_text`.
+    fn update_folds_for_edit(&mut self, range: &Range<usize>, new_text: &str) {
+        if self.folds.is_empty() {
+            return;
+        }
+
+        let start = self.line_index_for_offset(range.start);
+        let old_end = start + self.text[range.clone()].matches('\n').count();
+        let new_end = start + new_text.matches('\n').count();
+        self.folds.edit(start, old_end, new_end);
+    }
+
+    pub(super) fn hover(&mut self, _: &Hover, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(InputEvent::Hover {
+            offset: self.cursor_offset(),
+        });
+    }
+
+    pub(super) fn go_to_definition(
+        &mut self,
+        _: &GoToDefinition,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.emit(InputEvent::GoToDefinition {
+            offset: self.cursor_offset(),
+        });
+    }
+
+    /// Show the completion menu of the query before the cursor, even if the query is empty.
+    pub fn show_completion(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.request_completion(true, window, cx);
+    }
+
+    pub(super) fn show_completions(
+        &mut self,
+        _: &ShowCompletions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_completion(window, cx);
+    }
+
+    /// Filter the shown completion menu with the query before the cursor, or request the
+    /// completions from the provider if the query is started.
+    ///
+    /// The menu is only opened for an empty query if `manual` is true.
+    fn request_completion(&mut self, manual: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(provider) = self.completion_provider.clone() else {
+            return;
+        };
+        let Some(query_range) = provider.query_range(&self.text, self.cursor_offset()) else {
+            return self.hide_completion(cx);
+        };
+
+        if let Some(menu) = self.completion.as_mut() {
+            if menu.query_range.start == query_range.start {
+                menu.query_range = query_range.clone();
+                menu.filter(&self.text[query_range]);
+                if menu.is_empty() {
+                    self.completion = None;
+                }
+                cx.notify();
+                return;
+            }
+        }
+
+        if !manual && query_range.is_empty() {
+            return self.hide_completion(cx);
+        }
+
+        self.completion_epoch += 1;
+        let epoch = self.completion_epoch;
+        let task = provider.completions(&self.text, query_range.clone(), window, cx);
+        cx.spawn(async move |this, cx| {
+            let items = task.await;
+            _ = this.update(cx, |this, cx| {
+                if this.completion_epoch != epoch {
+                    return;
+                }
+                let items = match items {
+                    Ok(items) => items,
+                    Err(err) => {
+                        tracing::warn!("failed to load completions: {:?}", err);
+                        return;
+                    }
+                };
+
+                // The query may be changed while loading.
+                let Some(range) = provider.query_range(&this.text, this.cursor_offset()) else {
+                    return;
+                };
+                if range.start != query_range.start {
+                    return;
+                }
+
+                let mut menu = CompletionMenu::new(range.clone(), items);
+                menu.filter(&this.text[range]);
+                this.completion = (!menu.is_empty()).then_some(menu);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn hide_completion(&mut self, cx: &mut Context<Self>) {
+        self.completion_epoch += 1;
+        if self.completion.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    /// Replace the query with the `ix`th item of the completion menu.
+    fn confirm_completion(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(menu) = self.completion.take() else {
+            return;
+        };
+        self.completion_epoch += 1;
+        let Some((item, _)) = menu.item(ix) else {
+            return;
+        };
+
+        let range = menu.query_range.start..self.cursor_offset().max(menu.query_range.start);
+        let range_utf16 = self.range_to_utf16(&range);
+        self.replace_text_in_range(Some(range_utf16), item.text_to_insert(), window, cx);
+    }
+
+    pub(super) fn select_prev_completion(
+        &mut self,
+        _: &SelectPrev,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(menu) = self.completion.as_mut() {
+            menu.select_prev();
+            cx.notify();
+        }
+    }
+
+    pub(super) fn select_next_completion(
+        &mut self,
+        _: &SelectNext,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(menu) = self.completion.as_mut() {
+            menu.select_next();
+            cx.notify();
+        }
+    }
+
+    pub(super) fn confirm_selected_completion(
+        &mut self,
+        _: &Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self.completion.as_ref().map(|menu| menu.selected_ix()) else {
+            return;
+        };
+        self.confirm_completion(ix, window, cx);
+    }
+
+    pub(super) fn cancel_completion(&mut self, _: &Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        self.hide_completion(cx);
+    }
+
+    pub(super) fn move_to_matching_bracket(
+        &mut self,
+        _: &MoveToMatchingBracket,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some((_, matching)) = self.matching_brackets() {
+            self.move_to(matching, window, cx);
+        }
+    }
+
+    /// Return the offsets of the bracket next to the cursor and its matching bracket.
+    ///
+    /// The syntax tree is used to match the brackets if it is parsed for the current text.
+    pub(super) fn matching_brackets(&self) -> Option<(usize, usize)> {
+        if !self.selected_range.is_empty() {
+            return None;
+        }
+
+        let offset = bracket::bracket_near(&self.text, self.cursor_offset())?;
+        let matching = match self.mode.highlighter() {
+            Some(highlighter) if highlighter.borrow().is_parsed() => {
+                highlighter.borrow().matching_bracket(offset)
+            }
+            _ => bracket::find_matching_bracket(&self.text, offset),
+        }?;
+
+        // Make sure the matching offset is a bracket of the text.
+        if !matches!(
+            self.text.as_bytes().get(matching),
+            Some(b'(' | b')' | b'[' | b']' | b'{' | b'}')
+        ) {
+            return None;
+        }
+
+        Some((offset, matching))
+    }
+
+    /// Return the `(open, close)` pairs to auto-close, only for [`InputMode::CodeEditor`] mode.
+    fn auto_close_pairs(&self) -> &'static [(char, char)] {
+        let Some(highlighter) = self.mode.highlighter() else {
+            return &[];
+        };
+
+        match highlighter.borrow().language() {
+            Some(language) => language.auto_close_pairs(),
+            None => bracket::DEFAULT_PAIRS,
+        }
+    }
+
+    /// Handle the auto-close pairs on typing a char, returns true if the input is handled.
+    ///
+    /// - Typing an open char with selection, wraps the selection with the pair.
+    /// - Typing a close char before the same char, moves the cursor over it.
+    /// - Typing an open char, inserts the close char after the cursor.
+    fn handle_auto_close(
+        &mut self,
+        new_text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let mut chars = new_text.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return false;
+        };
+        let pairs = self.auto_close_pairs();
+        let range = self.selected_range.clone();
+
+        if !range.is_empty() {
+            let Some((open, close)) = pairs.iter().find(|(open, _)| *open == c) else {
+                return false;
+            };
+
+            let text = format!("{}{}{}", open, &self.text[range.clone()], close);
+            self.replace_text_in_range(Some(self.range_to_utf16(&range)), &text, window, cx);
+            self.selected_range = range.start + 1..range.end + 1;
+            return true;
+        }
+
+        let offset = range.start;
+        let prev_char = self.text[..offset].chars().next_back();
+        let next_char = self.text[offset..].chars().next();
+
+        if pairs.iter().any(|(_, close)| *close == c) && next_char == Some(c) {
+            self.move_to(offset + c.len_utf8(), window, cx);
+            return true;
+        }
+
+        let Some((open, close)) = pairs.iter().find(|(open, _)| *open == c) else {
+            return false;
+        };
+
+        let before_word_end = next_char.is_none_or(|next| {
+            next.is_whitespace() || pairs.iter().any(|(_, close)| *close == next)
+        });
+        // Avoid to close the quote after a word, e.g.: `don't`.
+        let after_word = open == close && prev_char.is_some_and(|prev| prev.is_alphanumeric());
+        if !before_word_end || after_word {
+            return false;
+        }
+
+        let text = format!("{}{}", open, close);
+        self.replace_text_in_range(Some(self.range_to_utf16(&range)), &text, window, cx);
+        self.move_to(offset + open.len_utf8(), window, cx);
+        true
     }
 
-    pub(super) fn move_to_end(
-        &mut self,
-        _: &MoveToEnd,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        let end = self.text.len();
-        self.move_to(end, window, cx);
-    }
+    /// Return the auto-close pair around the cursor, e.g.: `(|)`.
+    fn auto_close_pair_at_cursor(&self) -> Option<(char, char)> {
+        if !self.selected_range.is_empty() {
+            return None;
+        }
 
-    pub(super) fn move_to_previous_word(
-        &mut self,
-        _: &MoveToPreviousWord,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        let offset = self.previous_start_of_word();
-        self.move_to(offset, window, cx);
+        let offset = self.cursor_offset();
+        let prev_char = self.text[..offset].chars().next_back()?;
+        let next_char = self.text[offset..].chars().next()?;
+        self.auto_close_pairs()
+            .iter()
+            .find(|(open, close)| *open == prev_char && *close == next_char)
+            .copied()
     }
 
-    pub(super) fn move_to_next_word(
-        &mut self,
-        _: &MoveToNextWord,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        let offset = self.next_end_of_word();
-        self.move_to(offset, window, cx);
+    /// Return the marker to show the message for.
+    ///
+    /// The hovered marker wins, otherwise the marker under the cursor is used when focused.
+    fn active_marker(&self, window: &Window) -> Option<&Marker> {
+        let markers = self.mode.markers()?;
+        if let Some(marker) = self.hovered_marker.and_then(|ix| markers.get(ix)) {
+            return Some(marker);
+        }
+
+        if !self.focus_handle.is_focused(window) || !self.selected_range.is_empty() {
+            return None;
+        }
+
+        self.marker_at(self.cursor_offset()).and_then(|ix| markers.get(ix))
     }
 
     pub(super) fn select_to_start(
@@ -1120,7 +1922,215 @@ impl InputState {
         }
     }
 
+    /// Get indent string of next line by the syntax tree, `None` if no syntax tree.
+    ///
+    /// The indent is relative to the current line, by the difference of the indent levels.
+    fn syntax_indent_of_next_line(&self) -> Option<String> {
+        let tab_size = self.mode.tab_size()?;
+        let highlighter = self.mode.highlighter()?.borrow();
+
+        let offset = self.cursor_offset();
+        let line_start = self.text[..offset].rfind('\n').map_or(0, |ix| ix + 1);
+        let line = &self.text[line_start..offset];
+        let indent = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+        let regions = highlighter.indent_regions()?;
+        let level = regions.level(line_start, line_start + indent.len());
+
+        let rest = &self.text[offset..];
+        let next_content_start = offset + rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        let mut next_level = regions.level(offset, next_content_start);
+        if highlighter
+            .language()
+            .is_some_and(|language| language.indents_after_colon())
+            && line.trim_end().ends_with(':')
+        {
+            next_level = next_level.max(level + 1);
+        }
+
+        let width = tab_size.indent_width(indent) as isize
+            + (next_level as isize - level as isize) * tab_size.tab_size as isize;
+        Some(tab_size.indent_string(width.max(0) as usize))
+    }
+
+    /// Reindent the lines in the range by the syntax tree, returns true if the text changed.
+    ///
+    /// The indent is relative to the previous non-blank line before the range.
+    fn reindent_lines(
+        &mut self,
+        range: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(tab_size) = self.mode.tab_size().copied() else {
+            return false;
+        };
+        let Some(highlighter) = self.mode.highlighter().cloned() else {
+            return false;
+        };
+
+        let start = self.text[..range.start].rfind('\n').map_or(0, |ix| ix + 1);
+        let end = self.text[range.end..]
+            .find('\n')
+            .map_or(self.text.len(), |ix| range.end + ix);
+        let indent_len = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+
+        let new_text = {
+            let Some(regions) = highlighter.borrow().indent_regions() else {
+                return false;
+            };
+
+            // The indent width and level of the previous non-blank line as the base.
+            let mut base = (0, 0);
+            let mut line_end = start.saturating_sub(1);
+            while line_end > 0 {
+                let line_start = self.text[..line_end].rfind('\n').map_or(0, |ix| ix + 1);
+                let line = &self.text[line_start..line_end];
+                if !line.trim().is_empty() {
+                    let indent = &line[..indent_len(line)];
+                    let level = regions.level(line_start, line_start + indent.len());
+                    base = (tab_size.indent_width(indent) as isize, level as isize);
+                    break;
+                }
+                line_end = line_start.saturating_sub(1);
+            }
+
+            let mut lines = vec![];
+            let mut line_start = start;
+            for line in self.text[start..end].split('\n') {
+                if line.trim().is_empty() {
+                    lines.push(String::new());
+                } else {
+                    let content_start = line_start + indent_len(line);
+                    let level = regions.level(line_start, content_start);
+                    let width = base.0 + (level as isize - base.1) * tab_size.tab_size as isize;
+                    lines.push(format!(
+                        "{}{}",
+                        tab_size.indent_string(width.max(0) as usize),
+                        line.trim_start_matches([' ', '\t'])
+                    ));
+                }
+                // +1 for the `\n`
+                line_start += line.len() + 1;
+            }
+            lines.join("\n")
+        };
+
+        if new_text == self.text[start..end] {
+            return false;
+        }
+
+        self.replace_text_in_range(
+            Some(self.range_to_utf16(&(start..end))),
+            &new_text,
+            window,
+            cx,
+        );
+        self.selected_range = start..start + new_text.len();
+        true
+    }
+
+    pub(super) fn reindent(&mut self, _: &Reindent, window: &mut Window, cx: &mut Context<Self>) {
+        let range = self.selected_range.clone();
+        let cursor_to_end = self.text.len() - self.cursor_offset();
+        if self.reindent_lines(range.clone(), window, cx) && range.is_empty() {
+            // Keep the cursor at the same position from the end of the text.
+            let offset = self.text.len().saturating_sub(cursor_to_end);
+            self.move_to(offset.max(self.selected_range.start), window, cx);
+        }
+    }
+
+    /// Outdent the line when typing a closing bracket at the start of the line, e.g.: `}`.
+    fn outdent_closing_bracket(
+        &mut self,
+        new_text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !bracket::BRACKETS
+            .iter()
+            .any(|(_, close)| new_text.len() == 1 && new_text.starts_with(*close))
+        {
+            return;
+        }
+
+        let offset = self.cursor_offset();
+        if !self.text[..offset].ends_with(new_text) {
+            return;
+        }
+        let line_start = self.text[..offset].rfind('\n').map_or(0, |ix| ix + 1);
+        if !self.text[line_start..offset - new_text.len()]
+            .trim()
+            .is_empty()
+        {
+            return;
+        }
+
+        let cursor_to_end = self.text.len() - offset;
+        if self.reindent_lines(offset..offset, window, cx) {
+            self.move_to(self.text.len() - cursor_to_end, window, cx);
+        }
+    }
+
+    pub(super) fn toggle_comment(
+        &mut self,
+        _: &ToggleComment,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(highlighter) = self.mode.highlighter() else {
+            return;
+        };
+        let (line_comment, block_comment) = {
+            let highlighter = highlighter.borrow();
+            (
+                highlighter.line_comment().cloned(),
+                highlighter.block_comment().cloned(),
+            )
+        };
+
+        let range = self.selected_range.clone();
+        let start = self.text[..range.start].rfind('\n').map_or(0, |ix| ix + 1);
+        let mut end = range.end;
+        // Ignore the last line if the selection ends at the start of it.
+        if end > start && self.text[..end].ends_with('\n') {
+            end -= 1;
+        }
+        let end = self.text[end..]
+            .find('\n')
+            .map_or(self.text.len(), |ix| end + ix);
+
+        let old_text = &self.text[start..end];
+        let new_text = if let Some(token) = line_comment {
+            comment::toggle_line_comment(old_text, &token)
+        } else if let Some((open, close)) = block_comment {
+            comment::toggle_block_comment(old_text, &open, &close)
+        } else {
+            return;
+        };
+
+        let cursor_to_end = end - self.cursor_offset().min(end);
+        self.replace_text_in_range(
+            Some(self.range_to_utf16(&(start..end))),
+            &new_text,
+            window,
+            cx,
+        );
+        if range.is_empty() {
+            // Keep the cursor at the same position from the end of the line.
+            let offset = (start + new_text.len()).saturating_sub(cursor_to_end);
+            self.move_to(offset.max(start), window, cx);
+        } else {
+            self.selected_range = start..start + new_text.len();
+        }
+    }
+
     pub(super) fn backspace(&mut self, _: &Backspace, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some((open, close)) = self.auto_close_pair_at_cursor() {
+            // Delete the pair together, e.g.: `(|)`.
+            let offset = self.cursor_offset();
+            self.selected_range = offset - open.len_utf8()..offset + close.len_utf8();
+        }
+
         if self.selected_range.is_empty() {
             self.select_to(self.previous_boundary(self.cursor_offset()), window, cx)
         }
@@ -1210,14 +2220,29 @@ impl InputState {
     pub(super) fn enter(&mut self, action: &Enter, window: &mut Window, cx: &mut Context<Self>) {
         if self.is_multi_line() && !action.secondary {
             let indent = if self.mode.is_code_editor() {
-                self.indent_of_next_line(window, cx)
+                match self.syntax_indent_of_next_line() {
+                    Some(indent) => indent,
+                    None => self.indent_of_next_line(window, cx),
+                }
             } else {
                 "".to_string()
             };
 
-            // Add newline and indent
-            let new_line_text = format!("\n{}", indent);
-            self.replace_text_in_range(None, &new_line_text, window, cx);
+            let pair = self
+                .auto_close_pair_at_cursor()
+                .filter(|pair| bracket::BRACKETS.contains(pair));
+            if let (Some(_), Some(tab_size)) = (pair, self.mode.tab_size()) {
+                // Split the brackets into lines, and move the cursor to the indented middle line.
+                let inner_indent = format!("{}{}", indent, tab_size.to_string());
+                let new_line_text = format!("\n{}\n{}", inner_indent, indent);
+                let offset = self.cursor_offset();
+                self.replace_text_in_range(None, &new_line_text, window, cx);
+                self.move_to(offset + 1 + inner_indent.len(), window, cx);
+            } else {
+                // Add newline and indent
+                let new_line_text = format!("\n{}", indent);
+                self.replace_text_in_range(None, &new_line_text, window, cx);
+            }
         }
 
         cx.emit(InputEvent::PressEnter {
@@ -1341,6 +2366,10 @@ impl InputState {
     }
 
     pub(super) fn escape(&mut self, _: &Escape, window: &mut Window, cx: &mut Context<Self>) {
+        if self.hover.take().is_some() || self.spelling_menu.take().is_some() {
+            cx.notify();
+            return;
+        }
         if self.marked_range.is_some() {
             self.unmark_text(window, cx);
         }
@@ -1384,6 +2413,77 @@ impl InputState {
         }
     }
 
+    /// Show the suggestions of the misspelled word under the mouse.
+    pub(super) fn on_right_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let offset = self.index_for_mouse_position(event.position, window, cx);
+        let Some(range) = self
+            .misspellings
+            .iter()
+            .find(|range| range.start <= offset && offset <= range.end)
+            .cloned()
+        else {
+            return;
+        };
+
+        cx.stop_propagation();
+        self.hide_completion(cx);
+        self.hover = None;
+        self.spelling_menu = Some(SpellingMenu {
+            suggestions: SpellCheck::global(cx).suggest(&self.text[range.clone()]),
+            range,
+            position: event.position,
+        });
+        cx.notify();
+    }
+
+    /// Replace the misspelled word of the spelling menu with the suggestion.
+    fn confirm_spelling(&mut self, suggestion: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(menu) = self.spelling_menu.take() else {
+            return;
+        };
+
+        let range = self.range_to_utf16(&menu.range);
+        self.replace_text_in_range(Some(range), suggestion, window, cx);
+    }
+
+    /// Add the misspelled word of the spelling menu to the dictionary.
+    fn add_to_dictionary(&mut self, cx: &mut Context<Self>) {
+        let Some(menu) = self.spelling_menu.take() else {
+            return;
+        };
+
+        let word = self.text[menu.range].to_string();
+        SpellCheck::add_word(&word, cx);
+        cx.notify();
+    }
+
+    /// Check the spelling of the text if changed since the last check.
+    fn check_spelling(&mut self, cx: &App) {
+        let enabled = self.spell_check
+            && !self.masked
+            && matches!(
+                self.mode,
+                InputMode::MultiLine { .. } | InputMode::AutoGrow { .. }
+            );
+        let Some(spell_check) = cx.try_global::<SpellCheck>().filter(|_| enabled) else {
+            self.misspellings.clear();
+            self.last_spell_check = None;
+            return;
+        };
+
+        let key = (spell_check.version(), self.text.clone());
+        if self.last_spell_check.as_ref() == Some(&key) {
+            return;
+        }
+        self.misspellings = spell_check.check(&self.text);
+        self.last_spell_check = Some(key);
+    }
+
     pub(super) fn on_mouse_up(
         &mut self,
         _: &MouseUpEvent,
@@ -1515,7 +2615,11 @@ impl InputState {
     /// Ensure the offset use self.next_boundary or self.previous_boundary to get the correct offset.
     fn move_to(&mut self, offset: usize, _: &mut Window, cx: &mut Context<Self>) {
         let offset = offset.clamp(0, self.text.len());
+        self.reveal_offset(offset);
         self.selected_range = offset..offset;
+        self.hover = None;
+        self.spelling_menu = None;
+        self.hide_completion(cx);
         self.pause_blink_cursor(cx);
         self.update_preferred_x_offset(cx);
         cx.notify()
@@ -1566,7 +2670,13 @@ impl InputState {
         let mut index = 0;
         let mut y_offset = px(0.);
 
-        for (_, line) in lines.iter().enumerate() {
+        for (ix, line) in lines.iter().enumerate() {
+            if self.is_line_hidden(ix) {
+                // +1 for revert `lines` split `\n`
+                index += line.len() + 1;
+                continue;
+            }
+
             let line_origin = self.line_origin_with_y_offset(&mut y_offset, &line, line_height);
             let pos = inner_position - line_origin;
 
@@ -1638,6 +2748,7 @@ impl InputState {
     /// Ensure the offset use self.next_boundary or self.previous_boundary to get the correct offset.
     fn select_to(&mut self, offset: usize, _: &mut Window, cx: &mut Context<Self>) {
         let offset = offset.clamp(0, self.text.len());
+        self.reveal_offset(offset);
         if self.selection_reversed {
             self.selected_range.start = offset
         } else {
@@ -1793,6 +2904,7 @@ impl InputState {
 
     fn on_blur(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.unselect(window, cx);
+        self.hide_completion(cx);
         self.blink_cursor.update(cx, |cursor, cx| {
             cursor.stop(cx);
         });
@@ -1838,6 +2950,40 @@ impl InputState {
         self.select_to(offset, window, cx);
     }
 
+    pub(super) fn on_hover_move(
+        &mut self,
+        event: &MouseMoveEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.mode.markers().is_none_or(|markers| markers.is_empty()) {
+            if self.hovered_marker.take().is_some() {
+                cx.notify();
+            }
+            return;
+        }
+
+        let hovered_marker = if self.input_bounds.contains(&event.position) {
+            let offset = self.index_for_mouse_position(event.position, window, cx);
+            let in_gutter = self.last_bounds.is_some_and(|bounds| {
+                event.position.x < bounds.origin.x + self.line_number_width
+            });
+
+            if in_gutter {
+                self.marker_on_line(self.line_index_for_offset(offset))
+            } else {
+                self.marker_at(offset)
+            }
+        } else {
+            None
+        };
+
+        if self.hovered_marker != hovered_marker {
+            self.hovered_marker = hovered_marker;
+            cx.notify();
+        }
+    }
+
     fn is_valid_input(&self, new_text: &str) -> bool {
         if new_text.is_empty() {
             return true;
@@ -1958,6 +3104,12 @@ impl EntityInputHandler for InputState {
             return;
         }
 
+        // Typing in the editor, the `range_utf16` is only `None` from the platform input.
+        let typing = range_utf16.is_none() && self.marked_range.is_none();
+        if typing && self.handle_auto_close(new_text, window, cx) {
+            return;
+        }
+
         let range = range_utf16
             .as_ref()
             .map(|range_utf16| self.range_from_utf16(range_utf16))
@@ -1978,13 +3130,19 @@ impl EntityInputHandler for InputState {
         let new_pos = (range.start + new_text_len).min(mask_text.len());
 
         self.push_history(&range, &new_text, window, cx);
+        self.update_folds_for_edit(&range, &new_text);
         self.text = mask_text.clone();
         if let Some(highlighter) = self.mode.highlighter() {
             highlighter
                 .borrow_mut()
-                .update(&range, self.text.clone(), &new_text, cx);
+                .edit(&range, self.text.clone(), &new_text);
         }
+        self.update_highlights(cx);
         self.mode.clear_markers();
+        self.misspellings.clear();
+        self.spelling_menu = None;
+        self.hovered_marker = None;
+        self.hover = None;
         self.text_wrapper.update(self.text.clone(), false, cx);
         self.selected_range = new_pos..new_pos;
         self.marked_range.take();
@@ -1993,6 +3151,13 @@ impl EntityInputHandler for InputState {
         self.mode.update_auto_grow(&self.text_wrapper);
         cx.emit(InputEvent::Change(self.unmask_value()));
         cx.notify();
+
+        if typing {
+            self.outdent_closing_bracket(new_text, window, cx);
+            if !new_text.is_empty() || self.completion.is_some() {
+                self.request_completion(false, window, cx);
+            }
+        }
     }
 
     /// Mark text is the IME temporary insert on typing.
@@ -2022,13 +3187,19 @@ impl EntityInputHandler for InputState {
         }
 
         self.push_history(&range, new_text, window, cx);
+        self.update_folds_for_edit(&range, new_text);
         self.text = pending_text;
         if let Some(highlighter) = self.mode.highlighter() {
             highlighter
                 .borrow_mut()
-                .update(&range, self.text.clone(), &new_text, cx);
+                .edit(&range, self.text.clone(), &new_text);
         }
+        self.update_highlights(cx);
         self.mode.clear_markers();
+        self.misspellings.clear();
+        self.spelling_menu = None;
+        self.hovered_marker = None;
+        self.hover = None;
         self.text_wrapper.update(self.text.clone(), false, cx);
         if new_text.is_empty() {
             // Cancel selection, when cancel IME input.
@@ -2066,11 +3237,16 @@ impl EntityInputHandler for InputState {
         let mut y_offset = px(0.);
         let mut index_offset = 0;
 
-        for line in lines.iter() {
+        for (ix, line) in lines.iter().enumerate() {
             if start_origin.is_some() && end_origin.is_some() {
                 break;
             }
 
+            if self.is_line_hidden(ix) {
+                index_offset += line.len() + 1;
+                continue;
+            }
+
             if start_origin.is_none() {
                 if let Some(p) =
                     line.position_for_index(range.start.saturating_sub(index_offset), line_height)
@@ -2129,14 +3305,335 @@ impl Focusable for InputState {
     }
 }
 
+impl InputState {
+    /// Render the message of the active marker as a tooltip below the marker start.
+    fn render_marker_tooltip(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let marker = self.active_marker(window)?.clone();
+        let range = marker.byte_range(self)?;
+        let last_bounds = self.last_bounds?;
+        let marker_bounds = self.bounds_for_range(
+            self.range_to_utf16(&(range.start..range.start)),
+            last_bounds,
+            window,
+            cx,
+        )?;
+        // Hide the tooltip when the marker is scrolled out of the view.
+        if !self.input_bounds.contains(&marker_bounds.origin) {
+            return None;
+        }
+
+        let tooltip = match &self.marker_tooltip {
+            Some((last_marker, view)) if *last_marker == marker => view.clone(),
+            _ => {
+                let theme = LanguageRegistry::global(cx).theme(cx.theme().is_dark());
+                let icon = marker.severity.icon();
+                let color = marker.severity.color(theme);
+                let message = marker.message.clone();
+                let view = Tooltip::element(move |_, _| {
+                    h_flex()
+                        .gap_1()
+                        .child(Icon::new(icon.clone()).small().text_color(color))
+                        .child(message.clone())
+                })
+                .build(window, cx);
+                self.marker_tooltip = Some((marker, view.clone()));
+                view
+            }
+        };
+
+        Some(deferred(
+            anchored()
+                .position(marker_bounds.bottom_left())
+                .snap_to_window()
+                .child(tooltip),
+        ))
+    }
+}
+
+impl InputState {
+    /// Render the hover below the offset of the hover.
+    fn render_hover(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let (offset, view) = self.hover.clone()?;
+        let last_bounds = self.last_bounds?;
+        let bounds = self.bounds_for_range(
+            self.range_to_utf16(&(offset..offset)),
+            last_bounds,
+            window,
+            cx,
+        )?;
+        if !self.input_bounds.contains(&bounds.origin) {
+            return None;
+        }
+
+        Some(deferred(
+            anchored()
+                .position(bounds.bottom_left())
+                .snap_to_window()
+                .child(view),
+        ))
+    }
+}
+
+impl InputState {
+    /// Render the completion menu below the start of the query, with the documentation of the
+    /// selected item on the right side.
+    fn render_completion_menu(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let menu = self.completion.as_ref()?;
+        let start = menu.query_range.start;
+        let len = menu.len();
+        let scroll_handle = menu.scroll_handle.clone();
+        let documentation = menu
+            .selected_item()
+            .and_then(|item| item.documentation.clone());
+
+        let last_bounds = self.last_bounds?;
+        let bounds = self.bounds_for_range(
+            self.range_to_utf16(&(start..start)),
+            last_bounds,
+            window,
+            cx,
+        )?;
+        if !self.input_bounds.contains(&bounds.origin) {
+            return None;
+        }
+
+        let item_height = px(26.);
+        let list = uniform_list(
+            "completion-items",
+            len,
+            cx.processor(move |this, range: Range<usize>, window, cx| {
+                let Some(menu) = this.completion.as_ref() else {
+                    return vec![];
+                };
+                let text_style = window.text_style();
+                let highlight = HighlightStyle {
+                    color: Some(cx.theme().primary),
+                    font_weight: Some(FontWeight::BOLD),
+                    ..Default::default()
+                };
+
+                range
+                    .filter_map(|ix| {
+                        let (item, fuzzy_match) = menu.item(ix)?;
+                        let selected = ix == menu.selected_ix();
+                        // Only highlight the matches of the label.
+                        let highlights = match item.filter_text {
+                            Some(_) => vec![],
+                            None => fuzzy_match
+                                .positions
+                                .iter()
+                                .map(|pos| {
+                                    let len =
+                                        item.label[*pos..].chars().next().map_or(0, char::len_utf8);
+                                    (*pos..*pos + len, highlight)
+                                })
+                                .collect(),
+                        };
+
+                        Some(
+                            h_flex()
+                                .id(ix)
+                                .h(item_height)
+                                .px_2()
+                                .gap_3()
+                                .rounded(px(4.))
+                                .when(selected, |this| {
+                                    this.bg(cx.theme().accent)
+                                        .text_color(cx.theme().accent_foreground)
+                                })
+                                .child(
+                                    div().flex_1().overflow_hidden().whitespace_nowrap().child(
+                                        StyledText::new(item.label.clone())
+                                            .with_default_highlights(&text_style, highlights),
+                                    ),
+                                )
+                                .when_some(item.detail.clone(), |this, detail| {
+                                    this.child(
+                                        div()
+                                            .flex_shrink_0()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .child(detail),
+                                    )
+                                })
+                                .on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(move |this, _, window, cx| {
+                                        cx.stop_propagation();
+                                        this.confirm_completion(ix, window, cx);
+                                        this.focus_handle.focus(window);
+                                    }),
+                                ),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            }),
+        )
+        .track_scroll(scroll_handle)
+        .h(item_height * len.min(completion::MAX_VISIBLE_ITEMS) as f32);
+
+        let popover = |cx: &mut Context<Self>| {
+            div()
+                .p_1()
+                .bg(cx.theme().popover)
+                .text_color(cx.theme().popover_foreground)
+                .border_1()
+                .border_color(cx.theme().border)
+                .rounded(cx.theme().radius)
+                .shadow_md()
+        };
+
+        Some(deferred(
+            anchored()
+                .position(bounds.bottom_left())
+                .snap_to_window()
+                .child(
+                    h_flex()
+                        .occlude()
+                        .items_start()
+                        .gap_1()
+                        .font_family(cx.theme().font_family.clone())
+                        .text_sm()
+                        .child(popover(cx).w(px(280.)).child(list))
+                        .when_some(documentation, |this, documentation| {
+                            this.child(
+                                popover(cx)
+                                    .id("completion-documentation")
+                                    .max_w(px(360.))
+                                    .max_h(item_height * completion::MAX_VISIBLE_ITEMS as f32)
+                                    .overflow_y_scroll()
+                                    .px_2()
+                                    .child(documentation),
+                            )
+                        }),
+                ),
+        ))
+    }
+}
+
+/// The suggestions of a misspelled word, see [`InputState::on_right_mouse_down`].
+struct SpellingMenu {
+    range: Range<usize>,
+    suggestions: Vec<String>,
+    /// The position of the right click, in window coordinates.
+    position: Point<Pixels>,
+}
+
+impl InputState {
+    /// Render the suggestions of the misspelled word at the right click position, with the
+    /// "Add to dictionary" item at the end.
+    fn render_spelling_menu(&mut self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let menu = self.spelling_menu.as_ref()?;
+
+        let item = |id: SharedString, cx: &mut Context<Self>| {
+            h_flex()
+                .id(id)
+                .h(px(26.))
+                .px_2()
+                .rounded(px(4.))
+                .hover(|this| {
+                    this.bg(cx.theme().accent)
+                        .text_color(cx.theme().accent_foreground)
+                })
+        };
+
+        let suggestions = menu
+            .suggestions
+            .iter()
+            .map(|suggestion| {
+                let suggestion = suggestion.clone();
+                item(format!("suggestion-{}", suggestion).into(), cx)
+                    .child(suggestion.clone())
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _, window, cx| {
+                            cx.stop_propagation();
+                            this.confirm_spelling(&suggestion, window, cx);
+                        }),
+                    )
+            })
+            .collect::<Vec<_>>();
+        let is_empty = suggestions.is_empty();
+
+        Some(deferred(
+            anchored().position(menu.position).snap_to_window().child(
+                div()
+                    .occlude()
+                    .min_w(px(160.))
+                    .p_1()
+                    .font_family(cx.theme().font_family.clone())
+                    .text_sm()
+                    .bg(cx.theme().popover)
+                    .text_color(cx.theme().popover_foreground)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .rounded(cx.theme().radius)
+                    .shadow_md()
+                    .children(suggestions)
+                    .when(is_empty, |this| {
+                        this.child(
+                            h_flex()
+                                .h(px(26.))
+                                .px_2()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("No suggestions"),
+                        )
+                    })
+                    .child(div().my_1().h(px(1.)).bg(cx.theme().border))
+                    .child(
+                        item("add-to-dictionary".into(), cx)
+                            .child("Add to dictionary")
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _, _, cx| {
+                                    cx.stop_propagation();
+                                    this.add_to_dictionary(cx);
+                                }),
+                            ),
+                    )
+                    .on_mouse_down_out(cx.listener(|this, _, _, cx| {
+                        this.spelling_menu = None;
+                        cx.notify();
+                    })),
+            ),
+        ))
+    }
+}
+
 impl Render for InputState {
-    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         self.text_wrapper.update(self.text.clone(), false, cx);
-        if let Some(highlighter) = self.mode.highlighter() {
+        self.check_spelling(cx);
+        if let Some(highlighter) = self.mode.highlighter().cloned() {
             highlighter
                 .borrow_mut()
-                .update(&(0..0), self.text.clone(), "", cx);
-        }
+                .edit(&(0..0), self.text.clone(), "");
+            self.update_highlights(cx);
+            self.folds
+                .set_foldable(highlighter.borrow_mut().foldable_ranges());
+        }
+
+        // The hover takes the place of the marker tooltip.
+        let hover = self.render_hover(window, cx);
+        let completion_menu = self.render_completion_menu(window, cx);
+        let spelling_menu = self.render_spelling_menu(cx);
+        let marker_tooltip = if hover.is_some() || completion_menu.is_some() {
+            None
+        } else {
+            self.render_marker_tooltip(window, cx)
+        };
 
         div()
             .id("text-element")
@@ -2145,5 +3642,9 @@ impl Render for InputState {
             .flex_grow()
             .overflow_x_hidden()
             .child(TextElement::new(cx.entity().clone()).placeholder(self.placeholder.clone()))
+            .children(hover)
+            .children(marker_tooltip)
+            .children(completion_menu)
+            .children(spelling_menu)
     }
 }
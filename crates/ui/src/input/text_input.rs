@@ -1,7 +1,8 @@
 use gpui::prelude::FluentBuilder as _;
 use gpui::{
     div, px, relative, AnyElement, App, DefiniteLength, Entity, InteractiveElement as _,
-    IntoElement, MouseButton, ParentElement as _, Rems, RenderOnce, Styled as _, Window,
+    IntoElement, KeyContext, MouseButton, ParentElement as _, Rems, RenderOnce, Styled as _,
+    Window,
 };
 
 use crate::button::{Button, ButtonVariants as _};
@@ -13,7 +14,7 @@ use crate::{h_flex, StyledExt};
 use crate::{IconName, Size};
 use crate::{Sizable, StyleSized};
 
-use super::InputState;
+use super::{completion, InputState};
 
 #[derive(IntoElement)]
 pub struct TextInput {
@@ -192,10 +193,16 @@ impl RenderOnce for TextInput {
             theme_background
         };
 
+        let mut key_context = KeyContext::default();
+        key_context.add(crate::input::CONTEXT);
+        if state.completion.is_some() {
+            key_context.add(completion::CONTEXT);
+        }
+
         div()
             .id(("input", self.state.entity_id()))
             .flex()
-            .key_context(crate::input::CONTEXT)
+            .key_context(key_context)
             .track_focus(&state.focus_handle)
             .when(!state.disabled, |this| {
                 this.on_action(window.listener_for(&self.state, InputState::backspace))
@@ -214,6 +221,15 @@ impl RenderOnce for TextInput {
                     .on_action(window.listener_for(&self.state, InputState::cut))
                     .on_action(window.listener_for(&self.state, InputState::undo))
                     .on_action(window.listener_for(&self.state, InputState::redo))
+                    .on_action(window.listener_for(&self.state, InputState::show_completions))
+            })
+            .when(state.completion.is_some(), |this| {
+                this.on_action(window.listener_for(&self.state, InputState::select_prev_completion))
+                    .on_action(window.listener_for(&self.state, InputState::select_next_completion))
+                    .on_action(
+                        window.listener_for(&self.state, InputState::confirm_selected_completion),
+                    )
+                    .on_action(window.listener_for(&self.state, InputState::cancel_completion))
             })
             .on_action(window.listener_for(&self.state, InputState::left))
             .on_action(window.listener_for(&self.state, InputState::right))
@@ -225,6 +241,25 @@ impl RenderOnce for TextInput {
                     .on_action(window.listener_for(&self.state, InputState::select_up))
                     .on_action(window.listener_for(&self.state, InputState::select_down))
             })
+            .when(state.mode.is_code_editor(), |this| {
+                this.on_action(window.listener_for(&self.state, InputState::move_to_next_marker))
+                    .on_action(
+                        window.listener_for(&self.state, InputState::move_to_previous_marker),
+                    )
+                    .on_action(window.listener_for(&self.state, InputState::fold))
+                    .on_action(window.listener_for(&self.state, InputState::unfold))
+                    .on_action(window.listener_for(&self.state, InputState::fold_all))
+                    .on_action(window.listener_for(&self.state, InputState::unfold_all))
+                    .on_action(
+                        window.listener_for(&self.state, InputState::move_to_matching_bracket),
+                    )
+                    .on_action(window.listener_for(&self.state, InputState::hover))
+                    .on_action(window.listener_for(&self.state, InputState::go_to_definition))
+                    .when(!state.disabled, |this| {
+                        this.on_action(window.listener_for(&self.state, InputState::toggle_comment))
+                            .on_action(window.listener_for(&self.state, InputState::reindent))
+                    })
+            })
             .on_action(window.listener_for(&self.state, InputState::select_all))
             .on_action(window.listener_for(&self.state, InputState::select_to_start_of_line))
             .on_action(window.listener_for(&self.state, InputState::select_to_end_of_line))
@@ -245,6 +280,10 @@ impl RenderOnce for TextInput {
                 MouseButton::Left,
                 window.listener_for(&self.state, InputState::on_mouse_down),
             )
+            .on_mouse_down(
+                MouseButton::Right,
+                window.listener_for(&self.state, InputState::on_right_mouse_down),
+            )
             .on_mouse_up(
                 MouseButton::Left,
                 window.listener_for(&self.state, InputState::on_mouse_up),
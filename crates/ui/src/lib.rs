@@ -18,10 +18,29 @@ pub mod input;
 pub mod actions;
 pub mod drawer;
 pub mod highlighter;
+pub mod diff_view;
+pub mod lsp;
 pub mod history;
+pub mod spell_check;
 pub mod title_bar;
 pub mod window_border;
 pub mod sidebar;
+pub mod popup_menu;
+pub mod context_menu;
+pub mod popover;
+pub mod select;
+pub mod table;
+pub mod list;
+pub mod tab;
+pub mod switch;
+pub mod checkbox;
+pub mod radio;
+pub mod slider;
+pub mod accordion;
+pub mod progress;
+pub mod skeleton;
+pub mod description_list;
+pub mod resizable;
 
 pub use button::*;
 pub use colors::*;
@@ -33,5 +52,6 @@ pub use sidebar::*;
 pub use theme::*;
 pub use title_bar::*;
 pub use event::InteractiveElementExt;
+pub use context_menu::ContextMenuExt;
 pub use assets::Assets;
 pub use root::*;
\ No newline at end of file
@@ -0,0 +1,361 @@
+use std::{cell::Cell, ops::Range, rc::Rc};
+
+use gpui::{
+    App, AppContext as _, ClickEvent, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement as _, IntoElement, KeyBinding, ParentElement as _, Render, ScrollStrategy,
+    SharedString, StatefulInteractiveElement as _, Styled as _, Subscription, Task,
+    UniformListScrollHandle, Window, div, prelude::FluentBuilder as _, uniform_list,
+};
+
+use crate::{
+    ActiveTheme as _, Icon, IconName, Sizable, Size, StyleSized as _,
+    actions::{Cancel, Confirm, SelectNext, SelectPrev},
+    h_flex,
+    indicator::Indicator,
+    input::{InputEvent, InputState, TextInput},
+    scroll::{Scrollbar, ScrollbarState},
+    v_flex,
+};
+
+const CONTEXT: &str = "List";
+
+/// Bind the keys of the list, call it after [`crate::input::init`].
+///
+/// The keys are also bound in the search input, overriding the bindings of the input.
+pub fn init(cx: &mut App) {
+    let search_context = format!("{} > Input", CONTEXT);
+    for context in [CONTEXT, search_context.as_str()] {
+        cx.bind_keys([
+            KeyBinding::new("up", SelectPrev, Some(context)),
+            KeyBinding::new("down", SelectNext, Some(context)),
+            KeyBinding::new("enter", Confirm { secondary: false }, Some(context)),
+            KeyBinding::new("escape", Cancel, Some(context)),
+        ]);
+    }
+}
+
+/// The items of a [`List`].
+pub trait ListDelegate: Sized + 'static {
+    fn items_count(&self, cx: &App) -> usize;
+
+    /// Render the item, the items must have the same height.
+    fn render_item(
+        &self,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<List<Self>>,
+    ) -> impl IntoElement;
+
+    /// Filter the items by the query of the search input, the list is loading until the task is
+    /// done.
+    fn perform_search(
+        &mut self,
+        _query: &str,
+        _window: &mut Window,
+        _cx: &mut Context<List<Self>>,
+    ) -> Task<()> {
+        Task::ready(())
+    }
+
+    /// The content shown if there is no items.
+    fn render_empty(&self, _window: &mut Window, cx: &mut Context<List<Self>>) -> impl IntoElement {
+        h_flex()
+            .size_full()
+            .justify_center()
+            .text_color(cx.theme().muted_foreground)
+            .child("No results")
+    }
+}
+
+pub enum ListEvent {
+    /// The item is selected by the keyboard or the mouse.
+    Select(usize),
+    /// The item is confirmed by `enter` or a click.
+    Confirm(usize),
+    /// `escape` is pressed.
+    Cancel,
+}
+
+/// A virtualized list, only the visible items are rendered.
+///
+/// ```ignore
+/// let list = cx.new(|cx| List::new(FileItems::new(), window, cx).searchable(true));
+/// cx.subscribe(&list, |_, _, event: &ListEvent, _| { .. });
+/// ```
+pub struct List<D: ListDelegate> {
+    focus_handle: FocusHandle,
+    delegate: D,
+    search_input: Entity<InputState>,
+    searchable: bool,
+    selected_ix: Option<usize>,
+    loading: bool,
+    size: Size,
+    scroll_handle: UniformListScrollHandle,
+    scrollbar_state: Rc<Cell<ScrollbarState>>,
+    _search_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl<D: ListDelegate> EventEmitter<ListEvent> for List<D> {}
+
+impl<D: ListDelegate> Focusable for List<D> {
+    /// The focus handle of the search input if searchable.
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        if self.searchable {
+            self.search_input.focus_handle(cx)
+        } else {
+            self.focus_handle.clone()
+        }
+    }
+}
+
+impl<D: ListDelegate> Sizable for List<D> {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+impl<D: ListDelegate> List<D> {
+    pub fn new(delegate: D, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_input = cx.new(|cx| InputState::new(window, cx).placeholder("Search..."));
+        let _subscriptions = vec![cx.subscribe_in(
+            &search_input,
+            window,
+            |this, _, event: &InputEvent, window, cx| {
+                if let InputEvent::Change(query) = event {
+                    this.search(query, window, cx);
+                }
+            },
+        )];
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            delegate,
+            search_input,
+            searchable: false,
+            selected_ix: None,
+            loading: false,
+            size: Size::default(),
+            scroll_handle: UniformListScrollHandle::new(),
+            scrollbar_state: Rc::new(Cell::new(ScrollbarState::default())),
+            _search_task: Task::ready(()),
+            _subscriptions,
+        }
+    }
+
+    /// Show a search input above the items, see [`ListDelegate::perform_search`].
+    pub fn searchable(mut self, searchable: bool) -> Self {
+        self.searchable = searchable;
+        self
+    }
+
+    pub fn delegate(&self) -> &D {
+        &self.delegate
+    }
+
+    pub fn delegate_mut(&mut self) -> &mut D {
+        &mut self.delegate
+    }
+
+    pub fn query(&self, cx: &App) -> SharedString {
+        self.search_input.read(cx).value().clone()
+    }
+
+    /// Show the loading state, e.g.: while loading the items.
+    pub fn set_loading(&mut self, loading: bool, cx: &mut Context<Self>) {
+        self.loading = loading;
+        cx.notify();
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected_ix
+    }
+
+    /// Select the item and scroll to it, the selection is cleared if `None`.
+    pub fn set_selected_index(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_ix = ix;
+        if let Some(ix) = ix {
+            self.scroll_handle.scroll_to_item(ix, ScrollStrategy::Top);
+            cx.emit(ListEvent::Select(ix));
+        }
+        cx.notify();
+    }
+
+    /// Focus the search input if searchable, otherwise the list.
+    pub fn focus(&self, window: &mut Window, cx: &App) {
+        self.focus_handle(cx).focus(window);
+    }
+
+    /// Update the list after the items of the delegate changed, the first item is selected.
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        let ix = (self.delegate.items_count(cx) > 0).then_some(0);
+        self.set_selected_index(ix, cx);
+    }
+
+    fn search(&mut self, query: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let task = self.delegate.perform_search(query, window, cx);
+        self.loading = true;
+        self._search_task = cx.spawn_in(window, async move |this, cx| {
+            task.await;
+            this.update(cx, |this, cx| {
+                this.loading = false;
+                this.refresh(cx);
+            })
+            .ok();
+        });
+        cx.notify();
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, _: &mut Window, cx: &mut Context<Self>) {
+        let count = self.delegate.items_count(cx);
+        if count == 0 {
+            return;
+        }
+        let ix = match self.selected_ix {
+            Some(ix) => ix.checked_sub(1).unwrap_or(count - 1),
+            None => count - 1,
+        };
+        self.set_selected_index(Some(ix), cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let count = self.delegate.items_count(cx);
+        if count == 0 {
+            return;
+        }
+        let ix = match self.selected_ix {
+            Some(ix) => (ix + 1) % count,
+            None => 0,
+        };
+        self.set_selected_index(Some(ix), cx);
+    }
+
+    fn confirm(&mut self, _: &Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix.filter(|_| !self.loading) {
+            cx.emit(ListEvent::Confirm(ix));
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(ListEvent::Cancel);
+    }
+
+    fn render_search(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .flex_shrink_0()
+            .pl_3()
+            .bg(cx.theme().list_head)
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                Icon::new(IconName::Search)
+                    .size_4()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(
+                div().flex_1().child(
+                    TextInput::new(&self.search_input)
+                        .with_size(self.size)
+                        .bordered(false),
+                ),
+            )
+    }
+
+    fn render_item(
+        &mut self,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let selected = self.selected_ix == Some(ix);
+
+        h_flex()
+            .id(ix)
+            .relative()
+            .w_full()
+            .list_size(self.size)
+            .cursor_pointer()
+            .hover(|this| this.bg(cx.theme().list_hover))
+            .child(self.delegate.render_item(ix, window, cx))
+            .when(selected, |this| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .right_0()
+                        .bottom_0()
+                        .bg(cx.theme().list_active)
+                        .border_1()
+                        .border_color(cx.theme().list_active_border),
+                )
+            })
+            .on_click(cx.listener(move |this, _: &ClickEvent, _, cx| {
+                this.set_selected_index(Some(ix), cx);
+                cx.emit(ListEvent::Confirm(ix));
+            }))
+    }
+}
+
+impl<D: ListDelegate> Render for List<D> {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let items_count = self.delegate.items_count(cx);
+
+        v_flex()
+            .key_context(CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .overflow_hidden()
+            .bg(cx.theme().list)
+            .when(self.searchable, |this| this.child(self.render_search(cx)))
+            .child(div().relative().flex_1().w_full().map(|this| {
+                if self.loading {
+                    return this.child(
+                        h_flex()
+                            .size_full()
+                            .justify_center()
+                            .gap_2()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(Indicator::new().small())
+                            .child("Loading..."),
+                    );
+                }
+                if items_count == 0 {
+                    return this.child(self.delegate.render_empty(window, cx));
+                }
+
+                this.child(
+                    uniform_list(
+                        "list-items",
+                        items_count,
+                        cx.processor(|this, range: Range<usize>, window, cx| {
+                            range
+                                .map(|ix| this.render_item(ix, window, cx))
+                                .collect::<Vec<_>>()
+                        }),
+                    )
+                    .track_scroll(self.scroll_handle.clone())
+                    .size_full(),
+                )
+                .child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .right_0()
+                        .bottom_0()
+                        .child(Scrollbar::uniform_scroll(
+                            cx.entity_id(),
+                            self.scrollbar_state.clone(),
+                            self.scroll_handle.clone(),
+                        )),
+                )
+            }))
+    }
+}
@@ -0,0 +1,420 @@
+use std::{
+    collections::HashMap,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicI64, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use futures::channel::mpsc::UnboundedSender;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::protocol::{Location, Position, hover_text, path_to_uri, read_message, write_message};
+
+/// The time to wait for the response of a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The config to start a language server, e.g.:
+///
+/// ```toml
+/// [[language_servers]]
+/// language = "rust"
+/// command = "rust-analyzer"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageServerConfig {
+    /// The language name of the documents, see [`crate::highlighter::Language`].
+    pub language: String,
+    /// The command to start the language server, that communicates over stdio.
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// The options sent in the `initializationOptions` of the `initialize` request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initialization_options: Option<Value>,
+}
+
+/// A notification sent from the language server, e.g.: `textDocument/publishDiagnostics`.
+#[derive(Debug, Clone)]
+pub struct ServerNotification {
+    pub method: String,
+    pub params: Value,
+}
+
+type PendingRequests = Arc<Mutex<HashMap<i64, Sender<Result<Value>>>>>;
+
+/// The sender of the messages to the writer thread, the messages are queued until the server
+/// is initialized.
+///
+/// The messages are written to the server in the writer thread, so a server that is slow to
+/// read its stdin doesn't block the sender, e.g.: the UI thread on a `didChange`.
+struct Outbox {
+    tx: Sender<Value>,
+    /// The queued messages, `None` after the server is initialized.
+    queue: Option<Vec<Value>>,
+    /// The error of the `initialize` request, the messages are rejected after it.
+    error: Option<String>,
+}
+
+impl Outbox {
+    fn send(&mut self, message: Value) -> Result<()> {
+        if let Some(error) = &self.error {
+            bail!("failed to initialize: {}", error);
+        }
+
+        match &mut self.queue {
+            Some(queue) => {
+                queue.push(message);
+                Ok(())
+            }
+            None => self.write(message),
+        }
+    }
+
+    /// Send the message to the writer thread, bypassing the queue.
+    fn write(&self, message: Value) -> Result<()> {
+        self.tx
+            .send(message)
+            .map_err(|_| anyhow!("language server exited"))
+    }
+}
+
+/// Write the messages to the server until the [`Outbox`] is dropped or the server exits.
+fn run_writer(rx: Receiver<Value>, mut writer: BufWriter<ChildStdin>) {
+    for message in rx {
+        if let Err(err) = write_message(&mut writer, &message) {
+            tracing::error!("failed to write language server message: {:?}", err);
+            break;
+        }
+    }
+}
+
+/// The response of a request, call [`PendingResponse::wait`] to get the result.
+pub struct PendingResponse {
+    method: &'static str,
+    rx: Receiver<Result<Value>>,
+}
+
+impl PendingResponse {
+    /// Block the current thread to wait for the response, run it in the background.
+    pub fn wait(self) -> Result<Value> {
+        match self.rx.recv_timeout(REQUEST_TIMEOUT) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => bail!("{} request timed out", self.method),
+            Err(RecvTimeoutError::Disconnected) => bail!("language server exited"),
+        }
+    }
+}
+
+/// A language server client that communicates with JSON-RPC over stdio.
+///
+/// The document is synchronized with the full text on every change.
+pub struct LanguageServer {
+    config: LanguageServerConfig,
+    root: PathBuf,
+    child: Mutex<Child>,
+    outbox: Arc<Mutex<Outbox>>,
+    pending: PendingRequests,
+    next_id: AtomicI64,
+}
+
+impl LanguageServer {
+    /// Start the language server in the `root` directory and send the `initialize` request.
+    ///
+    /// This does not wait for the server to be initialized, the messages are sent after the
+    /// server is initialized. The notifications from the server are sent to `notifications`.
+    pub fn start(
+        config: &LanguageServerConfig,
+        root: impl AsRef<Path>,
+        notifications: UnboundedSender<ServerNotification>,
+    ) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .current_dir(&root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("failed to start {}", config.command))?;
+        let stdin = child.stdin.take().context("no stdin")?;
+        let stdout = child.stdout.take().context("no stdout")?;
+
+        let (tx, rx) = mpsc::channel();
+        thread::Builder::new()
+            .name(format!("lsp-{}-writer", config.language))
+            .spawn(move || run_writer(rx, BufWriter::new(stdin)))?;
+        let outbox = Arc::new(Mutex::new(Outbox {
+            tx,
+            queue: Some(vec![]),
+            error: None,
+        }));
+        let pending: PendingRequests = Arc::default();
+
+        // The `initialize` request is sent before the other queued messages.
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "method": "initialize",
+            "params": {
+                "processId": std::process::id(),
+                "rootUri": path_to_uri(&root),
+                "workspaceFolders": [{
+                    "uri": path_to_uri(&root),
+                    "name": root.file_name().map(|name| name.to_string_lossy()).unwrap_or_default(),
+                }],
+                "initializationOptions": config.initialization_options,
+                "capabilities": {
+                    "textDocument": {
+                        "synchronization": { "didSave": false },
+                        "hover": { "contentFormat": ["markdown", "plaintext"] },
+                        "definition": { "linkSupport": true },
+                        "publishDiagnostics": {},
+                    },
+                },
+            },
+        });
+        outbox.lock().unwrap().write(initialize)?;
+
+        let reader = Reader {
+            outbox: outbox.clone(),
+            pending: pending.clone(),
+            notifications,
+        };
+        thread::Builder::new()
+            .name(format!("lsp-{}", config.language))
+            .spawn(move || reader.run(BufReader::new(stdout)))?;
+
+        Ok(Self {
+            config: config.clone(),
+            root,
+            child: Mutex::new(child),
+            outbox,
+            pending,
+            next_id: AtomicI64::new(1),
+        })
+    }
+
+    pub fn config(&self) -> &LanguageServerConfig {
+        &self.config
+    }
+
+    /// The root directory of the workspace.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Send a request, the response is received by [`PendingResponse::wait`].
+    pub fn request(&self, method: &'static str, params: Value) -> PendingResponse {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = mpsc::channel();
+        self.pending.lock().unwrap().insert(id, tx.clone());
+
+        let mut message = json!({ "jsonrpc": "2.0", "id": id, "method": method });
+        set_params(&mut message, params);
+        if let Err(err) = self.outbox.lock().unwrap().send(message) {
+            self.pending.lock().unwrap().remove(&id);
+            _ = tx.send(Err(err));
+        }
+        PendingResponse { method, rx }
+    }
+
+    /// Send a notification.
+    pub fn notify(&self, method: &str, params: Value) -> Result<()> {
+        let mut message = json!({ "jsonrpc": "2.0", "method": method });
+        set_params(&mut message, params);
+        self.outbox.lock().unwrap().send(message)
+    }
+
+    pub fn did_open(&self, uri: &str, version: i32, text: &str) -> Result<()> {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {
+                    "uri": uri,
+                    "languageId": self.config.language,
+                    "version": version,
+                    "text": text,
+                },
+            }),
+        )
+    }
+
+    pub fn did_change(&self, uri: &str, version: i32, text: &str) -> Result<()> {
+        self.notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": uri, "version": version },
+                "contentChanges": [{ "text": text }],
+            }),
+        )
+    }
+
+    pub fn did_close(&self, uri: &str) -> Result<()> {
+        self.notify(
+            "textDocument/didClose",
+            json!({ "textDocument": { "uri": uri } }),
+        )
+    }
+
+    /// Request the hover text at the position, the text is usually markdown.
+    pub fn hover(&self, uri: &str, position: Position) -> Result<Option<String>> {
+        let result = self
+            .request("textDocument/hover", position_params(uri, position))
+            .wait()?;
+        Ok(hover_text(&result))
+    }
+
+    /// Request the locations of the definition of the symbol at the position.
+    pub fn definition(&self, uri: &str, position: Position) -> Result<Vec<Location>> {
+        let result = self
+            .request("textDocument/definition", position_params(uri, position))
+            .wait()?;
+        Ok(Location::parse_all(result))
+    }
+
+    /// Shutdown the language server gracefully, and kill it if failed.
+    pub fn shutdown(&self) {
+        let result = self
+            .request("shutdown", Value::Null)
+            .wait()
+            .and_then(|_| self.notify("exit", Value::Null));
+        if let Err(err) = result {
+            tracing::warn!("failed to shutdown {}: {:?}", self.config.command, err);
+            _ = self.child.lock().unwrap().kill();
+        }
+        _ = self.child.lock().unwrap().wait();
+    }
+}
+
+impl Drop for LanguageServer {
+    fn drop(&mut self) {
+        let mut child = self.child.lock().unwrap();
+        if let Ok(None) = child.try_wait() {
+            _ = child.kill();
+            _ = child.wait();
+        }
+    }
+}
+
+/// Set the params of the message, the `null` params are omitted, e.g.: `shutdown`.
+fn set_params(message: &mut Value, params: Value) {
+    if !params.is_null() {
+        message["params"] = params;
+    }
+}
+
+fn position_params(uri: &str, position: Position) -> Value {
+    json!({ "textDocument": { "uri": uri }, "position": position })
+}
+
+/// Read the messages from the server in the reader thread.
+struct Reader {
+    outbox: Arc<Mutex<Outbox>>,
+    pending: PendingRequests,
+    notifications: UnboundedSender<ServerNotification>,
+}
+
+impl Reader {
+    fn run(self, mut reader: BufReader<impl std::io::Read>) {
+        loop {
+            let message = match read_message(&mut reader) {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
+                Err(err) => {
+                    tracing::error!("failed to read language server message: {:?}", err);
+                    break;
+                }
+            };
+
+            if let Err(err) = self.handle_message(message) {
+                tracing::error!("failed to handle language server message: {:?}", err);
+            }
+        }
+
+        // Fail the pending requests when the server exits.
+        self.pending.lock().unwrap().clear();
+    }
+
+    fn handle_message(&self, mut message: Value) -> Result<()> {
+        let id = message.get("id").cloned();
+        let method = message
+            .get("method")
+            .and_then(|method| method.as_str())
+            .map(|method| method.to_string());
+
+        match (id, method) {
+            // Response of the `initialize` request.
+            (Some(Value::Number(id)), None) if id.as_i64() == Some(0) => {
+                if let Some(error) = message.get("error") {
+                    let error = error.to_string();
+                    self.outbox.lock().unwrap().error = Some(error.clone());
+                    // Fail the queued requests now, rather than wait for the timeout.
+                    for (_, tx) in self.pending.lock().unwrap().drain() {
+                        _ = tx.send(Err(anyhow!("failed to initialize: {}", error)));
+                    }
+                    bail!("failed to initialize: {}", error);
+                }
+
+                let mut outbox = self.outbox.lock().unwrap();
+                let queue = outbox.queue.take().unwrap_or_default();
+                let initialized =
+                    json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} });
+                for message in std::iter::once(initialized).chain(queue) {
+                    outbox.write(message)?;
+                }
+            }
+            // Response of a request.
+            (Some(id), None) => {
+                let id = id.as_i64().context("invalid response id")?;
+                let Some(tx) = self.pending.lock().unwrap().remove(&id) else {
+                    return Ok(());
+                };
+                let result = match message.get("error") {
+                    Some(error) => Err(anyhow!("{}", error)),
+                    None => Ok(message
+                        .get_mut("result")
+                        .map(Value::take)
+                        .unwrap_or_default()),
+                };
+                _ = tx.send(result);
+            }
+            // Request from the server, e.g.: `workspace/configuration`, reply with `null`.
+            (Some(id), Some(method)) => {
+                let result = match method.as_str() {
+                    "workspace/configuration" => {
+                        let count = message["params"]["items"]
+                            .as_array()
+                            .map_or(0, |items| items.len());
+                        Value::Array(vec![Value::Null; count])
+                    }
+                    _ => Value::Null,
+                };
+                self.outbox
+                    .lock()
+                    .unwrap()
+                    .send(json!({ "jsonrpc": "2.0", "id": id, "result": result }))?;
+            }
+            (None, Some(method)) => {
+                let params = message
+                    .get_mut("params")
+                    .map(Value::take)
+                    .unwrap_or_default();
+                _ = self
+                    .notifications
+                    .unbounded_send(ServerNotification { method, params });
+            }
+            (None, None) => {}
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+mod client;
+mod protocol;
+mod store;
+
+pub use client::*;
+pub use protocol::*;
+pub use store::*;
+
+use gpui::App;
+
+pub fn init(cx: &mut App) {
+    store::init(cx);
+}
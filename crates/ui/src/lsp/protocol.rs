@@ -0,0 +1,394 @@
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::input::{LineColumn, Marker, MarkerSeverity};
+
+/// Read a JSON-RPC message with the `Content-Length` header from the reader.
+///
+/// Returns `None` if the reader is closed.
+pub fn read_message(reader: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = Some(value.trim().parse::<usize>()?);
+            }
+        }
+    }
+
+    let content_length = content_length.context("missing Content-Length header")?;
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// Write a JSON-RPC message with the `Content-Length` header to the writer.
+pub fn write_message(writer: &mut impl Write, message: &Value) -> Result<()> {
+    let content = serde_json::to_string(message)?;
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Position in a text document, the `character` is the UTF-16 offset in the line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+impl Location {
+    /// Parse the result of `textDocument/definition`, that is a `Location`, `Location[]`,
+    /// `LocationLink[]` or `null`.
+    pub fn parse_all(value: Value) -> Vec<Location> {
+        let values = match value {
+            Value::Array(values) => values,
+            Value::Null => vec![],
+            value => vec![value],
+        };
+
+        values
+            .into_iter()
+            .filter_map(|value| {
+                if let Some(uri) = value.get("targetUri") {
+                    let range = value
+                        .get("targetSelectionRange")
+                        .or(value.get("targetRange"))?;
+                    return Some(Location {
+                        uri: uri.as_str()?.to_string(),
+                        range: serde_json::from_value(range.clone()).ok()?,
+                    });
+                }
+                serde_json::from_value(value).ok()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub range: Range,
+    /// 1: Error, 2: Warning, 3: Information, 4: Hint.
+    #[serde(default)]
+    pub severity: Option<u8>,
+    #[serde(default)]
+    pub source: Option<String>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Convert the diagnostic to a [`Marker`] of the `text`.
+    pub fn to_marker(&self, text: &str) -> Marker {
+        let severity = match self.severity {
+            Some(2) => MarkerSeverity::Warning,
+            Some(3) => MarkerSeverity::Info,
+            Some(4) => MarkerSeverity::Hint,
+            _ => MarkerSeverity::Error,
+        };
+        let message = match &self.source {
+            Some(source) => format!("{}: {}", source, self.message),
+            None => self.message.clone(),
+        };
+
+        Marker::new(
+            severity,
+            line_column(text, position_to_offset(text, self.range.start)),
+            line_column(text, position_to_offset(text, self.range.end)),
+            message,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublishDiagnosticsParams {
+    pub uri: String,
+    #[serde(default)]
+    pub version: Option<i32>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Returns the text of the `textDocument/hover` result, `None` if no hover.
+pub fn hover_text(value: &Value) -> Option<String> {
+    fn marked_text(value: &Value) -> Option<String> {
+        match value {
+            Value::String(text) => Some(text.clone()),
+            Value::Object(object) => {
+                let text = object.get("value")?.as_str()?;
+                match object
+                    .get("language")
+                    .and_then(|language| language.as_str())
+                {
+                    Some(language) => Some(format!("```{}\n{}\n```", language, text)),
+                    None => Some(text.to_string()),
+                }
+            }
+            Value::Array(values) => {
+                let texts = values.iter().filter_map(marked_text).collect::<Vec<_>>();
+                (!texts.is_empty()).then(|| texts.join("\n\n"))
+            }
+            _ => None,
+        }
+    }
+
+    let text = marked_text(value.get("contents")?)?;
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Returns the LSP position of the byte `offset` in the `text`.
+pub fn offset_to_position(text: &str, offset: usize) -> Position {
+    let offset = floor_char_boundary(text, offset);
+    let line_start = text[..offset].rfind('\n').map_or(0, |ix| ix + 1);
+    Position {
+        line: text[..line_start].matches('\n').count(),
+        character: text[line_start..offset].encode_utf16().count(),
+    }
+}
+
+/// Returns the byte offset of the LSP `position` in the `text`, clamped to the line end.
+pub fn position_to_offset(text: &str, position: Position) -> usize {
+    let Some(line_start) = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(ix, _)| ix + 1))
+        .nth(position.line)
+    else {
+        return text.len();
+    };
+    let line_end = text[line_start..]
+        .find('\n')
+        .map_or(text.len(), |ix| line_start + ix);
+
+    let mut character = 0;
+    for (ix, c) in text[line_start..line_end].char_indices() {
+        if character >= position.character {
+            return line_start + ix;
+        }
+        character += c.len_utf16();
+    }
+    line_end
+}
+
+/// Returns the [`LineColumn`] (1-based, column in chars) of the byte `offset` in the `text`.
+pub fn line_column(text: &str, offset: usize) -> LineColumn {
+    let offset = floor_char_boundary(text, offset);
+    let line_start = text[..offset].rfind('\n').map_or(0, |ix| ix + 1);
+    LineColumn::from((
+        text[..line_start].matches('\n').count() + 1,
+        text[line_start..offset].chars().count() + 1,
+    ))
+}
+
+fn floor_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// Returns the `file://` URI of the absolute path.
+pub fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/:".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+/// Returns the path of the `file://` URI.
+pub fn uri_to_path(uri: &str) -> Result<PathBuf> {
+    let path = uri
+        .strip_prefix("file://")
+        .ok_or_else(|| anyhow!("not a file uri: {}", uri))?;
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut iter = path.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next().unwrap_or(b'0'), iter.next().unwrap_or(b'0')];
+            let hex = std::str::from_utf8(&hex)?;
+            bytes.push(u8::from_str_radix(hex, 16)?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    let path = String::from_utf8(bytes)?;
+
+    // Strip the leading slash of the Windows path, e.g.: `/C:/foo`.
+    if cfg!(windows) && path.get(2..3) == Some(":") {
+        return Ok(PathBuf::from(&path[1..]));
+    }
+    Ok(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::BufReader, path::Path};
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_read_write_message() {
+        let mut buf = vec![];
+        write_message(&mut buf, &json!({"jsonrpc": "2.0", "method": "exit"})).unwrap();
+        write_message(
+            &mut buf,
+            &json!({"jsonrpc": "2.0", "id": 1, "result": null}),
+        )
+        .unwrap();
+        assert!(buf.starts_with(b"Content-Length: 33\r\n\r\n{"));
+
+        let mut reader = BufReader::new(buf.as_slice());
+        let message = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(message["method"], "exit");
+        let message = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(message["id"], 1);
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_position() {
+        let text = "fn main() {\n    let 你好 = \"😀\";\n}";
+        let offset = text.find('=').unwrap();
+        let position = offset_to_position(text, offset);
+        assert_eq!(
+            position,
+            Position {
+                line: 1,
+                character: 11
+            }
+        );
+        assert_eq!(position_to_offset(text, position), offset);
+
+        let offset = text.find(';').unwrap();
+        let position = offset_to_position(text, offset);
+        assert_eq!(
+            position,
+            Position {
+                line: 1,
+                character: 17
+            }
+        );
+        assert_eq!(position_to_offset(text, position), offset);
+
+        // Clamped to the line end.
+        let position = Position {
+            line: 0,
+            character: 100,
+        };
+        assert_eq!(position_to_offset(text, position), 11);
+        let position = Position {
+            line: 10,
+            character: 0,
+        };
+        assert_eq!(position_to_offset(text, position), text.len());
+
+        assert_eq!(
+            line_column(text, offset),
+            LineColumn {
+                line: 2,
+                column: 15
+            }
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_to_marker() {
+        let text = "let a = 1;\nlet b = c;";
+        let diagnostic: Diagnostic = serde_json::from_value(json!({
+            "range": {
+                "start": {"line": 1, "character": 8},
+                "end": {"line": 1, "character": 9}
+            },
+            "severity": 2,
+            "source": "rustc",
+            "message": "unknown `c`"
+        }))
+        .unwrap();
+
+        let marker = diagnostic.to_marker(text);
+        assert_eq!(marker.severity, MarkerSeverity::Warning);
+        assert_eq!(marker.start, LineColumn { line: 2, column: 9 });
+        assert_eq!(
+            marker.end,
+            LineColumn {
+                line: 2,
+                column: 10
+            }
+        );
+        assert_eq!(marker.message.as_ref(), "rustc: unknown `c`");
+    }
+
+    #[test]
+    fn test_hover_and_locations() {
+        let hover = json!({"contents": {"kind": "markdown", "value": "`fn main()`"}});
+        assert_eq!(hover_text(&hover).as_deref(), Some("`fn main()`"));
+        let hover = json!({"contents": [{"language": "rust", "value": "i32"}, "docs"]});
+        assert_eq!(
+            hover_text(&hover).as_deref(),
+            Some("```rust\ni32\n```\n\ndocs")
+        );
+        assert_eq!(hover_text(&json!({"contents": ""})), None);
+
+        let range =
+            json!({"start": {"line": 1, "character": 0}, "end": {"line": 1, "character": 3}});
+        let locations = Location::parse_all(json!({"uri": "file:///a.rs", "range": range}));
+        assert_eq!(locations.len(), 1);
+        let locations = Location::parse_all(json!([
+            {"targetUri": "file:///b.rs", "targetRange": range, "targetSelectionRange": range}
+        ]));
+        assert_eq!(locations[0].uri, "file:///b.rs");
+        assert_eq!(locations[0].range.start.line, 1);
+        assert!(Location::parse_all(json!(null)).is_empty());
+    }
+
+    #[test]
+    fn test_uri() {
+        let path = Path::new("/tmp/my project/main.rs");
+        let uri = path_to_uri(path);
+        assert_eq!(uri, "file:///tmp/my%20project/main.rs");
+        assert_eq!(uri_to_path(&uri).unwrap(), path);
+        assert!(uri_to_path("https://example.com").is_err());
+    }
+}
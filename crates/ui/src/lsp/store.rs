@@ -0,0 +1,381 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
+
+use futures::{
+    StreamExt as _,
+    channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+};
+use gpui::{
+    AnyWindowHandle, App, AppContext as _, Context, Entity, EntityId, Global, Subscription,
+    WeakEntity, Window,
+};
+
+use super::{
+    client::{LanguageServer, LanguageServerConfig, ServerNotification},
+    protocol::{
+        Location, PublishDiagnosticsParams, offset_to_position, path_to_uri, position_to_offset,
+        uri_to_path,
+    },
+};
+use crate::{
+    highlighter::LanguageRegistry,
+    input::{InputEvent, InputState},
+};
+
+type OpenLocationHandler = Rc<dyn Fn(PathBuf, Location, &mut Window, &mut App)>;
+
+struct GlobalLspStore(Entity<LspStore>);
+
+impl Global for GlobalLspStore {}
+
+/// A document opened in a code editor and synchronized to the language server.
+struct Document {
+    uri: String,
+    language: String,
+    version: i32,
+    editor: WeakEntity<InputState>,
+    window: AnyWindowHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// Start the language servers for the files in the root directory, and keep the documents of
+/// the code editors synchronized.
+///
+/// The diagnostics are shown as the markers of the editor, the hover and the go-to-definition
+/// are requested by the [`InputEvent::Hover`] and the [`InputEvent::GoToDefinition`] events.
+pub struct LspStore {
+    root: Option<PathBuf>,
+    configs: Vec<LanguageServerConfig>,
+    /// The started language servers, the key is the language name.
+    servers: HashMap<String, Arc<LanguageServer>>,
+    /// The opened documents, the key is the id of the editor.
+    documents: HashMap<EntityId, Document>,
+    notifications: UnboundedSender<ServerNotification>,
+    on_open_location: Option<OpenLocationHandler>,
+}
+
+impl LspStore {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let (tx, rx) = mpsc::unbounded();
+        Self::handle_notifications(rx, cx);
+
+        Self {
+            root: None,
+            configs: vec![],
+            servers: HashMap::new(),
+            documents: HashMap::new(),
+            notifications: tx,
+            on_open_location: None,
+        }
+    }
+
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalLspStore>().0.clone()
+    }
+
+    /// Set the language servers config, the running servers are restarted.
+    pub fn set_servers(&mut self, configs: Vec<LanguageServerConfig>, cx: &mut Context<Self>) {
+        if self.configs == configs {
+            return;
+        }
+
+        self.configs = configs;
+        self.restart_servers(cx);
+    }
+
+    /// Set the root directory of the workspace, only the files in the root are handled by the
+    /// language servers.
+    pub fn set_root(&mut self, root: Option<PathBuf>, cx: &mut Context<Self>) {
+        if self.root == root {
+            return;
+        }
+
+        self.root = root;
+        self.restart_servers(cx);
+    }
+
+    /// Set the handler to open the location of the definition in another file.
+    pub fn on_open_location(
+        &mut self,
+        f: impl Fn(PathBuf, Location, &mut Window, &mut App) + 'static,
+    ) {
+        self.on_open_location = Some(Rc::new(f));
+    }
+
+    /// Open the file of the code editor in the language server of the language, the language
+    /// is detected from the path, see [`LanguageRegistry::detect`].
+    ///
+    /// Nothing happens if the path is not in the root, or no language server is configured.
+    /// The document is closed when the editor is released.
+    pub fn open(
+        &mut self,
+        editor: &Entity<InputState>,
+        path: impl AsRef<Path>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.open_in(editor, path.as_ref(), window.window_handle(), cx);
+    }
+
+    fn open_in(
+        &mut self,
+        editor: &Entity<InputState>,
+        path: &Path,
+        window: AnyWindowHandle,
+        cx: &mut Context<Self>,
+    ) {
+        self.close(editor.entity_id());
+
+        let text = editor.read(cx).value().clone();
+        let first_line = text.split('\n').next().unwrap_or_default();
+        let Some(language) = LanguageRegistry::global(cx).detect(path, first_line) else {
+            return;
+        };
+        let Some(server) = self.server_for(path, &language) else {
+            return;
+        };
+
+        let uri = path_to_uri(path);
+        if let Err(err) = server.did_open(&uri, 0, &text) {
+            tracing::error!("failed to open {}: {:?}", uri, err);
+            return;
+        }
+
+        let entity_id = editor.entity_id();
+        self.documents.insert(
+            entity_id,
+            Document {
+                uri,
+                language: language.to_string(),
+                version: 0,
+                editor: editor.downgrade(),
+                window,
+                _subscriptions: vec![
+                    cx.subscribe(editor, Self::on_editor_event),
+                    cx.observe_release(editor, move |this, _, _| this.close(entity_id)),
+                ],
+            },
+        );
+    }
+
+    /// Close the document of the editor.
+    pub fn close(&mut self, entity_id: EntityId) {
+        let Some(document) = self.documents.remove(&entity_id) else {
+            return;
+        };
+
+        if let Some(server) = self.servers.get(&document.language) {
+            _ = server.did_close(&document.uri);
+        }
+    }
+
+    /// Return the running language server for the file, start it if not started.
+    fn server_for(&mut self, path: &Path, language: &str) -> Option<Arc<LanguageServer>> {
+        let root = self.root.as_ref()?;
+        if !path.starts_with(root) {
+            return None;
+        }
+        if let Some(server) = self.servers.get(language) {
+            return Some(server.clone());
+        }
+
+        let config = self
+            .configs
+            .iter()
+            .find(|config| config.language == language)?;
+        match LanguageServer::start(config, root, self.notifications.clone()) {
+            Ok(server) => {
+                let server = Arc::new(server);
+                self.servers.insert(language.to_string(), server.clone());
+                Some(server)
+            }
+            Err(err) => {
+                tracing::error!("failed to start language server: {:?}", err);
+                None
+            }
+        }
+    }
+
+    /// Shutdown the running servers, and open the documents again in the new servers.
+    fn restart_servers(&mut self, cx: &mut Context<Self>) {
+        for (_, server) in self.servers.drain() {
+            cx.background_executor()
+                .spawn(async move { server.shutdown() })
+                .detach();
+        }
+
+        let documents = self.documents.drain().collect::<Vec<_>>();
+        for (_, document) in documents {
+            let Some(editor) = document.editor.upgrade() else {
+                continue;
+            };
+            let Ok(path) = uri_to_path(&document.uri) else {
+                continue;
+            };
+
+            // Clear the markers of the old server.
+            _ = document.window.update(cx, |_, window, cx| {
+                editor.update(cx, |editor, cx| editor.set_markers(vec![], window, cx));
+            });
+            self.open_in(&editor, &path, document.window, cx);
+        }
+    }
+
+    fn on_editor_event(
+        &mut self,
+        editor: Entity<InputState>,
+        event: &InputEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(document) = self.documents.get_mut(&editor.entity_id()) else {
+            return;
+        };
+        let Some(server) = self.servers.get(&document.language).cloned() else {
+            return;
+        };
+        let window = document.window;
+
+        match event {
+            InputEvent::Change(text) => {
+                document.version += 1;
+                if let Err(err) = server.did_change(&document.uri, document.version, text) {
+                    tracing::error!("failed to sync {}: {:?}", document.uri, err);
+                }
+            }
+            InputEvent::Hover { offset } => {
+                let offset = *offset;
+                let uri = document.uri.clone();
+                let position = offset_to_position(editor.read(cx).value(), offset);
+                cx.spawn(async move |_, cx| {
+                    let hover = cx
+                        .background_executor()
+                        .spawn(async move { server.hover(&uri, position) })
+                        .await;
+                    match hover {
+                        Ok(Some(text)) => {
+                            _ = cx.update_window(window, |_, window, cx| {
+                                editor.update(cx, |editor, cx| {
+                                    editor.show_hover(offset, text, window, cx)
+                                })
+                            });
+                        }
+                        Ok(None) => {}
+                        Err(err) => tracing::warn!("failed to hover: {:?}", err),
+                    }
+                })
+                .detach();
+            }
+            InputEvent::GoToDefinition { offset } => {
+                let uri = document.uri.clone();
+                let position = offset_to_position(editor.read(cx).value(), *offset);
+                cx.spawn(async move |this, cx| {
+                    let locations = cx
+                        .background_executor()
+                        .spawn(async move { server.definition(&uri, position) })
+                        .await;
+                    let location = match locations {
+                        Ok(locations) => match locations.into_iter().next() {
+                            Some(location) => location,
+                            None => return,
+                        },
+                        Err(err) => {
+                            tracing::warn!("failed to go to definition: {:?}", err);
+                            return;
+                        }
+                    };
+
+                    if location.uri == uri {
+                        _ = cx.update_window(window, |_, window, cx| {
+                            editor.update(cx, |editor, cx| {
+                                let offset =
+                                    position_to_offset(editor.value(), location.range.start);
+                                editor.move_cursor_to(offset, window, cx);
+                            })
+                        });
+                        return;
+                    }
+
+                    let Ok(path) = uri_to_path(&location.uri) else {
+                        return;
+                    };
+                    let Ok(Some(on_open_location)) =
+                        this.update(cx, |this, _| this.on_open_location.clone())
+                    else {
+                        return;
+                    };
+                    _ = cx.update_window(window, |_, window, cx| {
+                        on_open_location(path, location, window, cx)
+                    });
+                })
+                .detach();
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle the notifications from the language servers, until the store and the servers
+    /// are released.
+    fn handle_notifications(mut rx: UnboundedReceiver<ServerNotification>, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            while let Some(notification) = rx.next().await {
+                if this
+                    .update(cx, |this, cx| this.handle_notification(notification, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn handle_notification(&mut self, notification: ServerNotification, cx: &mut Context<Self>) {
+        if notification.method != "textDocument/publishDiagnostics" {
+            return;
+        }
+
+        let params: PublishDiagnosticsParams = match serde_json::from_value(notification.params) {
+            Ok(params) => params,
+            Err(err) => {
+                tracing::warn!("invalid diagnostics: {:?}", err);
+                return;
+            }
+        };
+
+        for document in self.documents.values() {
+            if document.uri != params.uri {
+                continue;
+            }
+            // Skip the diagnostics of the outdated version, the offsets are changed.
+            if params
+                .version
+                .is_some_and(|version| version != document.version)
+            {
+                continue;
+            }
+            let Some(editor) = document.editor.upgrade() else {
+                continue;
+            };
+
+            _ = document.window.update(cx, |_, window, cx| {
+                editor.update(cx, |editor, cx| {
+                    let markers = params
+                        .diagnostics
+                        .iter()
+                        .map(|diagnostic| diagnostic.to_marker(editor.value()))
+                        .collect();
+                    editor.set_markers(markers, window, cx);
+                })
+            });
+        }
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let store = cx.new(LspStore::new);
+    cx.set_global(GlobalLspStore(store));
+}
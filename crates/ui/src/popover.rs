@@ -0,0 +1,165 @@
+use std::{cell::Cell, rc::Rc};
+
+use gpui::{
+    AnyElement, App, Bounds, Corner, Div, ElementId, FocusHandle, InteractiveElement as _,
+    IntoElement, KeyBinding, MouseDownEvent, ParentElement, Pixels, RenderOnce,
+    StatefulInteractiveElement as _, StyleRefinement, Styled, Window, anchored, canvas, deferred,
+    div, prelude::FluentBuilder as _,
+};
+
+use crate::{ActiveTheme as _, Placement, StyledExt as _, actions::Cancel};
+
+const CONTEXT: &str = "Popover";
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([KeyBinding::new("escape", Cancel, Some(CONTEXT))]);
+}
+
+/// An overlay anchored to the trigger element, e.g.: the menu of the [`crate::select::Select`].
+///
+/// The open state is owned by the parent, that opens the popover on the trigger click. The
+/// `on_dismiss` handler is called on the mouse down outside of the content and the trigger, and
+/// on `escape` if the content has the focus, see [`Self::track_focus`].
+///
+/// The children and the styles are applied to the content, e.g.: `.w(px(240.))`.
+#[derive(IntoElement)]
+pub struct Popover {
+    id: ElementId,
+    trigger: Option<AnyElement>,
+    content: Div,
+    open: bool,
+    placement: Placement,
+    focus_handle: Option<FocusHandle>,
+    on_dismiss: Option<Rc<dyn Fn(&mut Window, &mut App)>>,
+}
+
+impl Popover {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            trigger: None,
+            content: div(),
+            open: false,
+            placement: Placement::Bottom,
+            focus_handle: None,
+            on_dismiss: None,
+        }
+    }
+
+    /// Set the element to anchor the content to.
+    pub fn trigger(mut self, trigger: impl IntoElement) -> Self {
+        self.trigger = Some(trigger.into_any_element());
+        self
+    }
+
+    /// Show the content if open.
+    pub fn open(mut self, open: bool) -> Self {
+        self.open = open;
+        self
+    }
+
+    /// Set the side of the trigger to show the content, default is [`Placement::Bottom`].
+    ///
+    /// The content is moved into the window if it overflows.
+    pub fn placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Track the focus of the content, focus it to dismiss the popover on `escape`.
+    pub fn track_focus(mut self, focus_handle: &FocusHandle) -> Self {
+        self.focus_handle = Some(focus_handle.clone());
+        self
+    }
+
+    pub fn on_dismiss(mut self, handler: impl Fn(&mut Window, &mut App) + 'static) -> Self {
+        self.on_dismiss = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl ParentElement for Popover {
+    fn extend(&mut self, elements: impl IntoIterator<Item = AnyElement>) {
+        self.content.extend(elements);
+    }
+}
+
+impl Styled for Popover {
+    fn style(&mut self) -> &mut StyleRefinement {
+        self.content.style()
+    }
+}
+
+impl RenderOnce for Popover {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let trigger_bounds = Rc::new(Cell::new(Bounds::<Pixels>::default()));
+        let placement = self.placement;
+        let anchor = match placement {
+            Placement::Top => Corner::BottomLeft,
+            Placement::Bottom => Corner::TopLeft,
+            Placement::Left => Corner::TopRight,
+            Placement::Right => Corner::TopLeft,
+        };
+
+        let content = self.open.then(|| {
+            let on_dismiss = self.on_dismiss.clone();
+            let trigger_bounds = trigger_bounds.clone();
+
+            self.content
+                .id("popover-content")
+                .key_context(CONTEXT)
+                .when_some(self.focus_handle.as_ref(), |this, focus_handle| {
+                    this.track_focus(focus_handle)
+                })
+                .when_some(on_dismiss.clone(), |this, on_dismiss| {
+                    this.on_action(move |_: &Cancel, window, cx| on_dismiss(window, cx))
+                })
+                .occlude()
+                .map(|this| match placement {
+                    Placement::Top => this.mb_1(),
+                    Placement::Bottom => this.mt_1(),
+                    Placement::Left => this.mr_1(),
+                    Placement::Right => this.ml_1(),
+                })
+                .font_family(cx.theme().font_family.clone())
+                .text_color(cx.theme().popover_foreground)
+                .popover_style(cx)
+                // The trigger toggles the popover itself.
+                .when_some(on_dismiss, |this, on_dismiss| {
+                    this.on_mouse_down_out(move |event: &MouseDownEvent, window, cx| {
+                        if !trigger_bounds.get().contains(&event.position) {
+                            on_dismiss(window, cx);
+                        }
+                    })
+                })
+        });
+
+        div()
+            .id(self.id)
+            .relative()
+            .child(
+                canvas(
+                    move |bounds, _, _| trigger_bounds.set(bounds),
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
+            .children(self.trigger)
+            .when_some(content, |this, content| {
+                this.child(
+                    div()
+                        .absolute()
+                        .map(|this| match placement {
+                            Placement::Top => this.bottom_full().left_0(),
+                            Placement::Bottom => this.top_full().left_0(),
+                            Placement::Left => this.right_full().top_0(),
+                            Placement::Right => this.left_full().top_0(),
+                        })
+                        .child(deferred(
+                            anchored().anchor(anchor).snap_to_window().child(content),
+                        )),
+                )
+            })
+    }
+}
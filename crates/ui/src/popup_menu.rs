@@ -0,0 +1,499 @@
+use std::{cell::Cell, rc::Rc};
+
+use gpui::{
+    Action, AnyElement, App, AppContext as _, Bounds, Context, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, InteractiveElement as _, IntoElement, KeyBinding, MouseDownEvent,
+    ParentElement as _, Pixels, Point, Render, SharedString, StatefulInteractiveElement as _,
+    Styled as _, WeakEntity, Window, actions, canvas, div, prelude::FluentBuilder as _, px,
+};
+
+use crate::{
+    ActiveTheme as _, Icon, IconName, Kbd, StyledExt as _,
+    actions::{Cancel, Confirm, SelectNext, SelectPrev},
+    h_flex, v_flex,
+};
+
+const CONTEXT: &str = "PopupMenu";
+
+actions!(popup_menu, [SelectLeft, SelectRight]);
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("enter", Confirm { secondary: false }, Some(CONTEXT)),
+        KeyBinding::new("escape", Cancel, Some(CONTEXT)),
+        KeyBinding::new("up", SelectPrev, Some(CONTEXT)),
+        KeyBinding::new("down", SelectNext, Some(CONTEXT)),
+        KeyBinding::new("left", SelectLeft, Some(CONTEXT)),
+        KeyBinding::new("right", SelectRight, Some(CONTEXT)),
+    ]);
+}
+
+/// An item of the [`PopupMenu`].
+pub struct PopupMenuItem {
+    label: SharedString,
+    icon: Option<Icon>,
+    /// `None` if the item is not checkable.
+    checked: Option<bool>,
+    disabled: bool,
+    action: Option<Box<dyn Action>>,
+    handler: Option<Rc<dyn Fn(&mut Window, &mut App)>>,
+}
+
+impl PopupMenuItem {
+    pub fn new(label: impl Into<SharedString>) -> Self {
+        Self {
+            label: label.into(),
+            icon: None,
+            checked: None,
+            disabled: false,
+            action: None,
+            handler: None,
+        }
+    }
+
+    /// Set the icon before the label.
+    pub fn icon(mut self, icon: impl Into<Icon>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Make the item checkable, a check mark is shown before the label if checked.
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Set the action to show its key binding, the action is dispatched on click if there is
+    /// no [`Self::on_click`] handler.
+    pub fn action(mut self, action: Box<dyn Action>) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    /// Set the handler called on click, after the menu is dismissed.
+    pub fn on_click(mut self, handler: impl Fn(&mut Window, &mut App) + 'static) -> Self {
+        self.handler = Some(Rc::new(handler));
+        self
+    }
+}
+
+enum MenuEntry {
+    Item(PopupMenuItem),
+    Separator,
+    Submenu {
+        label: SharedString,
+        icon: Option<Icon>,
+        menu: Entity<PopupMenu>,
+    },
+}
+
+impl MenuEntry {
+    fn is_selectable(&self) -> bool {
+        match self {
+            Self::Item(item) => !item.disabled,
+            Self::Separator => false,
+            Self::Submenu { .. } => true,
+        }
+    }
+
+    fn has_icon(&self) -> bool {
+        match self {
+            Self::Item(item) => item.icon.is_some() || item.checked.is_some(),
+            Self::Separator => false,
+            Self::Submenu { icon, .. } => icon.is_some(),
+        }
+    }
+}
+
+/// A menu of the items, separators and submenus, e.g.: the context menu, see
+/// [`crate::context_menu::ContextMenuExt`].
+///
+/// Use `up`/`down` to select, `enter` to confirm, `right`/`left` to open/close the submenu and
+/// `escape` to dismiss. [`DismissEvent`] is emitted when dismissed.
+pub struct PopupMenu {
+    focus_handle: FocusHandle,
+    entries: Vec<MenuEntry>,
+    /// The key context to find the key bindings of the item actions.
+    key_context: Option<SharedString>,
+    selected_ix: Option<usize>,
+    open_submenu_ix: Option<usize>,
+    parent: Option<WeakEntity<PopupMenu>>,
+    /// The focus before the menu is shown, restored when dismissed.
+    previous_focus: Option<FocusHandle>,
+    bounds: Rc<Cell<Bounds<Pixels>>>,
+}
+
+impl EventEmitter<DismissEvent> for PopupMenu {}
+
+impl Focusable for PopupMenu {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl PopupMenu {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            entries: vec![],
+            key_context: None,
+            selected_ix: None,
+            open_submenu_ix: None,
+            parent: None,
+            previous_focus: window.focused(cx),
+            bounds: Rc::new(Cell::new(Bounds::default())),
+        }
+    }
+
+    /// Build the menu with the items added by `f`.
+    pub fn build(
+        window: &mut Window,
+        cx: &mut App,
+        f: impl FnOnce(Self, &mut Window, &mut Context<Self>) -> Self,
+    ) -> Entity<Self> {
+        cx.new(|cx| {
+            let menu = Self::new(window, cx);
+            f(menu, window, cx)
+        })
+    }
+
+    /// Set the key context to find the key bindings of the item actions, e.g.: `Editor`.
+    pub fn key_context(mut self, context: impl Into<SharedString>) -> Self {
+        self.key_context = Some(context.into());
+        self
+    }
+
+    pub fn item(mut self, item: PopupMenuItem) -> Self {
+        self.entries.push(MenuEntry::Item(item));
+        self
+    }
+
+    /// Add an item to dispatch the action.
+    pub fn menu(self, label: impl Into<SharedString>, action: Box<dyn Action>) -> Self {
+        self.item(PopupMenuItem::new(label).action(action))
+    }
+
+    /// Add a checkable item to dispatch the action.
+    pub fn menu_with_check(
+        self,
+        label: impl Into<SharedString>,
+        checked: bool,
+        action: Box<dyn Action>,
+    ) -> Self {
+        self.item(PopupMenuItem::new(label).checked(checked).action(action))
+    }
+
+    /// Add a separator, the leading and the repeated separators are ignored.
+    pub fn separator(mut self) -> Self {
+        if !matches!(self.entries.last(), None | Some(MenuEntry::Separator)) {
+            self.entries.push(MenuEntry::Separator);
+        }
+        self
+    }
+
+    /// Add a submenu with the items added by `f`, shown on the right of the item.
+    pub fn submenu(
+        mut self,
+        label: impl Into<SharedString>,
+        icon: Option<Icon>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        f: impl FnOnce(Self, &mut Window, &mut Context<Self>) -> Self,
+    ) -> Self {
+        let parent = cx.weak_entity();
+        let key_context = self.key_context.clone();
+        let menu = cx.new(|cx| {
+            let mut menu = Self::new(window, cx);
+            menu.parent = Some(parent);
+            menu.key_context = key_context;
+            menu.previous_focus = None;
+            f(menu, window, cx)
+        });
+        self.entries.push(MenuEntry::Submenu {
+            label: label.into(),
+            icon,
+            menu,
+        });
+        self
+    }
+
+    fn select_by(&mut self, step: isize, cx: &mut Context<Self>) {
+        let len = self.entries.len();
+        if len == 0 {
+            return;
+        }
+
+        let mut ix = match (self.selected_ix, step > 0) {
+            (Some(ix), _) => ix,
+            (None, true) => len - 1,
+            (None, false) => 0,
+        };
+        for _ in 0..len {
+            ix = (ix as isize + step).rem_euclid(len as isize) as usize;
+            if self.entries[ix].is_selectable() {
+                self.selected_ix = Some(ix);
+                self.open_submenu_ix = None;
+                cx.notify();
+                return;
+            }
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_by(-1, cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_by(1, cx);
+    }
+
+    /// Close the submenu and go back to the parent menu.
+    fn select_left(&mut self, _: &SelectLeft, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(parent) = self.parent.as_ref().and_then(|parent| parent.upgrade()) else {
+            return;
+        };
+
+        parent.update(cx, |parent, cx| {
+            parent.open_submenu_ix = None;
+            parent.focus_handle.focus(window);
+            cx.notify();
+        });
+    }
+
+    fn select_right(&mut self, _: &SelectRight, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix {
+            self.open_submenu(ix, true, window, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix {
+            self.confirm_ix(ix, window, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        self.dismiss(window, cx);
+    }
+
+    fn confirm_ix(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+
+        match entry {
+            MenuEntry::Item(item) if !item.disabled => {
+                let handler = item.handler.clone();
+                let action = item.action.as_ref().map(|action| action.boxed_clone());
+
+                // Dismiss first to dispatch the action to the previous focus.
+                self.dismiss(window, cx);
+                if let Some(handler) = handler {
+                    handler(window, cx);
+                } else if let Some(action) = action {
+                    window.dispatch_action(action, cx);
+                }
+            }
+            MenuEntry::Submenu { .. } => self.open_submenu(ix, true, window, cx),
+            _ => {}
+        }
+    }
+
+    /// Open the submenu at the index, and select its first item if `focus`.
+    fn open_submenu(
+        &mut self,
+        ix: usize,
+        focus: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(MenuEntry::Submenu { menu, .. }) = self.entries.get(ix) else {
+            return;
+        };
+
+        self.selected_ix = Some(ix);
+        self.open_submenu_ix = Some(ix);
+        if focus {
+            menu.update(cx, |menu, cx| {
+                menu.selected_ix = None;
+                menu.select_by(1, cx);
+                menu.focus_handle.focus(window);
+            });
+        }
+        cx.notify();
+    }
+
+    /// Dismiss the menu with the parent menus, and restore the focus.
+    fn dismiss(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+
+        match self.parent.as_ref().and_then(|parent| parent.upgrade()) {
+            Some(parent) => parent.update(cx, |parent, cx| parent.dismiss(window, cx)),
+            None => {
+                if let Some(focus_handle) = &self.previous_focus {
+                    window.focus(focus_handle);
+                }
+            }
+        }
+    }
+
+    /// Return true if the position is in the menu or the open submenu.
+    fn contains(&self, position: Point<Pixels>, cx: &App) -> bool {
+        if self.bounds.get().contains(&position) {
+            return true;
+        }
+
+        match self.open_submenu_ix.and_then(|ix| self.entries.get(ix)) {
+            Some(MenuEntry::Submenu { menu, .. }) => menu.read(cx).contains(position, cx),
+            _ => false,
+        }
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        entry: &MenuEntry,
+        has_icon: bool,
+        window: &Window,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let selected = self.selected_ix == Some(ix);
+        let row = |icon: Option<Icon>| {
+            h_flex()
+                .id(ix)
+                .relative()
+                .h(px(26.))
+                .px_2()
+                .gap_2()
+                .rounded(px(4.))
+                .when(selected, |this| {
+                    this.bg(cx.theme().accent)
+                        .text_color(cx.theme().accent_foreground)
+                })
+                .when(has_icon, |this| {
+                    this.child(div().flex_shrink_0().size_4().children(icon))
+                })
+        };
+        let on_hover = cx.listener(move |this, hovered: &bool, window, cx| {
+            if !*hovered {
+                return;
+            }
+            this.selected_ix = Some(ix);
+            match this.entries.get(ix) {
+                Some(MenuEntry::Submenu { .. }) => this.open_submenu(ix, false, window, cx),
+                _ => this.open_submenu_ix = None,
+            }
+            cx.notify();
+        });
+
+        match entry {
+            MenuEntry::Separator => div()
+                .my_1()
+                .h(px(1.))
+                .bg(cx.theme().border)
+                .into_any_element(),
+            MenuEntry::Item(item) => {
+                let icon = match item.checked {
+                    Some(true) => Some(Icon::new(IconName::Check).size_4()),
+                    Some(false) => None,
+                    None => item.icon.clone(),
+                };
+                let key_binding = item.action.as_ref().and_then(|action| {
+                    Kbd::binding_for_action(action.as_ref(), self.key_context.as_deref(), window)
+                });
+
+                row(icon)
+                    .child(div().flex_1().whitespace_nowrap().child(item.label.clone()))
+                    .when_some(key_binding, |this, kbd| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(kbd.appearance(false)),
+                        )
+                    })
+                    .map(|this| {
+                        if item.disabled {
+                            this.text_color(cx.theme().muted_foreground)
+                        } else {
+                            this.on_hover(on_hover).on_click(cx.listener(
+                                move |this, _, window, cx| this.confirm_ix(ix, window, cx),
+                            ))
+                        }
+                    })
+                    .into_any_element()
+            }
+            MenuEntry::Submenu { label, icon, menu } => {
+                let open = self.open_submenu_ix == Some(ix);
+
+                row(icon.clone())
+                    .child(div().flex_1().whitespace_nowrap().child(label.clone()))
+                    .child(Icon::new(IconName::ChevronRight).size_4())
+                    .on_hover(on_hover)
+                    .on_click(
+                        cx.listener(move |this, _, window, cx| this.confirm_ix(ix, window, cx)),
+                    )
+                    .when(open, |this| {
+                        this.child(
+                            div()
+                                .absolute()
+                                .top(px(-5.))
+                                .left_full()
+                                .pl_1()
+                                .child(menu.clone()),
+                        )
+                    })
+                    .into_any_element()
+            }
+        }
+    }
+}
+
+impl Render for PopupMenu {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let has_icon = self.entries.iter().any(MenuEntry::has_icon);
+        let bounds = self.bounds.clone();
+
+        v_flex()
+            .id("popup-menu")
+            .key_context(CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            // The submenus are dismissed with the root menu.
+            .when(self.parent.is_none(), |this| {
+                this.on_mouse_down_out(cx.listener(|this, event: &MouseDownEvent, window, cx| {
+                    if !this.contains(event.position, cx) {
+                        this.dismiss(window, cx);
+                    }
+                }))
+            })
+            .occlude()
+            .relative()
+            .min_w(px(180.))
+            .p_1()
+            .text_sm()
+            .text_color(cx.theme().popover_foreground)
+            .popover_style(cx)
+            .child(
+                canvas(move |b, _, _| bounds.set(b), |_, _, _, _| {})
+                    .absolute()
+                    .size_full(),
+            )
+            .children(
+                self.entries
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, entry)| self.render_entry(ix, entry, has_icon, window, cx)),
+            )
+    }
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use gpui::{
+    Animation, AnimationExt as _, App, IntoElement, ParentElement as _, Pixels, RenderOnce,
+    Styled as _, Window, div, ease_in_out, prelude::FluentBuilder as _, px, relative,
+};
+
+use crate::{ActiveTheme as _, Sizable, Size};
+
+/// A progress bar, indeterminate until a value is set.
+///
+/// ```ignore
+/// Progress::new().value(percentage)
+/// // e.g.: while the length of the work is unknown.
+/// Progress::new()
+/// ```
+#[derive(IntoElement)]
+pub struct Progress {
+    value: Option<f32>,
+    size: Size,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self {
+            value: None,
+            size: Size::default(),
+        }
+    }
+
+    /// Set the value from `0.` to `100.`.
+    pub fn value(mut self, value: f32) -> Self {
+        self.value = Some(value.clamp(0., 100.));
+        self
+    }
+
+    fn height(&self) -> Pixels {
+        match self.size {
+            Size::Size(size) => size,
+            Size::XSmall => px(2.),
+            Size::Small => px(4.),
+            Size::Medium => px(8.),
+            Size::Large => px(12.),
+        }
+    }
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sizable for Progress {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+impl RenderOnce for Progress {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let height = self.height();
+        let bar = div().h_full().rounded_full().bg(cx.theme().progress_bar);
+
+        div()
+            .relative()
+            .w_full()
+            .h(height)
+            .overflow_hidden()
+            .rounded_full()
+            .bg(cx.theme().progress_bar.opacity(0.2))
+            .map(|this| match self.value {
+                Some(value) => this.child(bar.w(relative(value / 100.))),
+                // A third of the bar slides from the left to the right.
+                None => this.child(
+                    bar.absolute().top_0().w(relative(1. / 3.)).with_animation(
+                        "indeterminate",
+                        Animation::new(Duration::from_secs_f64(1.5))
+                            .repeat()
+                            .with_easing(ease_in_out),
+                        |this, delta| this.left(relative(indeterminate_left(delta))),
+                    ),
+                ),
+            })
+    }
+}
+
+/// Return the left of the indeterminate bar relative to the track, the bar (a third of the
+/// track) slides from out of the left to out of the right.
+fn indeterminate_left(delta: f32) -> f32 {
+    delta * 4. / 3. - 1. / 3.
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::px;
+
+    use super::{Progress, indeterminate_left};
+    use crate::Sizable as _;
+
+    #[test]
+    fn test_value() {
+        assert_eq!(Progress::new().value, None);
+        assert_eq!(Progress::new().value(42.5).value, Some(42.5));
+        assert_eq!(Progress::new().value(120.).value, Some(100.));
+        assert_eq!(Progress::new().value(-1.).value, Some(0.));
+    }
+
+    #[test]
+    fn test_height() {
+        assert_eq!(Progress::new().height(), px(8.));
+        assert_eq!(Progress::new().xsmall().height(), px(2.));
+        assert_eq!(Progress::new().large().height(), px(12.));
+        assert_eq!(Progress::new().with_size(px(6.)).height(), px(6.));
+    }
+
+    #[test]
+    fn test_indeterminate_left() {
+        let width = 1. / 3.;
+        assert!((indeterminate_left(0.) + width).abs() < f32::EPSILON);
+        assert!(indeterminate_left(0.25).abs() < f32::EPSILON);
+        assert!((indeterminate_left(1.) - 1.).abs() < f32::EPSILON);
+    }
+}
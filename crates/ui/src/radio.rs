@@ -0,0 +1,278 @@
+use std::rc::Rc;
+
+use gpui::{
+    App, ClickEvent, ElementId, InteractiveElement as _, IntoElement, ParentElement as _,
+    RenderOnce, SharedString, StatefulInteractiveElement as _, Styled as _, Window, div,
+    prelude::FluentBuilder as _, px,
+};
+
+use crate::{ActiveTheme as _, Disableable, Sizable, Size, focus, h_flex};
+
+type RadioHandler = Rc<dyn Fn(&ClickEvent, &mut Window, &mut App)>;
+
+/// A radio button, registered in the focus registry by its id if it has a click handler.
+///
+/// In a [`RadioGroup`], the group handles the clicks and the focus.
+#[derive(IntoElement)]
+pub struct Radio {
+    id: ElementId,
+    checked: bool,
+    label: Option<SharedString>,
+    disabled: bool,
+    size: Size,
+    focused: bool,
+    on_click: Option<RadioHandler>,
+}
+
+impl Radio {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            checked: false,
+            label: None,
+            disabled: false,
+            size: Size::default(),
+            focused: false,
+            on_click: None,
+        }
+    }
+
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    pub fn label(mut self, label: impl Into<SharedString>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set the handler of the click, it's also called by `enter` and `space`.
+    pub fn on_click(
+        mut self,
+        handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_click = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl Disableable for Radio {
+    fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl Sizable for Radio {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+impl RenderOnce for Radio {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let on_click = self.on_click.filter(|_| !self.disabled);
+        let focus_handle = on_click.clone().map(|on_click| {
+            focus::register_control(
+                cx,
+                self.id.clone(),
+                self.disabled,
+                Some(
+                    move |event: &ClickEvent, window: &mut Window, cx: &mut App| {
+                        on_click(event, window, cx)
+                    },
+                ),
+            )
+        });
+        let focused = self.focused
+            || focus_handle
+                .as_ref()
+                .is_some_and(|handle| handle.is_focused(window));
+
+        let circle_size = match self.size {
+            Size::XSmall | Size::Small => px(14.),
+            _ => px(16.),
+        };
+
+        h_flex()
+            .id(self.id)
+            .when_some(focus_handle.as_ref(), |this, handle| {
+                this.track_focus(handle)
+            })
+            .gap_2()
+            .when(self.disabled, |this| this.opacity(0.5))
+            .when(!self.disabled, |this| this.cursor_pointer())
+            .child(
+                h_flex()
+                    .flex_shrink_0()
+                    .justify_center()
+                    .size(circle_size)
+                    .rounded_full()
+                    .border_1()
+                    .border_color(if focused {
+                        cx.theme().ring
+                    } else {
+                        cx.theme().primary
+                    })
+                    .when(self.checked, |this| {
+                        this.child(
+                            div()
+                                .size(circle_size / 2.)
+                                .rounded_full()
+                                .bg(cx.theme().primary),
+                        )
+                    }),
+            )
+            .when_some(self.label, |this, label| {
+                this.child(div().text_sm().child(label))
+            })
+            .when_some(on_click, |this, on_click| {
+                this.on_click({
+                    let on_click = on_click.clone();
+                    move |event, window, cx| on_click(event, window, cx)
+                })
+                .on_key_down(move |event, window, cx| {
+                    if event.keystroke.key == "space" {
+                        cx.stop_propagation();
+                        on_click(&ClickEvent::default(), window, cx);
+                    }
+                })
+            })
+    }
+}
+
+/// A group of radio buttons, one of them is selected.
+///
+/// The group is one stop of the tab traversal, registered in the focus registry by its id,
+/// the arrow keys select the previous or the next radio.
+///
+/// ```ignore
+/// RadioGroup::new("theme")
+///     .children(["Light", "Dark"])
+///     .selected_index(Some(ix))
+///     .on_change(|ix, _, cx| ..)
+/// ```
+#[derive(IntoElement)]
+pub struct RadioGroup {
+    id: ElementId,
+    labels: Vec<SharedString>,
+    selected_ix: Option<usize>,
+    vertical: bool,
+    disabled: bool,
+    size: Size,
+    on_change: Option<Rc<dyn Fn(&usize, &mut Window, &mut App)>>,
+}
+
+impl RadioGroup {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            labels: vec![],
+            selected_ix: None,
+            vertical: false,
+            disabled: false,
+            size: Size::default(),
+            on_change: None,
+        }
+    }
+
+    pub fn child(mut self, label: impl Into<SharedString>) -> Self {
+        self.labels.push(label.into());
+        self
+    }
+
+    pub fn children(mut self, labels: impl IntoIterator<Item = impl Into<SharedString>>) -> Self {
+        self.labels.extend(labels.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn selected_index(mut self, ix: Option<usize>) -> Self {
+        self.selected_ix = ix;
+        self
+    }
+
+    /// Stack the radios vertically, they are in a row by default.
+    pub fn vertical(mut self) -> Self {
+        self.vertical = true;
+        self
+    }
+
+    /// Set the handler of the selection, the argument is the index of the selected radio.
+    pub fn on_change(mut self, handler: impl Fn(&usize, &mut Window, &mut App) + 'static) -> Self {
+        self.on_change = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl Disableable for RadioGroup {
+    fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl Sizable for RadioGroup {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+impl RenderOnce for RadioGroup {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let on_change = self.on_change.filter(|_| !self.disabled);
+        let focus_handle = focus::register_control(
+            cx,
+            self.id.clone(),
+            self.disabled,
+            None::<fn(&ClickEvent, &mut Window, &mut App)>,
+        );
+        let focused = focus_handle.is_focused(window);
+        let count = self.labels.len();
+        let selected_ix = self.selected_ix;
+
+        let size = self.size;
+        let disabled = self.disabled;
+
+        div()
+            .id(self.id)
+            .track_focus(&focus_handle)
+            .flex()
+            .map(|this| {
+                if self.vertical {
+                    this.flex_col().gap_2()
+                } else {
+                    this.flex_row().gap_4()
+                }
+            })
+            .children(self.labels.into_iter().enumerate().map(|(ix, label)| {
+                let mut radio = Radio::new(ix)
+                    .label(label)
+                    .checked(selected_ix == Some(ix))
+                    .disabled(disabled)
+                    .with_size(size);
+                radio.focused = focused && selected_ix.unwrap_or(0) == ix;
+
+                div()
+                    .id(("radio", ix))
+                    .when_some(on_change.clone(), |this, on_change| {
+                        this.on_click(move |_, window, cx| on_change(&ix, window, cx))
+                    })
+                    .child(radio)
+            }))
+            .when_some(on_change.filter(|_| count > 0), |this, on_change| {
+                this.on_key_down(move |event, window, cx| {
+                    let ix = match (event.keystroke.key.as_str(), selected_ix) {
+                        ("left" | "up", Some(ix)) => ix.checked_sub(1).unwrap_or(count - 1),
+                        ("right" | "down", Some(ix)) => (ix + 1) % count,
+                        ("left" | "up" | "right" | "down" | "space", None) => 0,
+                        _ => return,
+                    };
+                    cx.stop_propagation();
+                    on_change(&ix, window, cx);
+                })
+            })
+    }
+}
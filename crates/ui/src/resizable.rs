@@ -0,0 +1,375 @@
+use std::{cell::Cell, rc::Rc};
+
+use gpui::{
+    AnyElement, App, AppContext as _, Axis, Bounds, Context, DragMoveEvent, ElementId, Empty,
+    Entity, EntityId, EventEmitter, InteractiveElement as _, IntoElement, MouseButton,
+    MouseUpEvent, ParentElement, Pixels, Render, RenderOnce, StatefulInteractiveElement as _,
+    Styled as _, Window, canvas, div, prelude::FluentBuilder as _, px, relative,
+};
+
+use crate::ActiveTheme as _;
+
+pub enum ResizableEvent {
+    /// The panels are resized by a drag, emitted when the drag ends inside or outside of the
+    /// group. The sizes are the parts of the group, see [`ResizableState::sizes`].
+    Resized(Vec<f32>),
+}
+
+/// The handle being dragged, `(group, ix)`, the handle is after the panel `ix`.
+#[derive(Clone)]
+struct DragHandle(EntityId, usize);
+
+impl Render for DragHandle {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        Empty
+    }
+}
+
+/// The state of a [`ResizablePanelGroup`], the size of each panel as a part of the group.
+pub struct ResizableState {
+    axis: Axis,
+    sizes: Vec<f32>,
+    /// The sizes of the panels if the `sizes` don't match them, set on render.
+    defaults: Vec<Option<f32>>,
+    /// The min and max sizes of the panels, set on render.
+    limits: Vec<(Pixels, Option<Pixels>)>,
+    bounds: Rc<Cell<Bounds<Pixels>>>,
+    /// Whether a handle of the group is being dragged.
+    resizing: bool,
+}
+
+impl EventEmitter<ResizableEvent> for ResizableState {}
+
+impl ResizableState {
+    pub fn new(axis: Axis) -> Self {
+        Self {
+            axis,
+            sizes: vec![],
+            defaults: vec![],
+            limits: vec![],
+            bounds: Rc::new(Cell::new(Bounds::default())),
+            resizing: false,
+        }
+    }
+
+    /// Set the sizes of the panels, e.g.: the sizes saved from [`ResizableEvent::Resized`].
+    ///
+    /// The sizes are ignored if there are not as many panels.
+    pub fn sizes(mut self, sizes: Vec<f32>) -> Self {
+        self.sizes = sizes;
+        self
+    }
+
+    pub fn axis(&self) -> Axis {
+        self.axis
+    }
+
+    /// The sizes of the panels, the `sizes` if they match the panels or the defaults.
+    fn panel_sizes(&self) -> Vec<f32> {
+        if self.sizes.len() == self.defaults.len() {
+            self.sizes.clone()
+        } else {
+            fill(&self.defaults)
+        }
+    }
+
+    fn on_drag_move(
+        &mut self,
+        event: &DragMoveEvent<DragHandle>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let &DragHandle(group, ix) = event.drag(cx);
+        if group != cx.entity_id() {
+            return;
+        }
+        let bounds = self.bounds.get();
+        let (position, origin, total) = match self.axis {
+            Axis::Horizontal => (event.event.position.x, bounds.left(), bounds.size.width),
+            Axis::Vertical => (event.event.position.y, bounds.top(), bounds.size.height),
+        };
+        if total <= px(0.) {
+            return;
+        }
+
+        let mut sizes = self.panel_sizes();
+        let limits = self
+            .limits
+            .iter()
+            .map(|(min, max)| (*min / total, max.map_or(1., |max| max / total)))
+            .collect::<Vec<_>>();
+        let handle = sizes[..=ix].iter().sum::<f32>();
+        resize(
+            &mut sizes,
+            ix,
+            (position - origin) / total - handle,
+            &limits,
+        );
+        self.sizes = sizes;
+        self.resizing = true;
+        cx.notify();
+    }
+
+    /// Emit the sizes when the drag ends, the mouse may be released outside of the group.
+    fn finish_resize(&mut self, _: &MouseUpEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.resizing {
+            return;
+        }
+        self.resizing = false;
+        cx.emit(ResizableEvent::Resized(self.sizes.clone()));
+    }
+}
+
+/// Return the sizes of the panels, the panels without a size share the rest of the group.
+fn fill(sizes: &[Option<f32>]) -> Vec<f32> {
+    let set = sizes.iter().flatten().sum::<f32>();
+    let unset = sizes.iter().filter(|size| size.is_none()).count();
+    let rest = if unset > 0 {
+        (1. - set).max(0.) / unset as f32
+    } else {
+        0.
+    };
+    let sizes = sizes
+        .iter()
+        .map(|size| size.unwrap_or(rest))
+        .collect::<Vec<_>>();
+    let total = sizes.iter().sum::<f32>();
+    if total <= 0. {
+        let count = sizes.len() as f32;
+        return sizes.iter().map(|_| 1. / count).collect();
+    }
+    sizes.iter().map(|size| size / total).collect()
+}
+
+/// Move the handle after the panel `ix` by `delta`, the panels on both sides of it are resized
+/// within their `(min, max)` limits.
+fn resize(sizes: &mut [f32], ix: usize, delta: f32, limits: &[(f32, f32)]) {
+    if ix + 1 >= sizes.len() {
+        return;
+    }
+    let (before, after) = (sizes[ix], sizes[ix + 1]);
+    let (before_min, before_max) = limits.get(ix).copied().unwrap_or((0., 1.));
+    let (after_min, after_max) = limits.get(ix + 1).copied().unwrap_or((0., 1.));
+    let lower = (before_min - before).max(after - after_max);
+    let upper = (before_max - before).min(after - after_min);
+    if lower > upper {
+        return;
+    }
+    let delta = delta.clamp(lower, upper);
+    sizes[ix] = before + delta;
+    sizes[ix + 1] = after - delta;
+}
+
+/// A panel of a [`ResizablePanelGroup`].
+pub struct ResizablePanel {
+    size: Option<f32>,
+    min_size: Pixels,
+    max_size: Option<Pixels>,
+    children: Vec<AnyElement>,
+}
+
+impl ResizablePanel {
+    pub fn new() -> Self {
+        Self {
+            size: None,
+            min_size: px(100.),
+            max_size: None,
+            children: vec![],
+        }
+    }
+
+    /// Set the initial size as a part of the group, e.g.: `0.3`, the panels without a size share
+    /// the rest of it.
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Set the min size, `100px` by default.
+    pub fn min_size(mut self, size: impl Into<Pixels>) -> Self {
+        self.min_size = size.into();
+        self
+    }
+
+    pub fn max_size(mut self, size: impl Into<Pixels>) -> Self {
+        self.max_size = Some(size.into());
+        self
+    }
+}
+
+impl Default for ResizablePanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParentElement for ResizablePanel {
+    fn extend(&mut self, elements: impl IntoIterator<Item = AnyElement>) {
+        self.children.extend(elements);
+    }
+}
+
+/// Panels in a row or a column, resized by dragging the handles between them.
+///
+/// ```ignore
+/// let state = cx.new(|_| ResizableState::new(Axis::Horizontal).sizes(saved_sizes));
+/// cx.subscribe(&state, |_, _, ResizableEvent::Resized(sizes), cx| save(sizes)).detach();
+///
+/// ResizablePanelGroup::new("main", &state)
+///     .child(ResizablePanel::new().min_size(px(320.)).child(chat))
+///     .child(ResizablePanel::new().size(0.4).child(changes))
+/// ```
+#[derive(IntoElement)]
+pub struct ResizablePanelGroup {
+    id: ElementId,
+    state: Entity<ResizableState>,
+    panels: Vec<ResizablePanel>,
+}
+
+impl ResizablePanelGroup {
+    pub fn new(id: impl Into<ElementId>, state: &Entity<ResizableState>) -> Self {
+        Self {
+            id: id.into(),
+            state: state.clone(),
+            panels: vec![],
+        }
+    }
+
+    pub fn child(mut self, panel: ResizablePanel) -> Self {
+        self.panels.push(panel);
+        self
+    }
+
+    pub fn children(mut self, panels: impl IntoIterator<Item = ResizablePanel>) -> Self {
+        self.panels.extend(panels);
+        self
+    }
+}
+
+impl RenderOnce for ResizablePanelGroup {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let defaults = self.panels.iter().map(|panel| panel.size).collect();
+        let limits = self
+            .panels
+            .iter()
+            .map(|panel| (panel.min_size, panel.max_size))
+            .collect();
+        let (axis, sizes, bounds) = self.state.update(cx, |state, _| {
+            state.defaults = defaults;
+            state.limits = limits;
+            (state.axis, state.panel_sizes(), state.bounds.clone())
+        });
+        let group = self.state.entity_id();
+        let horizontal = axis == Axis::Horizontal;
+
+        let handles = sizes
+            .iter()
+            .scan(0., |position, size| {
+                *position += size;
+                Some(*position)
+            })
+            .take(sizes.len().saturating_sub(1))
+            .enumerate()
+            .map(|(ix, position)| {
+                div()
+                    .id(("resize-handle", ix))
+                    .absolute()
+                    .map(|this| {
+                        if horizontal {
+                            this.top_0()
+                                .left(relative(position))
+                                .ml(px(-2.))
+                                .w(px(4.))
+                                .h_full()
+                                .cursor_col_resize()
+                        } else {
+                            this.left_0()
+                                .top(relative(position))
+                                .mt(px(-2.))
+                                .h(px(4.))
+                                .w_full()
+                                .cursor_row_resize()
+                        }
+                    })
+                    .hover(|this| this.bg(cx.theme().drag_border))
+                    .on_drag(DragHandle(group, ix), |drag, _, _, cx| {
+                        cx.new(|_| drag.clone())
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        div()
+            .id(self.id)
+            .relative()
+            .flex()
+            .when(!horizontal, |this| this.flex_col())
+            .size_full()
+            .overflow_hidden()
+            .on_drag_move(window.listener_for(&self.state, ResizableState::on_drag_move))
+            .on_mouse_up(
+                MouseButton::Left,
+                window.listener_for(&self.state, ResizableState::finish_resize),
+            )
+            .on_mouse_up_out(
+                MouseButton::Left,
+                window.listener_for(&self.state, ResizableState::finish_resize),
+            )
+            .child(
+                canvas(move |b, _, _| bounds.set(b), |_, _, _, _| {})
+                    .absolute()
+                    .size_full(),
+            )
+            .children(
+                self.panels
+                    .into_iter()
+                    .zip(sizes)
+                    .enumerate()
+                    .map(|(ix, (panel, size))| {
+                        div()
+                            .flex_shrink_0()
+                            .overflow_hidden()
+                            .map(|this| {
+                                if horizontal {
+                                    this.h_full().w(relative(size))
+                                } else {
+                                    this.w_full().h(relative(size))
+                                }
+                            })
+                            .when(ix > 0, |this| {
+                                if horizontal {
+                                    this.border_l_1().border_color(cx.theme().border)
+                                } else {
+                                    this.border_t_1().border_color(cx.theme().border)
+                                }
+                            })
+                            .children(panel.children)
+                    }),
+            )
+            .children(handles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fill, resize};
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(fill(&[None, None]), vec![0.5, 0.5]);
+        assert_eq!(fill(&[Some(0.25), None, None]), vec![0.25, 0.375, 0.375]);
+        assert_eq!(fill(&[Some(1.), Some(3.)]), vec![0.25, 0.75]);
+        assert_eq!(fill(&[Some(0.), Some(0.)]), vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_resize() {
+        let limits = [(0.25, 1.), (0., 0.5)];
+        let mut sizes = [0.5, 0.5];
+        resize(&mut sizes, 0, 0.25, &limits);
+        assert_eq!(sizes, [0.75, 0.25]);
+        resize(&mut sizes, 0, -0.75, &limits);
+        assert_eq!(sizes, [0.5, 0.5]);
+        resize(&mut sizes, 1, 0.25, &limits);
+        assert_eq!(sizes, [0.5, 0.5]);
+    }
+}
@@ -0,0 +1,604 @@
+use std::{cell::Cell, ops::Range, rc::Rc};
+
+use gpui::{
+    App, AppContext as _, Bounds, ClickEvent, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, FontWeight, HighlightStyle, InteractiveElement as _, IntoElement, KeyBinding,
+    KeyDownEvent, ParentElement as _, Pixels, Render, RenderOnce, ScrollStrategy, SharedString,
+    StatefulInteractiveElement as _, Styled as _, StyledText, UniformListScrollHandle, Window,
+    canvas, div, prelude::FluentBuilder as _, px, uniform_list,
+};
+
+use crate::{
+    ActiveTheme as _, Icon, IconName, Placement, Sizable, Size, StyleSized as _, StyledExt as _,
+    actions::{Cancel, Confirm, SelectNext, SelectPrev},
+    h_flex,
+    input::{FuzzyMatch, fuzzy_match},
+    popover::Popover,
+    v_flex,
+};
+
+const CONTEXT: &str = "Select";
+/// The maximum number of the visible items of the menu.
+const MAX_VISIBLE_ITEMS: usize = 8;
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("up", SelectPrev, Some(CONTEXT)),
+        KeyBinding::new("down", SelectNext, Some(CONTEXT)),
+        KeyBinding::new("enter", Confirm { secondary: false }, Some(CONTEXT)),
+        KeyBinding::new("escape", Cancel, Some(CONTEXT)),
+    ]);
+}
+
+/// An option of the [`SelectState`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectItem {
+    pub value: SharedString,
+    /// The text shown in the menu and the trigger, default is the `value`.
+    pub label: SharedString,
+    /// The secondary text shown after the label in the menu.
+    pub description: Option<SharedString>,
+}
+
+impl SelectItem {
+    pub fn new(value: impl Into<SharedString>) -> Self {
+        let value = value.into();
+        Self {
+            label: value.clone(),
+            value,
+            description: None,
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<SharedString>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<SharedString>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+impl From<&'static str> for SelectItem {
+    fn from(value: &'static str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for SelectItem {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<SharedString> for SelectItem {
+    fn from(value: SharedString) -> Self {
+        Self::new(value)
+    }
+}
+
+pub enum SelectEvent {
+    /// The item with the value is selected.
+    Change(SharedString),
+}
+
+/// The state of the [`Select`], a dropdown to select one of the items.
+///
+/// Use `up`/`down` to open the menu and highlight the items, and `enter` to select. Typing
+/// filters the items if [`Self::searchable`].
+pub struct SelectState {
+    focus_handle: FocusHandle,
+    items: Vec<SelectItem>,
+    selected_ix: Option<usize>,
+    placeholder: SharedString,
+    searchable: bool,
+    open: bool,
+    query: String,
+    /// The matched items, `(item_ix, match)`, the best match first.
+    matches: Vec<(usize, FuzzyMatch)>,
+    /// The index in `matches` of the highlighted item.
+    highlighted_ix: usize,
+    scroll_handle: UniformListScrollHandle,
+    size: Size,
+    /// The bounds of the trigger, the menu is as wide as the trigger.
+    bounds: Rc<Cell<Bounds<Pixels>>>,
+}
+
+impl EventEmitter<SelectEvent> for SelectState {}
+
+impl Focusable for SelectState {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl SelectState {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let focus_handle = cx.focus_handle();
+        cx.on_blur(&focus_handle, window, |this, _, cx| this.close(cx))
+            .detach();
+
+        Self {
+            focus_handle,
+            items: vec![],
+            selected_ix: None,
+            placeholder: "Select...".into(),
+            searchable: false,
+            open: false,
+            query: String::new(),
+            matches: vec![],
+            highlighted_ix: 0,
+            scroll_handle: UniformListScrollHandle::new(),
+            size: Size::default(),
+            bounds: Rc::new(Cell::new(Bounds::default())),
+        }
+    }
+
+    pub fn items(mut self, items: impl IntoIterator<Item = impl Into<SelectItem>>) -> Self {
+        self.items = items.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Replace the items, the selected value is kept if it's still an item.
+    pub fn set_items(
+        &mut self,
+        items: impl IntoIterator<Item = impl Into<SelectItem>>,
+        cx: &mut Context<Self>,
+    ) {
+        let selected_value = self.selected_value().cloned();
+        self.items = items.into_iter().map(Into::into).collect();
+        self.selected_ix = selected_value.and_then(|value| self.position(&value));
+        self.filter();
+        cx.notify();
+    }
+
+    /// Set the selected item by the value.
+    pub fn default_value(mut self, value: impl Into<SharedString>) -> Self {
+        self.selected_ix = self.position(&value.into());
+        self
+    }
+
+    /// Set the text shown if nothing is selected.
+    pub fn placeholder(mut self, placeholder: impl Into<SharedString>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Filter the items by typing when the select is focused, default is false.
+    pub fn searchable(mut self, searchable: bool) -> Self {
+        self.searchable = searchable;
+        self
+    }
+
+    pub fn selected_item(&self) -> Option<&SelectItem> {
+        self.selected_ix.and_then(|ix| self.items.get(ix))
+    }
+
+    pub fn selected_value(&self) -> Option<&SharedString> {
+        self.selected_item().map(|item| &item.value)
+    }
+
+    /// Select the item with the value, or clear the selection if there is no such item. No
+    /// [`SelectEvent`] is emitted.
+    pub fn set_selected_value(&mut self, value: &str, cx: &mut Context<Self>) {
+        self.selected_ix = self.position(value);
+        cx.notify();
+    }
+
+    fn position(&self, value: &str) -> Option<usize> {
+        self.items.iter().position(|item| item.value == value)
+    }
+
+    fn filter(&mut self) {
+        self.matches = filter_items(&self.items, &self.query);
+        self.highlighted_ix = self
+            .selected_ix
+            .and_then(|selected_ix| self.matches.iter().position(|(ix, _)| *ix == selected_ix))
+            .filter(|_| self.query.is_empty())
+            .unwrap_or(0);
+        self.scroll_handle
+            .scroll_to_item(self.highlighted_ix, ScrollStrategy::Top);
+    }
+
+    fn open(&mut self, cx: &mut Context<Self>) {
+        self.open = true;
+        self.query.clear();
+        self.filter();
+        cx.notify();
+    }
+
+    fn close(&mut self, cx: &mut Context<Self>) {
+        if self.open {
+            self.open = false;
+            cx.notify();
+        }
+    }
+
+    fn toggle(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if self.open {
+            self.close(cx);
+        } else {
+            self.open(cx);
+        }
+        self.focus_handle.focus(window);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.open {
+            return self.open(cx);
+        }
+        if self.matches.is_empty() {
+            return;
+        }
+        self.highlighted_ix = self
+            .highlighted_ix
+            .checked_sub(1)
+            .unwrap_or(self.matches.len() - 1);
+        self.scroll_handle
+            .scroll_to_item(self.highlighted_ix, ScrollStrategy::Top);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.open {
+            return self.open(cx);
+        }
+        if self.matches.is_empty() {
+            return;
+        }
+        self.highlighted_ix = (self.highlighted_ix + 1) % self.matches.len();
+        self.scroll_handle
+            .scroll_to_item(self.highlighted_ix, ScrollStrategy::Top);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if self.open {
+            self.confirm_ix(self.highlighted_ix, cx);
+        } else {
+            self.open(cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        if self.open {
+            self.close(cx);
+        } else {
+            cx.propagate();
+        }
+    }
+
+    /// Select the `ix`th matched item, and close the menu.
+    fn confirm_ix(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let Some((item_ix, _)) = self.matches.get(ix) else {
+            return;
+        };
+
+        self.selected_ix = Some(*item_ix);
+        cx.emit(SelectEvent::Change(self.items[*item_ix].value.clone()));
+        self.close(cx);
+    }
+
+    fn on_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let keystroke = &event.keystroke;
+        if !self.searchable || keystroke.modifiers.control || keystroke.modifiers.platform {
+            return;
+        }
+
+        if keystroke.key == "backspace" {
+            if self.open && self.query.pop().is_some() {
+                self.filter();
+                cx.notify();
+            }
+            return cx.stop_propagation();
+        }
+
+        let Some(text) = keystroke
+            .key_char
+            .as_ref()
+            .filter(|text| !text.chars().any(char::is_control))
+        else {
+            return;
+        };
+        if !self.open {
+            self.open(cx);
+        }
+        self.query.push_str(text);
+        self.filter();
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn item_height(&self) -> Pixels {
+        match self.size {
+            Size::Large => px(36.),
+            Size::Small | Size::XSmall => px(26.),
+            _ => px(30.),
+        }
+    }
+
+    fn render_search(&self, cx: &Context<Self>) -> impl IntoElement {
+        h_flex()
+            .h(self.item_height())
+            .mb_1()
+            .px_2()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                Icon::new(IconName::Search)
+                    .size_4()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .map(|this| {
+                if self.query.is_empty() {
+                    this.text_color(cx.theme().muted_foreground)
+                        .child("Search...")
+                } else {
+                    this.child(self.query.clone())
+                }
+            })
+    }
+}
+
+/// Return the items matching the query, `(item_ix, match)`, the best match first. All the
+/// items are returned in order if the query is empty.
+fn filter_items(items: &[SelectItem], query: &str) -> Vec<(usize, FuzzyMatch)> {
+    let mut matches = items
+        .iter()
+        .enumerate()
+        .filter_map(|(ix, item)| Some((ix, fuzzy_match(query, &item.label)?)))
+        .collect::<Vec<_>>();
+    if !query.is_empty() {
+        matches.sort_by(|(a_ix, a), (b_ix, b)| b.score.cmp(&a.score).then(a_ix.cmp(b_ix)));
+    }
+    matches
+}
+
+impl Render for SelectState {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let item_height = self.item_height();
+        let width = self.bounds.get().size.width;
+        let len = self.matches.len();
+        let size = self.size;
+
+        let list = uniform_list(
+            "select-items",
+            len,
+            cx.processor(move |this, range: Range<usize>, window, cx| {
+                let text_style = window.text_style();
+                let highlight = HighlightStyle {
+                    font_weight: Some(FontWeight::BOLD),
+                    ..Default::default()
+                };
+
+                range
+                    .filter_map(|ix| {
+                        let (item_ix, fuzzy_match) = this.matches.get(ix)?;
+                        let item = &this.items[*item_ix];
+                        let highlighted = ix == this.highlighted_ix;
+                        let selected = this.selected_ix == Some(*item_ix);
+                        let highlights = fuzzy_match
+                            .positions
+                            .iter()
+                            .map(|pos| {
+                                let len =
+                                    item.label[*pos..].chars().next().map_or(0, char::len_utf8);
+                                (*pos..*pos + len, highlight)
+                            })
+                            .collect::<Vec<_>>();
+
+                        Some(
+                            h_flex()
+                                .id(ix)
+                                .h(item_height)
+                                .list_px(size)
+                                .gap_2()
+                                .rounded(px(4.))
+                                .when(highlighted, |this| {
+                                    this.bg(cx.theme().accent)
+                                        .text_color(cx.theme().accent_foreground)
+                                })
+                                .child(
+                                    div().flex_1().overflow_hidden().whitespace_nowrap().child(
+                                        StyledText::new(item.label.clone())
+                                            .with_default_highlights(&text_style, highlights),
+                                    ),
+                                )
+                                .when_some(item.description.clone(), |this, description| {
+                                    this.child(
+                                        div()
+                                            .flex_shrink_0()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .child(description),
+                                    )
+                                })
+                                .when(selected, |this| {
+                                    this.child(Icon::new(IconName::Check).size_4())
+                                })
+                                .on_hover(cx.listener(move |this, hovered: &bool, _, cx| {
+                                    if *hovered {
+                                        this.highlighted_ix = ix;
+                                        cx.notify();
+                                    }
+                                }))
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    this.confirm_ix(ix, cx);
+                                })),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            }),
+        )
+        .track_scroll(self.scroll_handle.clone())
+        .h(item_height * len.min(MAX_VISIBLE_ITEMS) as f32);
+
+        v_flex()
+            .min_w(px(160.))
+            .when(width > px(0.), |this| this.w(width))
+            .input_text_size(size)
+            .when(self.searchable, |this| this.child(self.render_search(cx)))
+            .map(|this| {
+                if len == 0 {
+                    this.child(
+                        div()
+                            .h(item_height)
+                            .px_2()
+                            .flex()
+                            .items_center()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("No results"),
+                    )
+                } else {
+                    this.child(list)
+                }
+            })
+    }
+}
+
+/// A dropdown bound to the [`SelectState`].
+#[derive(IntoElement)]
+pub struct Select {
+    state: Entity<SelectState>,
+    size: Size,
+    placement: Placement,
+    disabled: bool,
+}
+
+impl Sizable for Select {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+impl Select {
+    pub fn new(state: &Entity<SelectState>) -> Self {
+        Self {
+            state: state.clone(),
+            size: Size::default(),
+            placement: Placement::Bottom,
+            disabled: false,
+        }
+    }
+
+    /// Set the side of the trigger to show the menu, default is [`Placement::Bottom`].
+    pub fn placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl RenderOnce for Select {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        self.state.update(cx, |state, _| state.size = self.size);
+
+        let state = self.state.read(cx);
+        let focused = state.focus_handle.is_focused(window);
+        let open = state.open && !self.disabled;
+        let bounds = state.bounds.clone();
+        let (label, has_value) = match state.selected_item() {
+            Some(item) => (item.label.clone(), true),
+            None => (state.placeholder.clone(), false),
+        };
+
+        let trigger = h_flex()
+            .id("trigger")
+            .relative()
+            .w_full()
+            .gap_1()
+            .input_px(self.size)
+            .input_h(self.size)
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().input)
+            .map(|this| {
+                if self.disabled {
+                    this.bg(cx.theme().muted)
+                        .text_color(cx.theme().muted_foreground)
+                } else {
+                    this.bg(cx.theme().background)
+                }
+            })
+            .when(cx.theme().shadow, |this| this.shadow_sm())
+            .when(focused, |this| this.focused_border(cx))
+            .child(
+                canvas(move |b, _, _| bounds.set(b), |_, _, _, _| {})
+                    .absolute()
+                    .size_full(),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .when(!has_value, |this| {
+                        this.text_color(cx.theme().muted_foreground)
+                    })
+                    .child(label),
+            )
+            .child(
+                Icon::new(IconName::ChevronsUpDown)
+                    .size_4()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .when(!self.disabled, |this| {
+                this.on_click(window.listener_for(&self.state, SelectState::toggle))
+            });
+
+        div()
+            .id(("select", self.state.entity_id()))
+            .w_full()
+            .key_context(CONTEXT)
+            .when(!self.disabled, |this| {
+                this.track_focus(&state.focus_handle)
+                    .on_action(window.listener_for(&self.state, SelectState::select_prev))
+                    .on_action(window.listener_for(&self.state, SelectState::select_next))
+                    .on_action(window.listener_for(&self.state, SelectState::confirm))
+                    .on_action(window.listener_for(&self.state, SelectState::cancel))
+                    .on_key_down(window.listener_for(&self.state, SelectState::on_key_down))
+            })
+            .child(
+                Popover::new("popover")
+                    .trigger(trigger)
+                    .open(open)
+                    .placement(self.placement)
+                    .on_dismiss({
+                        let state = self.state.clone();
+                        move |_, cx| state.update(cx, |state, cx| state.close(cx))
+                    })
+                    .p_1()
+                    .child(self.state.clone()),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SelectItem, filter_items};
+
+    #[test]
+    fn test_filter_items() {
+        let items =
+            ["gpt-5", "gpt-5-mini", "claude-sonnet-4-5", "gemini-2.5-pro"].map(SelectItem::from);
+        let filter = |query: &str| {
+            filter_items(&items, query)
+                .into_iter()
+                .map(|(ix, _)| items[ix].value.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(filter("").len(), 4);
+        assert_eq!(filter("son"), vec!["claude-sonnet-4-5"]);
+        assert_eq!(filter("gm")[0], "gpt-5-mini");
+        assert!(filter("xyz").is_empty());
+    }
+}
@@ -1,6 +1,9 @@
-use crate::{h_flex, v_flex, ActiveTheme as _, Collapsible, Icon, IconName, StyledExt};
+use crate::{
+    context_menu::ContextMenuExt as _, h_flex, popup_menu::PopupMenu, v_flex, ActiveTheme as _,
+    Collapsible, Icon, IconName, StyledExt,
+};
 use gpui::{
-    div, percentage, prelude::FluentBuilder as _, AnyElement, App, ClickEvent, ElementId,
+    div, percentage, prelude::FluentBuilder as _, AnyElement, App, ClickEvent, Context, ElementId,
     InteractiveElement as _, IntoElement, ParentElement as _, RenderOnce, SharedString,
     StatefulInteractiveElement as _, Styled as _, Window,
 };
@@ -65,6 +68,7 @@ pub struct SidebarMenuItem {
     collapsed: bool,
     children: Vec<Self>,
     suffix: Option<AnyElement>,
+    context_menu: Option<Rc<dyn Fn(PopupMenu, &mut Window, &mut Context<PopupMenu>) -> PopupMenu>>,
 }
 
 impl SidebarMenuItem {
@@ -79,6 +83,7 @@ impl SidebarMenuItem {
             collapsed: false,
             children: Vec::new(),
             suffix: None,
+            context_menu: None,
         }
     }
 
@@ -126,6 +131,15 @@ impl SidebarMenuItem {
         self
     }
 
+    /// Show the popup menu built by `f` on right click.
+    pub fn context_menu(
+        mut self,
+        f: impl Fn(PopupMenu, &mut Window, &mut Context<PopupMenu>) -> PopupMenu + 'static,
+    ) -> Self {
+        self.context_menu = Some(Rc::new(f));
+        self
+    }
+
     fn is_submenu(&self) -> bool {
         self.children.len() > 0
     }
@@ -146,67 +160,72 @@ impl RenderOnce for SidebarMenuItem {
         let is_active = self.active;
         let is_open = self.is_open();
         let is_submenu = self.is_submenu();
-
-        div()
-            .id(self.id.clone())
-            .w_full()
-            .child(
-                h_flex()
-                    .size_full()
-                    .id("item")
-                    .overflow_x_hidden()
-                    .flex_shrink_0()
-                    .p_2()
-                    .gap_x_2()
-                    .rounded(cx.theme().radius)
-                    .text_sm()
-                    .hover(|this| {
-                        if is_active {
-                            return this;
-                        }
-
-                        this.bg(cx.theme().accent)
-                            .text_color(cx.theme().sidebar_accent_foreground)
-                    })
-                    .when(is_active && !is_submenu, |this| {
-                        this.font_medium()
-                            .bg(cx.theme().sidebar_accent)
-                            .text_color(cx.theme().sidebar_accent_foreground)
-                    })
-                    .when_some(self.icon.clone(), |this, icon| this.child(icon))
-                    .when(is_collapsed, |this| {
-                        this.justify_center().when(is_active, |this| {
-                            this.bg(cx.theme().sidebar_accent)
-                                .text_color(cx.theme().sidebar_accent_foreground)
-                        })
-                    })
-                    .when(!is_collapsed, |this| {
-                        this.h_7()
+        let context_menu = self.context_menu;
+
+        let item = h_flex()
+            .size_full()
+            .id("item")
+            .overflow_x_hidden()
+            .flex_shrink_0()
+            .p_2()
+            .gap_x_2()
+            .rounded(cx.theme().radius)
+            .text_sm()
+            .hover(|this| {
+                if is_active {
+                    return this;
+                }
+
+                this.bg(cx.theme().accent)
+                    .text_color(cx.theme().sidebar_accent_foreground)
+            })
+            .when(is_active && !is_submenu, |this| {
+                this.font_medium()
+                    .bg(cx.theme().sidebar_accent)
+                    .text_color(cx.theme().sidebar_accent_foreground)
+            })
+            .when_some(self.icon.clone(), |this, icon| this.child(icon))
+            .when(is_collapsed, |this| {
+                this.justify_center().when(is_active, |this| {
+                    this.bg(cx.theme().sidebar_accent)
+                        .text_color(cx.theme().sidebar_accent_foreground)
+                })
+            })
+            .when(!is_collapsed, |this| {
+                this.h_7()
+                    .child(
+                        h_flex()
+                            .flex_1()
+                            .gap_x_2()
+                            .justify_between()
+                            .overflow_x_hidden()
                             .child(
                                 h_flex()
                                     .flex_1()
-                                    .gap_x_2()
-                                    .justify_between()
                                     .overflow_x_hidden()
-                                    .child(
-                                        h_flex()
-                                            .flex_1()
-                                            .overflow_x_hidden()
-                                            .child(self.label.clone()),
-                                    )
-                                    .when_some(self.suffix, |this, suffix| this.child(suffix)),
+                                    .child(self.label.clone()),
                             )
-                            .when(is_submenu, |this| {
-                                this.child(
-                                    Icon::new(IconName::ChevronRight)
-                                        .size_4()
-                                        .when(is_open, |this| this.rotate(percentage(90. / 360.))),
-                                )
-                            })
+                            .when_some(self.suffix, |this, suffix| this.child(suffix)),
+                    )
+                    .when(is_submenu, |this| {
+                        this.child(
+                            Icon::new(IconName::ChevronRight)
+                                .size_4()
+                                .when(is_open, |this| this.rotate(percentage(90. / 360.))),
+                        )
                     })
-                    .on_click(move |ev, window, cx| handler(ev, window, cx)),
-            )
-            .when(is_submenu && is_open && !is_collapsed, |this| {
+            })
+            .on_click(move |ev, window, cx| handler(ev, window, cx));
+        let item = match context_menu {
+            Some(f) => item
+                .context_menu(move |menu, window, cx| f(menu, window, cx))
+                .into_any_element(),
+            None => item.into_any_element(),
+        };
+
+        div().id(self.id.clone()).w_full().child(item).when(
+            is_submenu && is_open && !is_collapsed,
+            |this| {
                 this.child(
                     v_flex()
                         .id("submenu")
@@ -223,6 +242,7 @@ impl RenderOnce for SidebarMenuItem {
                                 .map(|(ix, item)| item.id(ix)),
                         ),
                 )
-            })
+            },
+        )
     }
 }
\ No newline at end of file
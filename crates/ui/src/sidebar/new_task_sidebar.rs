@@ -1,5 +1,5 @@
 use crate::{
-    Collapsible, Icon, IconName,
+    Collapsible, Icon, IconName, SidebarGroup, SidebarMenu,
     button::{Button, ButtonVariants},
     v_flex,
 };
@@ -13,6 +13,7 @@ use std::rc::Rc;
 pub struct NewTaskSidebar {
     collapsed: bool,
     on_new_task: Option<Rc<dyn Fn(&ClickEvent, &mut Window, &mut App)>>,
+    tasks: Option<SidebarMenu>,
 }
 
 impl NewTaskSidebar {
@@ -20,6 +21,7 @@ impl NewTaskSidebar {
         Self {
             collapsed: false,
             on_new_task: None,
+            tasks: None,
         }
     }
 
@@ -30,6 +32,12 @@ impl NewTaskSidebar {
         self.on_new_task = Some(Rc::new(handler));
         self
     }
+
+    /// Set the menu of the tasks, shown below the "New Task" button.
+    pub fn tasks(mut self, tasks: SidebarMenu) -> Self {
+        self.tasks = Some(tasks);
+        self
+    }
 }
 
 impl Collapsible for NewTaskSidebar {
@@ -46,19 +54,29 @@ impl Collapsible for NewTaskSidebar {
 impl RenderOnce for NewTaskSidebar {
     fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
         let on_new_task = self.on_new_task.clone();
-        v_flex().gap_2().p_2().child(
-            div().w_full().child(
-                Button::new("new-task")
-                    .primary()
-                    .icon(Icon::new(IconName::Plus).size_4()) // Bigger icon
-                    .when(!self.collapsed, |btn| btn.label("New Task")) // Removed .small() for bigger button
-                    .when(self.collapsed, |btn| btn.compact().justify_center()) // Removed .small()
-                    .when_some(on_new_task, |btn, handler| {
-                        btn.on_click(cx, move |ev, window, cx| {
-                            handler(ev, window, cx);
-                        })
-                    }),
-            ),
-        )
+        v_flex()
+            .gap_2()
+            .p_2()
+            .child(
+                div().w_full().child(
+                    Button::new("new-task")
+                        .primary()
+                        .icon(Icon::new(IconName::Plus).size_4()) // Bigger icon
+                        .when(!self.collapsed, |btn| btn.label("New Task")) // Removed .small() for bigger button
+                        .when(self.collapsed, |btn| btn.compact().justify_center()) // Removed .small()
+                        .when_some(on_new_task, |btn, handler| {
+                            btn.on_click(cx, move |ev, window, cx| {
+                                handler(ev, window, cx);
+                            })
+                        }),
+                ),
+            )
+            .when_some(self.tasks, |this, tasks| {
+                this.child(
+                    SidebarGroup::new("Tasks")
+                        .child(tasks)
+                        .collapsed(self.collapsed),
+                )
+            })
     }
 }
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use gpui::{
+    Animation, AnimationExt as _, App, Div, IntoElement, RenderOnce, StyleRefinement, Styled,
+    Window, bounce, div, ease_in_out,
+};
+
+use crate::ActiveTheme as _;
+
+/// A placeholder of the content being loaded, the size is set by the [`Styled`] methods.
+///
+/// ```ignore
+/// v_flex()
+///     .gap_2()
+///     .child(Skeleton::new().w(px(240.)).h_4())
+///     .child(Skeleton::new().w_full().h_4())
+/// ```
+#[derive(IntoElement)]
+pub struct Skeleton {
+    base: Div,
+    secondary: bool,
+}
+
+impl Skeleton {
+    pub fn new() -> Self {
+        Self {
+            base: div().w_full().h_4(),
+            secondary: false,
+        }
+    }
+
+    /// Use a lighter color, e.g.: for the less important lines.
+    pub fn secondary(mut self, secondary: bool) -> Self {
+        self.secondary = secondary;
+        self
+    }
+}
+
+impl Default for Skeleton {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Styled for Skeleton {
+    fn style(&mut self) -> &mut StyleRefinement {
+        self.base.style()
+    }
+}
+
+impl RenderOnce for Skeleton {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let color = if self.secondary {
+            cx.theme().skeleton.opacity(0.5)
+        } else {
+            cx.theme().skeleton
+        };
+
+        self.base
+            .rounded(cx.theme().radius)
+            .bg(color)
+            .with_animation(
+                "skeleton",
+                Animation::new(Duration::from_secs(2))
+                    .repeat()
+                    .with_easing(bounce(ease_in_out)),
+                |this, delta| this.opacity(pulse_opacity(delta)),
+            )
+    }
+}
+
+/// Return the opacity of the pulse, from fully opaque to half transparent.
+fn pulse_opacity(delta: f32) -> f32 {
+    1. - delta * 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{Styled as _, px};
+
+    use super::{Skeleton, pulse_opacity};
+
+    #[test]
+    fn test_size() {
+        let mut skeleton = Skeleton::new();
+        assert_eq!(skeleton.style().size.height, Some(gpui::rems(1.).into()));
+        assert!(!skeleton.secondary);
+
+        let mut skeleton = skeleton.w(px(240.)).h(px(12.)).secondary(true);
+        assert_eq!(skeleton.style().size.width, Some(px(240.).into()));
+        assert_eq!(skeleton.style().size.height, Some(px(12.).into()));
+        assert!(skeleton.secondary);
+    }
+
+    #[test]
+    fn test_pulse_opacity() {
+        assert_eq!(pulse_opacity(0.), 1.);
+        assert_eq!(pulse_opacity(0.5), 0.75);
+        assert_eq!(pulse_opacity(1.), 0.5);
+    }
+}
@@ -0,0 +1,328 @@
+use std::{cell::Cell, rc::Rc};
+
+use gpui::{
+    App, AppContext as _, Bounds, ClickEvent, Context, DragMoveEvent, ElementId, Empty, Entity,
+    EntityId, EventEmitter, InteractiveElement as _, IntoElement, KeyBinding, MouseButton,
+    MouseDownEvent, ParentElement as _, Pixels, Point, Render, RenderOnce,
+    StatefulInteractiveElement as _, Styled as _, Window, actions, canvas, div,
+    prelude::FluentBuilder as _, px, relative,
+};
+
+use crate::{ActiveTheme as _, Disableable, Sizable, Size, focus, h_flex};
+
+const CONTEXT: &str = "Slider";
+
+actions!(slider, [Increment, Decrement, MoveToMin, MoveToMax]);
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("left", Decrement, Some(CONTEXT)),
+        KeyBinding::new("down", Decrement, Some(CONTEXT)),
+        KeyBinding::new("right", Increment, Some(CONTEXT)),
+        KeyBinding::new("up", Increment, Some(CONTEXT)),
+        KeyBinding::new("home", MoveToMin, Some(CONTEXT)),
+        KeyBinding::new("end", MoveToMax, Some(CONTEXT)),
+    ]);
+}
+
+pub enum SliderEvent {
+    /// The value is changed by the mouse or the keyboard.
+    Change(f32),
+}
+
+/// The thumb being dragged, the id is the entity of the [`SliderState`].
+#[derive(Clone)]
+struct DragThumb(EntityId);
+
+impl Render for DragThumb {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        Empty
+    }
+}
+
+/// The state of a [`Slider`], a value in a range, rounded to the step.
+pub struct SliderState {
+    min: f32,
+    max: f32,
+    step: f32,
+    value: f32,
+    /// The bounds of the bar, to convert the mouse position to a value.
+    bounds: Rc<Cell<Bounds<Pixels>>>,
+}
+
+impl EventEmitter<SliderEvent> for SliderState {}
+
+impl Default for SliderState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SliderState {
+    pub fn new() -> Self {
+        Self {
+            min: 0.,
+            max: 100.,
+            step: 1.,
+            value: 0.,
+            bounds: Rc::new(Cell::new(Bounds::default())),
+        }
+    }
+
+    pub fn min(mut self, min: f32) -> Self {
+        self.min = min;
+        self.value = snap(self.value, self.min, self.max, self.step);
+        self
+    }
+
+    pub fn max(mut self, max: f32) -> Self {
+        self.max = max;
+        self.value = snap(self.value, self.min, self.max, self.step);
+        self
+    }
+
+    /// The step of the value, e.g.: of the arrow keys, the value is not rounded if `0.`.
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = step;
+        self.value = snap(self.value, self.min, self.max, self.step);
+        self
+    }
+
+    pub fn default_value(mut self, value: f32) -> Self {
+        self.value = snap(value, self.min, self.max, self.step);
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Set the value without emitting [`SliderEvent::Change`].
+    pub fn set_value(&mut self, value: f32, cx: &mut Context<Self>) {
+        self.value = snap(value, self.min, self.max, self.step);
+        cx.notify();
+    }
+
+    /// The position of the value in the range, from `0.` to `1.`.
+    fn percentage(&self) -> f32 {
+        if self.max <= self.min {
+            return 0.;
+        }
+        (self.value - self.min) / (self.max - self.min)
+    }
+
+    fn update_value(&mut self, value: f32, cx: &mut Context<Self>) {
+        let value = snap(value, self.min, self.max, self.step);
+        if value == self.value {
+            return;
+        }
+        self.value = value;
+        cx.emit(SliderEvent::Change(value));
+        cx.notify();
+    }
+
+    fn update_position(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let bounds = self.bounds.get();
+        if bounds.size.width <= px(0.) {
+            return;
+        }
+        let percentage = ((position.x - bounds.left()) / bounds.size.width).clamp(0., 1.);
+        self.update_value(self.min + percentage * (self.max - self.min), cx);
+    }
+
+    /// The step of the keys, 1% of the range if the value is not rounded.
+    fn key_step(&self) -> f32 {
+        if self.step > 0. {
+            self.step
+        } else {
+            (self.max - self.min) / 100.
+        }
+    }
+
+    fn increment(&mut self, _: &Increment, _: &mut Window, cx: &mut Context<Self>) {
+        self.update_value(self.value + self.key_step(), cx);
+    }
+
+    fn decrement(&mut self, _: &Decrement, _: &mut Window, cx: &mut Context<Self>) {
+        self.update_value(self.value - self.key_step(), cx);
+    }
+
+    fn move_to_min(&mut self, _: &MoveToMin, _: &mut Window, cx: &mut Context<Self>) {
+        self.update_value(self.min, cx);
+    }
+
+    fn move_to_max(&mut self, _: &MoveToMax, _: &mut Window, cx: &mut Context<Self>) {
+        self.update_value(self.max, cx);
+    }
+
+    fn on_mouse_down(&mut self, event: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        self.update_position(event.position, cx);
+    }
+
+    fn on_drag_move(
+        &mut self,
+        event: &DragMoveEvent<DragThumb>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if event.drag(cx).0 != cx.entity_id() {
+            return;
+        }
+        self.update_position(event.event.position, cx);
+    }
+}
+
+/// Clamp the value to the range and round it to the nearest step from `min`.
+fn snap(value: f32, min: f32, max: f32, step: f32) -> f32 {
+    let value = value.clamp(min, max.max(min));
+    if step <= 0. {
+        return value;
+    }
+    let value = min + ((value - min) / step).round() * step;
+    // The last step may be shorter than the step.
+    value.min(max.max(min))
+}
+
+/// A slider bound to a [`SliderState`], registered in the focus registry by its id.
+///
+/// The arrow keys change the value by the step, `home` and `end` move it to the ends.
+///
+/// ```ignore
+/// let state = cx.new(|_| SliderState::new().min(0.).max(16.).default_value(8.));
+/// Slider::new("radius", &state)
+/// ```
+#[derive(IntoElement)]
+pub struct Slider {
+    id: ElementId,
+    state: Entity<SliderState>,
+    disabled: bool,
+    size: Size,
+}
+
+impl Slider {
+    pub fn new(id: impl Into<ElementId>, state: &Entity<SliderState>) -> Self {
+        Self {
+            id: id.into(),
+            state: state.clone(),
+            disabled: false,
+            size: Size::default(),
+        }
+    }
+}
+
+impl Disableable for Slider {
+    fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl Sizable for Slider {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+impl RenderOnce for Slider {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let focus_handle = focus::register_control(
+            cx,
+            self.id.clone(),
+            self.disabled,
+            None::<fn(&ClickEvent, &mut Window, &mut App)>,
+        );
+        let focused = focus_handle.is_focused(window);
+        let state = self.state.read(cx);
+        let percentage = state.percentage();
+        let bounds = state.bounds.clone();
+        let entity_id = self.state.entity_id();
+        let thumb_size = match self.size {
+            Size::XSmall | Size::Small => px(12.),
+            _ => px(16.),
+        };
+
+        h_flex()
+            .id(self.id)
+            .key_context(CONTEXT)
+            .track_focus(&focus_handle)
+            .relative()
+            .w_full()
+            .h(thumb_size)
+            .when(self.disabled, |this| this.opacity(0.5))
+            .when(!self.disabled, |this| {
+                this.cursor_pointer()
+                    .on_action(window.listener_for(&self.state, SliderState::increment))
+                    .on_action(window.listener_for(&self.state, SliderState::decrement))
+                    .on_action(window.listener_for(&self.state, SliderState::move_to_min))
+                    .on_action(window.listener_for(&self.state, SliderState::move_to_max))
+                    .on_mouse_down(MouseButton::Left, {
+                        let focus_handle = focus_handle.clone();
+                        move |_, window, _| focus_handle.focus(window)
+                    })
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        window.listener_for(&self.state, SliderState::on_mouse_down),
+                    )
+                    .on_drag_move(window.listener_for(&self.state, SliderState::on_drag_move))
+            })
+            .child(
+                canvas(move |b, _, _| bounds.set(b), |_, _, _, _| {})
+                    .absolute()
+                    .size_full(),
+            )
+            .child(
+                div()
+                    .w_full()
+                    .h_1p5()
+                    .rounded_full()
+                    .bg(cx.theme().slider_bar.opacity(0.2))
+                    .child(
+                        div()
+                            .h_full()
+                            .w(relative(percentage))
+                            .rounded_full()
+                            .bg(cx.theme().slider_bar),
+                    ),
+            )
+            .child(
+                div()
+                    .id("thumb")
+                    .absolute()
+                    .top_0()
+                    .left(relative(percentage))
+                    .ml(-(thumb_size / 2.))
+                    .size(thumb_size)
+                    .rounded_full()
+                    .border_2()
+                    .border_color(if focused {
+                        cx.theme().ring
+                    } else {
+                        cx.theme().slider_bar
+                    })
+                    .bg(cx.theme().slider_thumb)
+                    .shadow_sm()
+                    .when(!self.disabled, |this| {
+                        this.on_drag(DragThumb(entity_id), |drag, _, _, cx| {
+                            cx.new(|_| drag.clone())
+                        })
+                    }),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::snap;
+
+    #[test]
+    fn test_snap() {
+        assert_eq!(snap(5.4, 0., 10., 1.), 5.);
+        assert_eq!(snap(5.6, 0., 10., 1.), 6.);
+        assert_eq!(snap(-3., 0., 10., 1.), 0.);
+        assert_eq!(snap(12., 0., 10., 1.), 10.);
+        assert_eq!(snap(7., 1., 10., 4.), 9.);
+        assert_eq!(snap(10., 1., 10., 4.), 9.);
+        assert_eq!(snap(0.37, 0., 1., 0.), 0.37);
+    }
+}
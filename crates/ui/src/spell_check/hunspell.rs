@@ -0,0 +1,527 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use anyhow::{Context as _, Result};
+
+use super::SpellChecker;
+
+/// The maximum number of the suggestions of a misspelled word.
+const MAX_SUGGESTIONS: usize = 5;
+/// The chars to try in the suggestions if the `.aff` file has no `TRY`.
+const DEFAULT_TRY: &str = "esianrtolcdugmphbyfvkwzxjq'";
+
+/// The format of the flags, see the `FLAG` option of the `.aff` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagFormat {
+    /// A char is a flag, the default.
+    Char,
+    /// Two chars are a flag.
+    Long,
+    /// The flags are the numbers separated by `,`.
+    Num,
+}
+
+impl FlagFormat {
+    fn parse(&self, flags: &str) -> Vec<u32> {
+        match self {
+            Self::Char => flags.chars().map(|c| c as u32).collect(),
+            Self::Long => {
+                let chars = flags.chars().collect::<Vec<_>>();
+                chars
+                    .chunks(2)
+                    .map(|pair| pair.iter().fold(0, |flag, c| flag << 16 | *c as u32))
+                    .collect()
+            }
+            Self::Num => flags
+                .split(',')
+                .filter_map(|flag| flag.trim().parse().ok())
+                .collect(),
+        }
+    }
+}
+
+/// A char of the condition of an affix, e.g.: `[^aeiou]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Any,
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+    Char(char),
+}
+
+impl Condition {
+    fn parse_all(condition: &str) -> Vec<Self> {
+        let mut conditions = vec![];
+        let mut chars = condition.chars();
+        while let Some(c) = chars.next() {
+            let condition = match c {
+                '.' => Self::Any,
+                '[' => {
+                    let mut set = chars.by_ref().take_while(|c| *c != ']').collect::<Vec<_>>();
+                    if set.first() == Some(&'^') {
+                        set.remove(0);
+                        Self::NoneOf(set)
+                    } else {
+                        Self::OneOf(set)
+                    }
+                }
+                c => Self::Char(c),
+            };
+            conditions.push(condition);
+        }
+        conditions
+    }
+
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Self::Any => true,
+            Self::OneOf(set) => set.contains(&c),
+            Self::NoneOf(set) => !set.contains(&c),
+            Self::Char(expected) => *expected == c,
+        }
+    }
+}
+
+/// A prefix or suffix rule, e.g.: `SFX D y ied [^aeiou]y`.
+#[derive(Debug, Clone)]
+struct Affix {
+    flag: u32,
+    cross_product: bool,
+    strip: String,
+    add: String,
+    conditions: Vec<Condition>,
+}
+
+impl Affix {
+    fn apply_suffix(&self, word: &str) -> Option<String> {
+        let stem = word.strip_suffix(self.strip.as_str())?;
+        let chars = word.chars().rev().collect::<Vec<_>>();
+        if chars.len() < self.conditions.len()
+            || !self
+                .conditions
+                .iter()
+                .rev()
+                .zip(&chars)
+                .all(|(condition, c)| condition.matches(*c))
+        {
+            return None;
+        }
+        Some(format!("{}{}", stem, self.add))
+    }
+
+    fn apply_prefix(&self, word: &str) -> Option<String> {
+        let stem = word.strip_prefix(self.strip.as_str())?;
+        let chars = word.chars().collect::<Vec<_>>();
+        if chars.len() < self.conditions.len()
+            || !self
+                .conditions
+                .iter()
+                .zip(&chars)
+                .all(|(condition, c)| condition.matches(*c))
+        {
+            return None;
+        }
+        Some(format!("{}{}", self.add, stem))
+    }
+}
+
+/// The options parsed from the `.aff` file.
+#[derive(Debug)]
+struct AffixFile {
+    flag_format: FlagFormat,
+    try_chars: Vec<char>,
+    prefixes: Vec<Affix>,
+    suffixes: Vec<Affix>,
+    /// The `REP` pairs, the common misspellings and their corrections.
+    replacements: Vec<(String, String)>,
+    need_affix: Option<u32>,
+    forbidden: Option<u32>,
+}
+
+impl AffixFile {
+    fn parse(content: &str) -> Self {
+        let mut flag_format = FlagFormat::Char;
+        let mut try_chars = None;
+        let mut prefixes = vec![];
+        let mut suffixes = vec![];
+        let mut replacements = vec![];
+        let mut need_affix = None;
+        let mut forbidden = None;
+
+        // The flags of `NEEDAFFIX` may be parsed before `FLAG`, parse them at the end.
+        let mut need_affix_flag = None;
+        let mut forbidden_flag = None;
+        let mut affix_lines = vec![];
+
+        for line in content.lines() {
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            match parts.as_slice() {
+                ["FLAG", "long", ..] => flag_format = FlagFormat::Long,
+                ["FLAG", "num", ..] => flag_format = FlagFormat::Num,
+                ["TRY", chars, ..] => try_chars = Some(chars.to_lowercase().chars().collect()),
+                ["NEEDAFFIX", flag, ..] => need_affix_flag = Some(flag.to_string()),
+                ["FORBIDDENWORD", flag, ..] => forbidden_flag = Some(flag.to_string()),
+                ["REP", from, to, ..] => {
+                    replacements.push((from.replace('_', " "), to.replace('_', " ")))
+                }
+                [kind @ ("PFX" | "SFX"), ..] => affix_lines.push((*kind == "PFX", parts)),
+                _ => {}
+            }
+        }
+
+        let parse_flag = |flag: &str| flag_format.parse(flag).first().copied();
+        if let Some(flag) = need_affix_flag {
+            need_affix = parse_flag(&flag);
+        }
+        if let Some(flag) = forbidden_flag {
+            forbidden = parse_flag(&flag);
+        }
+
+        // The header is `PFX flag cross_product count`, the rules are
+        // `PFX flag strip add [condition]`.
+        let mut cross_products = vec![];
+        for (is_prefix, parts) in affix_lines {
+            let Some(flag) = parts.get(1).and_then(|flag| parse_flag(flag)) else {
+                continue;
+            };
+            if parts.len() == 4 && matches!(parts[2], "Y" | "N") {
+                cross_products.push((flag, parts[2] == "Y"));
+                continue;
+            }
+            if parts.len() < 4 {
+                continue;
+            }
+
+            let strip = match parts[2] {
+                "0" => String::new(),
+                strip => strip.to_string(),
+            };
+            // The continuation flags after `/` are not supported.
+            let add = match parts[3].split('/').next().unwrap_or_default() {
+                "0" => String::new(),
+                add => add.to_string(),
+            };
+            let affix = Affix {
+                flag,
+                cross_product: cross_products
+                    .iter()
+                    .rev()
+                    .find(|(f, _)| *f == flag)
+                    .is_some_and(|(_, cross_product)| *cross_product),
+                strip,
+                add,
+                conditions: Condition::parse_all(parts.get(4).copied().unwrap_or(".")),
+            };
+            if is_prefix {
+                prefixes.push(affix);
+            } else {
+                suffixes.push(affix);
+            }
+        }
+
+        Self {
+            flag_format,
+            try_chars: try_chars.unwrap_or_else(|| DEFAULT_TRY.chars().collect()),
+            prefixes,
+            suffixes,
+            replacements,
+            need_affix,
+            forbidden,
+        }
+    }
+}
+
+/// A spell checker of the [Hunspell](https://hunspell.github.io) format dictionary, that is a
+/// `.dic` file of the words with the affix flags, and a `.aff` file of the affix rules.
+///
+/// The words are expanded with the prefixes and suffixes when loading, the compounding and the
+/// continuation flags are not supported.
+pub struct HunspellDictionary {
+    words: HashSet<String>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+}
+
+impl HunspellDictionary {
+    /// Load the dictionary from the `.dic` and `.aff` files of the path, e.g.:
+    /// `dictionaries/en_US.dic` or `dictionaries/en_US`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let aff = fs::read(path.with_extension("aff"))
+            .with_context(|| format!("failed to read {}", path.with_extension("aff").display()))?;
+        let dic = fs::read(path.with_extension("dic"))
+            .with_context(|| format!("failed to read {}", path.with_extension("dic").display()))?;
+
+        // The dictionaries are encoded in the `SET` encoding, only UTF-8 and ISO8859-1 are
+        // supported.
+        let latin1 = String::from_utf8_lossy(&aff)
+            .lines()
+            .any(|line| line.trim() == "SET ISO8859-1");
+        let decode = |bytes: &[u8]| match latin1 {
+            true => bytes.iter().map(|b| *b as char).collect::<String>(),
+            false => String::from_utf8_lossy(bytes).into_owned(),
+        };
+
+        Ok(Self::parse(&decode(&aff), &decode(&dic)))
+    }
+
+    /// Parse the dictionary from the content of the `.aff` and `.dic` files.
+    pub fn parse(aff: &str, dic: &str) -> Self {
+        let aff = AffixFile::parse(aff);
+        let mut words = HashSet::new();
+
+        // The first line is the approximate number of the words.
+        for line in dic
+            .lines()
+            .skip_while(|line| line.trim().parse::<usize>().is_ok())
+        {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = match entry.split_once('/') {
+                Some((word, flags)) => (word, aff.flag_format.parse(flags)),
+                None => (entry, vec![]),
+            };
+            if word.is_empty() || aff.forbidden.is_some_and(|flag| flags.contains(&flag)) {
+                continue;
+            }
+
+            if !aff.need_affix.is_some_and(|flag| flags.contains(&flag)) {
+                words.insert(word.to_string());
+            }
+
+            let suffixed = aff
+                .suffixes
+                .iter()
+                .filter(|suffix| flags.contains(&suffix.flag))
+                .filter_map(|suffix| Some((suffix, suffix.apply_suffix(word)?)))
+                .collect::<Vec<_>>();
+            for prefix in aff.prefixes.iter().filter(|p| flags.contains(&p.flag)) {
+                words.extend(prefix.apply_prefix(word));
+                if !prefix.cross_product {
+                    continue;
+                }
+                for (_, suffixed) in suffixed.iter().filter(|(s, _)| s.cross_product) {
+                    words.extend(prefix.apply_prefix(suffixed));
+                }
+            }
+            words.extend(suffixed.into_iter().map(|(_, word)| word));
+        }
+
+        Self {
+            words,
+            try_chars: aff.try_chars,
+            replacements: aff.replacements,
+        }
+    }
+
+    /// Check the word with the exact case.
+    fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    /// Return the candidates one edit away from the word.
+    fn edits(&self, word: &str) -> Vec<String> {
+        let chars = word.chars().collect::<Vec<_>>();
+        let mut edits = vec![];
+
+        for (from, to) in &self.replacements {
+            for (ix, _) in word.match_indices(from.as_str()) {
+                edits.push(format!("{}{}{}", &word[..ix], to, &word[ix + from.len()..]));
+            }
+        }
+        for ix in 0..chars.len().saturating_sub(1) {
+            let mut chars = chars.clone();
+            chars.swap(ix, ix + 1);
+            edits.push(chars.into_iter().collect());
+        }
+        for ix in 0..chars.len() {
+            for c in &self.try_chars {
+                let mut chars = chars.clone();
+                chars[ix] = *c;
+                edits.push(chars.into_iter().collect());
+            }
+        }
+        for ix in 0..chars.len() {
+            let mut chars = chars.clone();
+            chars.remove(ix);
+            edits.push(chars.into_iter().collect());
+        }
+        for ix in 0..=chars.len() {
+            for c in &self.try_chars {
+                let mut chars = chars.clone();
+                chars.insert(ix, *c);
+                edits.push(chars.into_iter().collect());
+            }
+        }
+        // Missing space, e.g.: `alot` -> `a lot`.
+        for (ix, _) in word.char_indices().skip(1) {
+            edits.push(format!("{} {}", &word[..ix], &word[ix..]));
+        }
+
+        edits
+    }
+}
+
+impl SpellChecker for HunspellDictionary {
+    fn check(&self, word: &str) -> bool {
+        if self.contains(word) {
+            return true;
+        }
+
+        // `Hello` at the start of the sentence, or `HELLO` for emphasis.
+        let lowercase = word.to_lowercase();
+        let mut chars = word.chars();
+        let capitalized =
+            chars.next().is_some_and(char::is_uppercase) && chars.all(|c| !c.is_uppercase());
+        let uppercase = word.chars().all(|c| !c.is_lowercase());
+        if capitalized {
+            return self.contains(&lowercase);
+        }
+        uppercase && (self.contains(&lowercase) || self.contains(&capitalize(&lowercase)))
+    }
+
+    fn suggest(&self, word: &str) -> Vec<String> {
+        let lowercase = word.to_lowercase();
+        let is_valid = |candidate: &str| {
+            candidate
+                .split(' ')
+                .all(|word| !word.is_empty() && self.check(word))
+        };
+
+        let mut seen = HashSet::new();
+        let mut suggestions = self
+            .edits(&lowercase)
+            .into_iter()
+            .filter(|candidate| *candidate != lowercase && is_valid(candidate))
+            .filter(|candidate| seen.insert(candidate.clone()))
+            .take(MAX_SUGGESTIONS)
+            .collect::<Vec<_>>();
+
+        // Try two edits only if no suggestions, that is much slower.
+        if suggestions.is_empty() {
+            suggestions = self
+                .edits(&lowercase)
+                .into_iter()
+                .filter(|candidate| !candidate.contains(' '))
+                .flat_map(|candidate| self.edits(&candidate))
+                .filter(|candidate| *candidate != lowercase && is_valid(candidate))
+                .filter(|candidate| seen.insert(candidate.clone()))
+                .take(MAX_SUGGESTIONS)
+                .collect();
+        }
+
+        // Keep the case of the word.
+        let uppercase = word.chars().count() > 1 && word.chars().all(|c| !c.is_lowercase());
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        suggestions
+            .into_iter()
+            .map(|suggestion| {
+                if uppercase {
+                    suggestion.to_uppercase()
+                } else if capitalized {
+                    capitalize(&suggestion)
+                } else {
+                    suggestion
+                }
+            })
+            .collect()
+    }
+
+    fn add_word(&mut self, word: &str) {
+        self.words.insert(word.to_string());
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HunspellDictionary, SpellChecker as _};
+
+    const AFF: &str = "SET UTF-8
+TRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'
+REP 1
+REP alot a_lot
+
+PFX A Y 1
+PFX A   0     re         .
+
+SFX D Y 4
+SFX D   0     d          e
+SFX D   y     ied        [^aeiou]y
+SFX D   0     ed         [^ey]
+SFX D   0     ed         [aeiou]y
+
+SFX S Y 3
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+SFX S   0     s          [^sxzhy]
+";
+
+    const DIC: &str = "6
+a
+lot
+create/ADS
+try/DS
+play/ADS
+London
+";
+
+    #[test]
+    fn test_affixes() {
+        let dictionary = HunspellDictionary::parse(AFF, DIC);
+        for word in [
+            "create",
+            "created",
+            "creates",
+            "recreate",
+            "recreated",
+            "try",
+            "tried",
+            "tries",
+            "played",
+            "replays",
+        ] {
+            assert!(dictionary.check(word), "{} should be correct", word);
+        }
+        for word in ["tryed", "playied", "retry", "creat"] {
+            assert!(!dictionary.check(word), "{} should be misspelled", word);
+        }
+    }
+
+    #[test]
+    fn test_case() {
+        let mut dictionary = HunspellDictionary::parse(AFF, DIC);
+        assert!(dictionary.check("Created"));
+        assert!(dictionary.check("CREATED"));
+        assert!(dictionary.check("London"));
+        assert!(dictionary.check("LONDON"));
+        assert!(!dictionary.check("london"));
+        assert!(!dictionary.check("cREATED"));
+
+        assert!(!dictionary.check("gpui"));
+        dictionary.add_word("gpui");
+        assert!(dictionary.check("gpui"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let dictionary = HunspellDictionary::parse(AFF, DIC);
+        assert_eq!(dictionary.suggest("tryed")[0], "tried");
+        assert_eq!(dictionary.suggest("Craete")[0], "Create");
+        assert!(dictionary.suggest("PLAYD").contains(&"PLAYED".to_string()));
+        assert_eq!(dictionary.suggest("alot")[0], "a lot");
+        assert!(
+            dictionary
+                .suggest("crated")
+                .contains(&"created".to_string())
+        );
+        assert!(dictionary.suggest("xyzzyq").is_empty());
+    }
+}
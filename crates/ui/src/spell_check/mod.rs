@@ -0,0 +1,304 @@
+mod hunspell;
+
+pub use hunspell::*;
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write as _,
+    ops::Range,
+    path::PathBuf,
+};
+
+use gpui::{App, Global};
+
+pub fn init(cx: &mut App) {
+    cx.set_global(SpellCheck::default());
+}
+
+/// The engine to check the words of the prose inputs, e.g.: [`HunspellDictionary`].
+pub trait SpellChecker {
+    /// Return true if the word is spelled correctly.
+    fn check(&self, word: &str) -> bool;
+
+    /// Return the corrections of the misspelled word, the best first.
+    fn suggest(&self, word: &str) -> Vec<String>;
+
+    /// Accept the word, e.g.: added by "Add to dictionary".
+    fn add_word(&mut self, word: &str);
+}
+
+/// The spell checker used by the `MultiLine` and `AutoGrow` inputs, nothing is checked until a
+/// checker is set.
+#[derive(Default)]
+pub struct SpellCheck {
+    checker: Option<Box<dyn SpellChecker>>,
+    /// The file of the words added by "Add to dictionary", one word per line.
+    personal_dictionary: Option<PathBuf>,
+    personal_words: Vec<String>,
+    /// Bumped when the accepted words are changed, to check the inputs again.
+    version: usize,
+    /// Bumped when a checker starts loading or is cleared, the checkers of the earlier loads
+    /// are ignored, see [`SpellCheck::start_loading`].
+    generation: usize,
+}
+
+impl Global for SpellCheck {}
+
+impl SpellCheck {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    pub fn global_mut(cx: &mut App) -> &mut Self {
+        cx.global_mut::<Self>()
+    }
+
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    pub fn set_checker(&mut self, mut checker: impl SpellChecker + 'static) {
+        for word in &self.personal_words {
+            checker.add_word(word);
+        }
+        self.checker = Some(Box::new(checker));
+        self.version += 1;
+    }
+
+    /// Start loading a checker in the background, returns the generation to set the loaded
+    /// checker by [`SpellCheck::set_loaded_checker`].
+    pub fn start_loading(&mut self) -> usize {
+        self.generation += 1;
+        self.generation
+    }
+
+    /// Set the checker loaded by the `generation` of [`SpellCheck::start_loading`], the
+    /// checker is ignored if another checker starts loading or is cleared after it.
+    ///
+    /// Returns true if the checker is set.
+    pub fn set_loaded_checker(
+        &mut self,
+        generation: usize,
+        checker: impl SpellChecker + 'static,
+    ) -> bool {
+        if generation != self.generation {
+            return false;
+        }
+
+        self.set_checker(checker);
+        true
+    }
+
+    /// Remove the checker, e.g.: if the spell check is disabled, nothing is checked.
+    pub fn clear_checker(&mut self) {
+        self.checker = None;
+        self.generation += 1;
+        self.version += 1;
+    }
+
+    /// Load the words from the personal dictionary, the words added later are appended to it.
+    pub fn set_personal_dictionary(&mut self, path: PathBuf) {
+        self.personal_words = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|word| !word.is_empty())
+            .map(ToString::to_string)
+            .collect();
+        if let Some(checker) = &mut self.checker {
+            for word in &self.personal_words {
+                checker.add_word(word);
+            }
+        }
+        self.personal_dictionary = Some(path);
+        self.version += 1;
+    }
+
+    /// Return the byte ranges of the misspelled words in the text.
+    pub fn check(&self, text: &str) -> Vec<Range<usize>> {
+        match &self.checker {
+            Some(checker) => misspelled_words(text, checker.as_ref()),
+            None => vec![],
+        }
+    }
+
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        match &self.checker {
+            Some(checker) => checker.suggest(word),
+            None => vec![],
+        }
+    }
+
+    /// Accept the word, and save it to the personal dictionary.
+    pub fn add_word(word: &str, cx: &mut App) {
+        let this = Self::global_mut(cx);
+        let Some(checker) = &mut this.checker else {
+            return;
+        };
+        checker.add_word(word);
+        this.personal_words.push(word.to_string());
+        this.version += 1;
+
+        if let Some(path) = &this.personal_dictionary {
+            let result = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", word));
+            if let Err(err) = result {
+                tracing::error!("failed to save {} to {}: {}", word, path.display(), err);
+            }
+        }
+        cx.refresh_windows();
+    }
+}
+
+/// Return the byte ranges of the misspelled words in the text.
+///
+/// The code is skipped, that is the text in backticks, and the words like paths, URLs,
+/// identifiers or acronyms, e.g.: `src/main.rs`, `snake_case`, `camelCase` or `HTTP`.
+pub fn misspelled_words(text: &str, checker: &dyn SpellChecker) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut in_code = false;
+
+    for token in text.split(char::is_whitespace) {
+        let offset = token.as_ptr() as usize - text.as_ptr() as usize;
+
+        // The code spans and blocks may contain the whitespaces.
+        let was_in_code = in_code;
+        let ticks = token.matches('`').count();
+        if ticks % 2 == 1 {
+            in_code = !in_code;
+        }
+        if was_in_code || ticks > 0 || is_code(token) {
+            continue;
+        }
+
+        for word in words(token) {
+            let word = word.start + offset..word.end + offset;
+            if should_check(&text[word.clone()]) && !checker.check(&text[word.clone()]) {
+                ranges.push(word);
+            }
+        }
+    }
+
+    ranges
+}
+
+fn is_code(token: &str) -> bool {
+    if token.contains(|c: char| c.is_ascii_digit() || "/\\@_=<>{}#$|~".contains(c)) {
+        return true;
+    }
+
+    // A file name or a method call, e.g.: `main.rs`, but not the end of a sentence.
+    token
+        .split('.')
+        .skip(1)
+        .any(|part| part.starts_with(char::is_alphanumeric))
+}
+
+/// Return the ranges of the words in the token, the apostrophes between the letters are kept,
+/// e.g.: `don't`.
+fn words(token: &str) -> Vec<Range<usize>> {
+    let mut words = vec![];
+    let mut start = None;
+    let mut chars = token.char_indices().peekable();
+
+    while let Some((ix, c)) = chars.next() {
+        let next_is_letter = chars.peek().is_some_and(|(_, c)| c.is_alphabetic());
+        let is_word_char =
+            c.is_alphabetic() || (matches!(c, '\'' | '’') && start.is_some() && next_is_letter);
+        match (is_word_char, start) {
+            (true, None) => start = Some(ix),
+            (false, Some(word_start)) => {
+                words.push(word_start..ix);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = start {
+        words.push(start..token.len());
+    }
+
+    words
+}
+
+/// Skip the single letters, the acronyms and the mixed case words, e.g.: `iPhone`.
+fn should_check(word: &str) -> bool {
+    let mut chars = word.chars();
+    let Some(_) = chars.next() else {
+        return false;
+    };
+    let rest = chars.collect::<String>();
+    !rest.is_empty() && !rest.contains(char::is_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SpellCheck, SpellChecker, misspelled_words};
+
+    struct Words(Vec<&'static str>);
+
+    impl SpellChecker for Words {
+        fn check(&self, word: &str) -> bool {
+            self.0.contains(&word.to_lowercase().as_str())
+        }
+
+        fn suggest(&self, _: &str) -> Vec<String> {
+            vec![]
+        }
+
+        fn add_word(&mut self, _: &str) {}
+    }
+
+    #[test]
+    fn test_misspelled_words() {
+        let checker = Words(vec!["fix", "the", "in", "don't", "use", "please", "a"]);
+        let misspelled = |text: &str| {
+            misspelled_words(text, &checker)
+                .into_iter()
+                .map(|range| text[range].to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(misspelled("Fix teh bug."), vec!["teh", "bug"]);
+        assert_eq!(
+            misspelled("Don't  use\nthe tset-suite, please!"),
+            vec!["tset", "suite"]
+        );
+        assert_eq!(
+            misspelled("fix `foo bar` in src/main.rs"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            misspelled("```rust\nlet x = wrnog;\n```\nfix teh"),
+            vec!["teh"]
+        );
+        assert_eq!(
+            misspelled("use HTTP, iPhone, snake_case, https://x.io, v2 and é"),
+            vec!["and"]
+        );
+
+        let text = "a naïve fix";
+        assert_eq!(misspelled_words(text, &checker), vec![2..8]);
+    }
+
+    #[test]
+    fn test_set_loaded_checker() {
+        let mut spell_check = SpellCheck::default();
+        let first = spell_check.start_loading();
+        let second = spell_check.start_loading();
+
+        // The checker of the earlier load is ignored.
+        assert!(spell_check.set_loaded_checker(second, Words(vec!["fix"])));
+        assert!(!spell_check.set_loaded_checker(first, Words(vec!["teh"])));
+        assert_eq!(spell_check.check("fix teh").len(), 1);
+
+        // The loading checker is ignored after the checker is cleared.
+        let third = spell_check.start_loading();
+        spell_check.clear_checker();
+        assert!(!spell_check.set_loaded_checker(third, Words(vec!["fix"])));
+        assert!(spell_check.check("fix teh").is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use std::rc::Rc;
+
+use gpui::{
+    App, ClickEvent, ElementId, InteractiveElement as _, IntoElement, ParentElement as _,
+    RenderOnce, SharedString, StatefulInteractiveElement as _, Styled as _, Window, div,
+    prelude::FluentBuilder as _, px,
+};
+
+use crate::{ActiveTheme as _, Disableable, Sizable, Size, focus, h_flex};
+
+type ToggleHandler = Rc<dyn Fn(&bool, &mut Window, &mut App)>;
+
+/// A toggle switch, registered in the focus registry by its id, `enter` and `space` toggle it.
+///
+/// ```ignore
+/// Switch::new("spell-check")
+///     .label("Spell check")
+///     .checked(enabled)
+///     .on_click(|checked, _, cx| ..)
+/// ```
+#[derive(IntoElement)]
+pub struct Switch {
+    id: ElementId,
+    checked: bool,
+    label: Option<SharedString>,
+    disabled: bool,
+    size: Size,
+    on_click: Option<ToggleHandler>,
+}
+
+impl Switch {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            checked: false,
+            label: None,
+            disabled: false,
+            size: Size::default(),
+            on_click: None,
+        }
+    }
+
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    pub fn label(mut self, label: impl Into<SharedString>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set the handler of the toggle, the argument is the new checked state.
+    pub fn on_click(mut self, handler: impl Fn(&bool, &mut Window, &mut App) + 'static) -> Self {
+        self.on_click = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl Disableable for Switch {
+    fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl Sizable for Switch {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+impl RenderOnce for Switch {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let checked = self.checked;
+        let toggle: Option<Rc<dyn Fn(&mut Window, &mut App)>> =
+            self.on_click.filter(|_| !self.disabled).map(|on_click| {
+                Rc::new(move |window: &mut Window, cx: &mut App| on_click(&!checked, window, cx))
+                    as _
+            });
+        let focus_handle = focus::register_control(
+            cx,
+            self.id.clone(),
+            self.disabled,
+            toggle.clone().map(|toggle| {
+                move |_: &ClickEvent, window: &mut Window, cx: &mut App| toggle(window, cx)
+            }),
+        );
+        let focused = focus_handle.is_focused(window);
+
+        let (width, thumb_size) = match self.size {
+            Size::XSmall | Size::Small => (px(28.), px(12.)),
+            _ => (px(36.), px(16.)),
+        };
+        let inset = px(2.);
+
+        h_flex()
+            .id(self.id)
+            .track_focus(&focus_handle)
+            .gap_2()
+            .when(self.disabled, |this| this.opacity(0.5))
+            .when(!self.disabled, |this| this.cursor_pointer())
+            .child(
+                div()
+                    .relative()
+                    .flex_shrink_0()
+                    .w(width)
+                    .h(thumb_size + inset * 2.)
+                    .rounded_full()
+                    .border_1()
+                    .border_color(cx.theme().transparent)
+                    .bg(if checked {
+                        cx.theme().primary
+                    } else {
+                        cx.theme().switch
+                    })
+                    .when(focused, |this| this.border_color(cx.theme().ring))
+                    .child(
+                        div()
+                            .absolute()
+                            .top(inset - px(1.))
+                            .left(if checked {
+                                width - thumb_size - inset - px(1.)
+                            } else {
+                                inset - px(1.)
+                            })
+                            .size(thumb_size)
+                            .rounded_full()
+                            .bg(cx.theme().background)
+                            .shadow_sm(),
+                    ),
+            )
+            .when_some(self.label, |this, label| {
+                this.child(div().text_sm().child(label))
+            })
+            .when_some(toggle, |this, toggle| {
+                this.on_click({
+                    let toggle = toggle.clone();
+                    move |_, window, cx| toggle(window, cx)
+                })
+                .on_key_down(move |event, window, cx| {
+                    if event.keystroke.key == "space" {
+                        cx.stop_propagation();
+                        toggle(window, cx);
+                    }
+                })
+            })
+    }
+}
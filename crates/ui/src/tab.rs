@@ -0,0 +1,314 @@
+use std::rc::Rc;
+
+use gpui::{
+    AnyElement, App, AppContext as _, ClickEvent, Context, ElementId, InteractiveElement as _,
+    IntoElement, MouseButton, ParentElement as _, Pixels, Render, RenderOnce, ScrollHandle,
+    SharedString, StatefulInteractiveElement as _, Styled as _, Window, div,
+    prelude::FluentBuilder as _, px,
+};
+
+use crate::{ActiveTheme as _, Icon, IconName, Sizable, Size, h_flex};
+
+type TabHandler = Rc<dyn Fn(&usize, &mut Window, &mut App)>;
+type ReorderHandler = Rc<dyn Fn(usize, usize, &mut Window, &mut App)>;
+
+/// A tab of the [`TabBar`].
+pub struct Tab {
+    label: SharedString,
+    icon: Option<Icon>,
+    closable: bool,
+}
+
+impl Tab {
+    pub fn new(label: impl Into<SharedString>) -> Self {
+        Self {
+            label: label.into(),
+            icon: None,
+            closable: false,
+        }
+    }
+
+    pub fn icon(mut self, icon: impl Into<Icon>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Show a close button, the tab is also closed by a middle click.
+    pub fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+}
+
+/// The tab being dragged, it's also the drag view.
+#[derive(Clone)]
+struct DragTab {
+    bar_id: ElementId,
+    ix: usize,
+    label: SharedString,
+}
+
+impl Render for DragTab {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_3()
+            .py_1()
+            .text_sm()
+            .bg(cx.theme().tab_active)
+            .text_color(cx.theme().tab_active_foreground)
+            .border_1()
+            .border_color(cx.theme().drag_border)
+            .rounded(cx.theme().radius)
+            .shadow_md()
+            .child(self.label.clone())
+    }
+}
+
+/// A row of tabs, scrolled horizontally if the tabs overflow.
+///
+/// ```ignore
+/// TabBar::new("files")
+///     .children(files.iter().map(|file| Tab::new(file.name()).closable(true)))
+///     .selected_index(active_ix)
+///     .on_click(|ix, _, cx| ..)
+///     .on_close(|ix, _, cx| ..)
+///     .on_reorder(|from, to, _, cx| ..)
+/// ```
+#[derive(IntoElement)]
+pub struct TabBar {
+    id: ElementId,
+    tabs: Vec<Tab>,
+    selected_ix: Option<usize>,
+    segmented: bool,
+    size: Size,
+    scroll_handle: Option<ScrollHandle>,
+    suffix: Option<AnyElement>,
+    on_click: Option<TabHandler>,
+    on_close: Option<TabHandler>,
+    on_reorder: Option<ReorderHandler>,
+}
+
+impl Sizable for TabBar {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+impl TabBar {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            tabs: vec![],
+            selected_ix: None,
+            segmented: false,
+            size: Size::default(),
+            scroll_handle: None,
+            suffix: None,
+            on_click: None,
+            on_close: None,
+            on_reorder: None,
+        }
+    }
+
+    pub fn child(mut self, tab: Tab) -> Self {
+        self.tabs.push(tab);
+        self
+    }
+
+    pub fn children(mut self, tabs: impl IntoIterator<Item = Tab>) -> Self {
+        self.tabs.extend(tabs);
+        self
+    }
+
+    pub fn selected_index(mut self, ix: Option<usize>) -> Self {
+        self.selected_ix = ix;
+        self
+    }
+
+    /// Show the tabs as a segmented control, e.g.: to switch the views of a panel.
+    pub fn segmented(mut self) -> Self {
+        self.segmented = true;
+        self
+    }
+
+    /// Track the scroll of the tabs, e.g.: to scroll to the selected tab with
+    /// [`ScrollHandle::scroll_to_item`].
+    pub fn track_scroll(mut self, scroll_handle: &ScrollHandle) -> Self {
+        self.scroll_handle = Some(scroll_handle.clone());
+        self
+    }
+
+    /// Set the element after the tabs, it's not scrolled with the tabs, e.g.: a new tab button.
+    pub fn suffix(mut self, suffix: impl IntoElement) -> Self {
+        self.suffix = Some(suffix.into_any_element());
+        self
+    }
+
+    /// Set the handler of the click on a tab, the argument is the tab index.
+    pub fn on_click(mut self, handler: impl Fn(&usize, &mut Window, &mut App) + 'static) -> Self {
+        self.on_click = Some(Rc::new(handler));
+        self
+    }
+
+    /// Set the handler to close a closable tab, the argument is the tab index.
+    pub fn on_close(mut self, handler: impl Fn(&usize, &mut Window, &mut App) + 'static) -> Self {
+        self.on_close = Some(Rc::new(handler));
+        self
+    }
+
+    /// Reorder the tabs by dragging, the arguments are the index of the dragged tab and the
+    /// index of the tab it is dropped on.
+    pub fn on_reorder(
+        mut self,
+        handler: impl Fn(usize, usize, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_reorder = Some(Rc::new(handler));
+        self
+    }
+
+    fn height(&self) -> Pixels {
+        match self.size {
+            Size::XSmall => px(26.),
+            Size::Small => px(30.),
+            Size::Large => px(40.),
+            _ => px(34.),
+        }
+    }
+
+    fn render_tab(&self, ix: usize, tab: Tab, cx: &App) -> impl IntoElement {
+        let selected = self.selected_ix == Some(ix);
+        let group = SharedString::from(format!("tab-{}", ix));
+
+        h_flex()
+            .id(ix)
+            .group(group.clone())
+            .flex_shrink_0()
+            .gap_1p5()
+            .map(|this| match self.size {
+                Size::XSmall | Size::Small => this.px_2().text_xs(),
+                _ => this.px_3().text_sm(),
+            })
+            .cursor_pointer()
+            .text_color(cx.theme().tab_foreground)
+            .bg(cx.theme().tab)
+            .map(|this| {
+                if self.segmented {
+                    this.h_full()
+                        .rounded(cx.theme().radius)
+                        .when(selected, |this| {
+                            this.bg(cx.theme().tab_active)
+                                .text_color(cx.theme().tab_active_foreground)
+                                .shadow_sm()
+                        })
+                } else {
+                    this.h_full()
+                        .border_r_1()
+                        .border_color(cx.theme().border)
+                        .when(selected, |this| {
+                            this.bg(cx.theme().tab_active)
+                                .text_color(cx.theme().tab_active_foreground)
+                        })
+                }
+            })
+            .when(!selected, |this| {
+                this.hover(|this| this.text_color(cx.theme().tab_active_foreground))
+            })
+            .when_some(tab.icon, |this, icon| this.child(icon.size_4()))
+            .child(
+                div()
+                    .max_w(px(180.))
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .child(tab.label.clone()),
+            )
+            .when_some(self.on_click.clone(), |this, on_click| {
+                this.on_click(move |_: &ClickEvent, window, cx| on_click(&ix, window, cx))
+            })
+            .when_some(
+                self.on_close.clone().filter(|_| tab.closable),
+                |this, on_close| {
+                    this.on_mouse_down(MouseButton::Middle, {
+                        let on_close = on_close.clone();
+                        move |_, window, cx| on_close(&ix, window, cx)
+                    })
+                    .child(
+                        div()
+                            .id(("close", ix))
+                            .flex_shrink_0()
+                            .p_0p5()
+                            .rounded(px(4.))
+                            .hover(|this| this.bg(cx.theme().accent))
+                            .when(!selected, |this| {
+                                this.invisible().group_hover(group, |this| this.visible())
+                            })
+                            .child(Icon::new(IconName::Close).size_3())
+                            .on_click(move |_, window, cx| {
+                                cx.stop_propagation();
+                                on_close(&ix, window, cx);
+                            }),
+                    )
+                },
+            )
+            .when_some(self.on_reorder.clone(), |this, on_reorder| {
+                let bar_id = self.id.clone();
+                this.on_drag(
+                    DragTab {
+                        bar_id: bar_id.clone(),
+                        ix,
+                        label: tab.label,
+                    },
+                    |drag, _, _, cx| cx.new(|_| drag.clone()),
+                )
+                .drag_over::<DragTab>(|this, _, _, cx| this.bg(cx.theme().drop_target))
+                .on_drop(move |drag: &DragTab, window, cx| {
+                    if drag.bar_id == bar_id && drag.ix != ix {
+                        on_reorder(drag.ix, ix, window, cx);
+                    }
+                })
+            })
+    }
+}
+
+impl RenderOnce for TabBar {
+    fn render(mut self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let height = self.height();
+        let tabs = std::mem::take(&mut self.tabs)
+            .into_iter()
+            .enumerate()
+            .map(|(ix, tab)| self.render_tab(ix, tab, cx))
+            .collect::<Vec<_>>();
+
+        h_flex()
+            .id(self.id.clone())
+            .flex_shrink_0()
+            .map(|this| {
+                if self.segmented {
+                    this.h(height)
+                        .p_0p5()
+                        .rounded(cx.theme().radius)
+                        .bg(cx.theme().tab_bar_segmented)
+                } else {
+                    this.w_full()
+                        .h(height)
+                        .bg(cx.theme().tab_bar)
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                }
+            })
+            .child(
+                h_flex()
+                    .id("tabs")
+                    .h_full()
+                    .min_w(px(0.))
+                    .when(self.segmented, |this| this.gap_1())
+                    .when(!self.segmented, |this| this.flex_1())
+                    .overflow_x_scroll()
+                    .when_some(self.scroll_handle.as_ref(), |this, scroll_handle| {
+                        this.track_scroll(scroll_handle)
+                    })
+                    .children(tabs),
+            )
+            .children(self.suffix)
+    }
+}
@@ -0,0 +1,580 @@
+use std::{cell::Cell, ops::Range, rc::Rc};
+
+use gpui::{
+    App, AppContext as _, ClickEvent, Context, DragMoveEvent, Empty, EntityId, EventEmitter,
+    FocusHandle, Focusable, FontWeight, InteractiveElement as _, IntoElement, KeyBinding,
+    ParentElement as _, Pixels, Render, ScrollHandle, ScrollStrategy, SharedString,
+    StatefulInteractiveElement as _, Styled as _, UniformListScrollHandle, Window, actions, div,
+    prelude::FluentBuilder as _, px, size, uniform_list,
+};
+
+use crate::{
+    ActiveTheme as _, Icon, IconName, InteractiveElementExt as _, Sizable, Size, StyleSized as _,
+    actions::{Cancel, Confirm, SelectNext, SelectPrev},
+    h_flex,
+    scroll::{Scrollbar, ScrollbarState},
+    v_flex,
+};
+
+const CONTEXT: &str = "Table";
+
+actions!(table, [SelectFirst, SelectLast]);
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("up", SelectPrev, Some(CONTEXT)),
+        KeyBinding::new("down", SelectNext, Some(CONTEXT)),
+        KeyBinding::new("home", SelectFirst, Some(CONTEXT)),
+        KeyBinding::new("end", SelectLast, Some(CONTEXT)),
+        KeyBinding::new("enter", Confirm { secondary: false }, Some(CONTEXT)),
+        KeyBinding::new("escape", Cancel, Some(CONTEXT)),
+    ]);
+}
+
+/// The sort order of a sortable [`Column`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnSort {
+    /// The original order of the rows.
+    #[default]
+    Default,
+    Ascending,
+    Descending,
+}
+
+impl ColumnSort {
+    /// The order after a click on the header: default, ascending, descending, and back.
+    fn next(self) -> Self {
+        match self {
+            Self::Default => Self::Ascending,
+            Self::Ascending => Self::Descending,
+            Self::Descending => Self::Default,
+        }
+    }
+}
+
+/// The definition of a column of the [`Table`].
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub key: SharedString,
+    pub name: SharedString,
+    pub width: Pixels,
+    /// The minimum width when resizing.
+    pub min_width: Pixels,
+    /// The sort order, `None` if the column is not sortable.
+    pub sort: Option<ColumnSort>,
+    pub resizable: bool,
+    /// Align the header and the cells to the right, e.g.: for the numbers.
+    pub align_right: bool,
+}
+
+impl Column {
+    pub fn new(key: impl Into<SharedString>, name: impl Into<SharedString>) -> Self {
+        Self {
+            key: key.into(),
+            name: name.into(),
+            width: px(120.),
+            min_width: px(40.),
+            sort: None,
+            resizable: true,
+            align_right: false,
+        }
+    }
+
+    pub fn width(mut self, width: impl Into<Pixels>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    pub fn min_width(mut self, min_width: impl Into<Pixels>) -> Self {
+        self.min_width = min_width.into();
+        self
+    }
+
+    /// Sort the rows by a click on the header, see [`TableDelegate::perform_sort`].
+    pub fn sortable(mut self) -> Self {
+        self.sort = Some(ColumnSort::Default);
+        self
+    }
+
+    /// Resize the column by dragging the right edge of the header, default is true.
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn text_right(mut self) -> Self {
+        self.align_right = true;
+        self
+    }
+}
+
+/// The rows and the columns of a [`Table`].
+pub trait TableDelegate: Sized + 'static {
+    /// The columns, read once when the table is created.
+    fn columns(&self, cx: &App) -> Vec<Column>;
+
+    fn rows_count(&self, cx: &App) -> usize;
+
+    fn render_cell(
+        &self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> impl IntoElement;
+
+    /// Sort the rows by the column, [`ColumnSort::Default`] restores the original order.
+    fn perform_sort(
+        &mut self,
+        _col_ix: usize,
+        _sort: ColumnSort,
+        _window: &mut Window,
+        _cx: &mut Context<Table<Self>>,
+    ) {
+    }
+
+    /// The content shown if there is no rows.
+    fn render_empty(
+        &self,
+        _window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> impl IntoElement {
+        h_flex()
+            .size_full()
+            .justify_center()
+            .text_color(cx.theme().muted_foreground)
+            .child("No data")
+    }
+}
+
+pub enum TableEvent {
+    /// The row is selected by a click or the keyboard.
+    SelectRow(usize),
+    /// The row is confirmed by a double click or `enter`.
+    ConfirmRow(usize),
+}
+
+/// The column being resized, `(table, col_ix)`, it's also the (empty) drag view.
+#[derive(Clone)]
+struct ResizeColumn(EntityId, usize);
+
+impl Render for ResizeColumn {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        Empty
+    }
+}
+
+/// A virtualized table, only the visible rows are rendered.
+///
+/// ```ignore
+/// let table = cx.new(|cx| Table::new(TokenUsage::new(), window, cx).stripe(true).small());
+/// cx.subscribe(&table, |_, _, event: &TableEvent, _| { .. });
+/// ```
+pub struct Table<D: TableDelegate> {
+    focus_handle: FocusHandle,
+    delegate: D,
+    columns: Vec<Column>,
+    selected_row: Option<usize>,
+    stripe: bool,
+    size: Size,
+    scroll_handle: UniformListScrollHandle,
+    scrollbar_state: Rc<Cell<ScrollbarState>>,
+    /// Scroll the header and the rows together if the columns are wider than the table.
+    horizontal_scroll_handle: ScrollHandle,
+    horizontal_scrollbar_state: Rc<Cell<ScrollbarState>>,
+}
+
+impl<D: TableDelegate> EventEmitter<TableEvent> for Table<D> {}
+
+impl<D: TableDelegate> Focusable for Table<D> {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl<D: TableDelegate> Sizable for Table<D> {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+impl<D: TableDelegate> Table<D> {
+    pub fn new(delegate: D, _: &mut Window, cx: &mut Context<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            columns: delegate.columns(cx),
+            delegate,
+            selected_row: None,
+            stripe: false,
+            size: Size::default(),
+            scroll_handle: UniformListScrollHandle::new(),
+            scrollbar_state: Rc::new(Cell::new(ScrollbarState::default())),
+            horizontal_scroll_handle: ScrollHandle::new(),
+            horizontal_scrollbar_state: Rc::new(Cell::new(ScrollbarState::default())),
+        }
+    }
+
+    /// Use the `table_even` background for the odd rows, default is false.
+    pub fn stripe(mut self, stripe: bool) -> Self {
+        self.stripe = stripe;
+        self
+    }
+
+    pub fn delegate(&self) -> &D {
+        &self.delegate
+    }
+
+    pub fn delegate_mut(&mut self) -> &mut D {
+        &mut self.delegate
+    }
+
+    pub fn selected_row(&self) -> Option<usize> {
+        self.selected_row
+    }
+
+    /// Select the row and scroll to it, the selection is cleared if `None`.
+    pub fn set_selected_row(&mut self, row_ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_row = row_ix;
+        if let Some(row_ix) = row_ix {
+            self.scroll_handle
+                .scroll_to_item(row_ix, ScrollStrategy::Top);
+            cx.emit(TableEvent::SelectRow(row_ix));
+        }
+        cx.notify();
+    }
+
+    /// Update the table after the rows of the delegate changed, the selection is cleared if the
+    /// selected row is removed.
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        let rows_count = self.delegate.rows_count(cx);
+        self.selected_row = self.selected_row.filter(|ix| *ix < rows_count);
+        cx.notify();
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, _: &mut Window, cx: &mut Context<Self>) {
+        let rows_count = self.delegate.rows_count(cx);
+        if rows_count == 0 {
+            return;
+        }
+        let row_ix = match self.selected_row {
+            Some(ix) => ix.checked_sub(1).unwrap_or(rows_count - 1),
+            None => rows_count - 1,
+        };
+        self.set_selected_row(Some(row_ix), cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let rows_count = self.delegate.rows_count(cx);
+        if rows_count == 0 {
+            return;
+        }
+        let row_ix = match self.selected_row {
+            Some(ix) => (ix + 1) % rows_count,
+            None => 0,
+        };
+        self.set_selected_row(Some(row_ix), cx);
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        if self.delegate.rows_count(cx) > 0 {
+            self.set_selected_row(Some(0), cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        let rows_count = self.delegate.rows_count(cx);
+        if rows_count > 0 {
+            self.set_selected_row(Some(rows_count - 1), cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(row_ix) = self.selected_row {
+            cx.emit(TableEvent::ConfirmRow(row_ix));
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_row.is_some() {
+            self.set_selected_row(None, cx);
+        } else {
+            cx.propagate();
+        }
+    }
+
+    /// Sort by the column in the next order, the other columns are unsorted.
+    fn toggle_sort(&mut self, col_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(sort) = self.columns[col_ix].sort.map(ColumnSort::next) else {
+            return;
+        };
+        for (ix, column) in self.columns.iter_mut().enumerate() {
+            if column.sort.is_some() {
+                column.sort = Some(if ix == col_ix {
+                    sort
+                } else {
+                    ColumnSort::Default
+                });
+            }
+        }
+        self.delegate.perform_sort(col_ix, sort, window, cx);
+        self.refresh(cx);
+    }
+
+    fn on_resize_move(
+        &mut self,
+        event: &DragMoveEvent<ResizeColumn>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ResizeColumn(entity_id, col_ix) = *event.drag(cx);
+        if entity_id != cx.entity_id() {
+            return;
+        }
+
+        // The header starts at the left of the first column.
+        let left = self.columns[..col_ix]
+            .iter()
+            .fold(event.bounds.left(), |left, column| left + column.width);
+        let column = &mut self.columns[col_ix];
+        column.width = (event.event.position.x - left).max(column.min_width);
+        cx.notify();
+    }
+
+    fn render_head(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let entity_id = cx.entity_id();
+        let row_height = self.size.table_row_height();
+
+        h_flex()
+            .id("table-head")
+            .w_full()
+            .flex_shrink_0()
+            .bg(cx.theme().table_head)
+            .text_color(cx.theme().table_head_foreground)
+            .font_weight(FontWeight::MEDIUM)
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .on_drag_move(cx.listener(Self::on_resize_move))
+            .children(self.columns.iter().enumerate().map(|(col_ix, column)| {
+                h_flex()
+                    .id(("th", col_ix))
+                    .relative()
+                    .w(column.width)
+                    .h(row_height)
+                    .flex_shrink_0()
+                    .gap_1()
+                    .table_cell_size(self.size)
+                    .when(column.align_right, |this| this.justify_end())
+                    .child(
+                        div()
+                            .overflow_hidden()
+                            .whitespace_nowrap()
+                            .child(column.name.clone()),
+                    )
+                    .when_some(column.sort, |this, sort| {
+                        let icon = match sort {
+                            ColumnSort::Descending => IconName::SortDescending,
+                            _ => IconName::SortAscending,
+                        };
+                        this.cursor_pointer()
+                            .child(
+                                Icon::new(icon)
+                                    .size_3p5()
+                                    .flex_shrink_0()
+                                    .when(sort == ColumnSort::Default, |this| {
+                                        this.text_color(cx.theme().muted_foreground)
+                                    }),
+                            )
+                            .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| {
+                                this.toggle_sort(col_ix, window, cx);
+                            }))
+                    })
+                    .when(column.resizable, |this| {
+                        this.child(
+                            div()
+                                .id(("resize", col_ix))
+                                .absolute()
+                                .top_0()
+                                .right_0()
+                                .h_full()
+                                .w(px(4.))
+                                .cursor_col_resize()
+                                .hover(|this| this.bg(cx.theme().drag_border))
+                                .on_drag(ResizeColumn(entity_id, col_ix), |drag, _, _, cx| {
+                                    cx.new(|_| drag.clone())
+                                }),
+                        )
+                    })
+            }))
+    }
+
+    fn render_row(
+        &mut self,
+        row_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let selected = self.selected_row == Some(row_ix);
+
+        h_flex()
+            .id(row_ix)
+            .relative()
+            .w_full()
+            .h(self.size.table_row_height())
+            .border_b_1()
+            .border_color(cx.theme().table_row_border)
+            .when(self.stripe && row_ix % 2 == 1, |this| {
+                this.bg(cx.theme().table_even)
+            })
+            .hover(|this| this.bg(cx.theme().table_hover))
+            .children(self.columns.iter().enumerate().map(|(col_ix, column)| {
+                h_flex()
+                    .w(column.width)
+                    .h_full()
+                    .flex_shrink_0()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .table_cell_size(self.size)
+                    .when(column.align_right, |this| this.justify_end())
+                    .child(self.delegate.render_cell(row_ix, col_ix, window, cx))
+            }))
+            .when(selected, |this| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .right_0()
+                        .bottom_0()
+                        .bg(cx.theme().table_active)
+                        .border_1()
+                        .border_color(cx.theme().table_active_border),
+                )
+            })
+            .on_click(cx.listener(move |this, _: &ClickEvent, window, cx| {
+                this.focus_handle.focus(window);
+                this.set_selected_row(Some(row_ix), cx);
+            }))
+            .on_double_click(cx.listener(move |_, _: &ClickEvent, _, cx| {
+                cx.emit(TableEvent::ConfirmRow(row_ix));
+            }))
+    }
+}
+
+impl<D: TableDelegate> Render for Table<D> {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let rows_count = self.delegate.rows_count(cx);
+        let columns_width = self
+            .columns
+            .iter()
+            .fold(px(0.), |width, column| width + column.width);
+        // The height of the header, with the bottom border.
+        let head_height = self.size.table_row_height() + px(1.);
+
+        v_flex()
+            .id("table")
+            .key_context(CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .relative()
+            .size_full()
+            .overflow_hidden()
+            .bg(cx.theme().table)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded(cx.theme().radius)
+            .child(
+                div()
+                    .id("table-scroll")
+                    .size_full()
+                    .overflow_x_scroll()
+                    .track_scroll(&self.horizontal_scroll_handle)
+                    .child(
+                        v_flex()
+                            .h_full()
+                            .w(columns_width)
+                            .min_w_full()
+                            .child(self.render_head(cx))
+                            .child(div().flex_1().w_full().map(|this| {
+                                if rows_count == 0 {
+                                    return this.child(self.delegate.render_empty(window, cx));
+                                }
+
+                                this.child(
+                                    uniform_list(
+                                        "table-rows",
+                                        rows_count,
+                                        cx.processor(|this, range: Range<usize>, window, cx| {
+                                            range
+                                                .map(|ix| this.render_row(ix, window, cx))
+                                                .collect::<Vec<_>>()
+                                        }),
+                                    )
+                                    .track_scroll(self.scroll_handle.clone())
+                                    .size_full(),
+                                )
+                            })),
+                    ),
+            )
+            // The scrollbars are out of the scrolled content, to keep them in the view.
+            .child(
+                div()
+                    .absolute()
+                    .top(head_height)
+                    .left_0()
+                    .right_0()
+                    .bottom_0()
+                    .when(rows_count > 0, |this| {
+                        this.child(Scrollbar::uniform_scroll(
+                            cx.entity_id(),
+                            self.scrollbar_state.clone(),
+                            self.scroll_handle.clone(),
+                        ))
+                    }),
+            )
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .left_0()
+                    .right_0()
+                    .bottom_0()
+                    .child(Scrollbar::horizontal(
+                        cx.entity_id(),
+                        self.horizontal_scrollbar_state.clone(),
+                        self.horizontal_scroll_handle.clone(),
+                        size(columns_width, px(0.)),
+                    )),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColumnSort;
+
+    #[test]
+    fn test_column_sort_next() {
+        let mut sort = ColumnSort::default();
+        let mut orders = vec![];
+        for _ in 0..4 {
+            sort = sort.next();
+            orders.push(sort);
+        }
+
+        assert_eq!(
+            orders,
+            vec![
+                ColumnSort::Ascending,
+                ColumnSort::Descending,
+                ColumnSort::Default,
+                ColumnSort::Ascending,
+            ]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    fs, thread,
+    time::{Duration, Instant},
+};
+
+use futures::{
+    StreamExt as _,
+    channel::mpsc::{self, UnboundedReceiver},
+    executor::block_on,
+};
+use gpui::{AppContext as _, Entity, TestAppContext, VisualTestContext};
+use serde_json::{Value, json};
+use ui::{
+    highlighter,
+    input::{InputState, Marker},
+    lsp::{
+        self, LanguageServer, LanguageServerConfig, LspStore, Position, PublishDiagnosticsParams,
+        ServerNotification, path_to_uri,
+    },
+};
+
+fn config(initialization_options: Option<Value>) -> LanguageServerConfig {
+    LanguageServerConfig {
+        language: "rust".into(),
+        command: env!("CARGO_BIN_EXE_fake-language-server").into(),
+        args: vec![],
+        initialization_options,
+    }
+}
+
+fn start_server() -> (LanguageServer, UnboundedReceiver<ServerNotification>) {
+    start_server_with(None)
+}
+
+fn start_server_with(
+    initialization_options: Option<Value>,
+) -> (LanguageServer, UnboundedReceiver<ServerNotification>) {
+    let (tx, rx) = mpsc::unbounded();
+    let server =
+        LanguageServer::start(&config(initialization_options), std::env::temp_dir(), tx).unwrap();
+    (server, rx)
+}
+
+fn next_diagnostics(rx: &mut UnboundedReceiver<ServerNotification>) -> PublishDiagnosticsParams {
+    loop {
+        let notification = block_on(rx.next()).expect("language server exited");
+        if notification.method == "textDocument/publishDiagnostics" {
+            return serde_json::from_value(notification.params).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_diagnostics() {
+    let (server, mut rx) = start_server();
+    let uri = path_to_uri(&std::env::temp_dir().join("main.rs"));
+
+    let text = "fn main() {\n    error();\n}";
+    server.did_open(&uri, 0, text).unwrap();
+    let params = next_diagnostics(&mut rx);
+    assert_eq!(params.uri, uri);
+    assert_eq!(params.version, Some(0));
+    assert_eq!(params.diagnostics.len(), 1);
+
+    let marker = params.diagnostics[0].to_marker(text);
+    assert_eq!((marker.start.line, marker.start.column), (2, 5));
+    assert_eq!((marker.end.line, marker.end.column), (2, 10));
+    assert_eq!(marker.message.as_ref(), "fake: found error");
+
+    server.did_change(&uri, 1, "fn main() {}").unwrap();
+    let params = next_diagnostics(&mut rx);
+    assert_eq!(params.version, Some(1));
+    assert!(params.diagnostics.is_empty());
+
+    server.shutdown();
+}
+
+#[test]
+fn test_hover_and_definition() {
+    let (server, _rx) = start_server();
+    let uri = path_to_uri(&std::env::temp_dir().join("lib.rs"));
+
+    let text = "fn foo() {}\nfn bar() {\n    foo();\n}";
+    server.did_open(&uri, 0, text).unwrap();
+
+    let position = Position {
+        line: 2,
+        character: 5,
+    };
+    let hover = server.hover(&uri, position).unwrap();
+    assert_eq!(hover.as_deref(), Some("`foo`"));
+
+    let locations = server.definition(&uri, position).unwrap();
+    assert_eq!(locations.len(), 1);
+    assert_eq!(locations[0].uri, uri);
+    assert_eq!(
+        locations[0].range.start,
+        Position {
+            line: 0,
+            character: 3
+        }
+    );
+
+    let position = Position {
+        line: 1,
+        character: 10,
+    };
+    assert_eq!(server.hover(&uri, position).unwrap(), None);
+    assert!(server.definition(&uri, position).unwrap().is_empty());
+
+    server.shutdown();
+}
+
+#[test]
+fn test_initialize_failed() {
+    let (server, _rx) = start_server_with(Some(json!({ "fail": true })));
+    let uri = path_to_uri(&std::env::temp_dir().join("main.rs"));
+
+    // The queued request fails as soon as the `initialize` request fails.
+    let start = Instant::now();
+    let err = server
+        .hover(
+            &uri,
+            Position {
+                line: 0,
+                character: 0,
+            },
+        )
+        .unwrap_err();
+    assert!(err.to_string().starts_with("failed to initialize"));
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // The later messages are rejected.
+    assert!(server.did_open(&uri, 0, "fn main() {}").is_err());
+}
+
+/// Wait for the markers of the editor, the diagnostics are published from the server process.
+fn wait_for_markers(editor: &Entity<InputState>, cx: &mut VisualTestContext) -> Vec<Marker> {
+    let start = Instant::now();
+    loop {
+        cx.run_until_parked();
+        let markers = cx.update(|_, cx| editor.read(cx).markers().to_vec());
+        if !markers.is_empty() || start.elapsed() > Duration::from_secs(5) {
+            return markers;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[gpui::test]
+fn test_store_sync(cx: &mut TestAppContext) {
+    let root = std::env::temp_dir().join(format!("lsp-store-{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    let path = root.join("main.rs");
+    cx.update(|cx| {
+        highlighter::init(cx);
+        lsp::init(cx);
+        LspStore::global(cx).update(cx, |store, cx| {
+            store.set_servers(vec![config(None)], cx);
+            store.set_root(Some(root.clone()), cx);
+        });
+    });
+
+    let cx = cx.add_empty_window();
+    let editor = cx.update(|window, cx| {
+        let editor = cx.new(|cx| InputState::new(window, cx).code_editor("rust"));
+        LspStore::global(cx).update(cx, |store, cx| store.open(&editor, &path, window, cx));
+        editor
+    });
+
+    // The document is opened empty, the error comes with the `didChange` of the edit.
+    cx.update(|window, cx| {
+        editor.update(cx, |editor, cx| {
+            editor.set_value("fn main() {\n    error();\n}", window, cx)
+        });
+    });
+    let markers = wait_for_markers(&editor, cx);
+    assert_eq!(markers.len(), 1);
+    assert_eq!((markers[0].start.line, markers[0].start.column), (2, 5));
+    assert_eq!((markers[0].end.line, markers[0].end.column), (2, 10));
+    assert_eq!(markers[0].message.as_ref(), "fake: found error");
+
+    _ = fs::remove_dir_all(&root);
+}
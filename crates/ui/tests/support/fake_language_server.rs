@@ -0,0 +1,182 @@
+//! A tiny language server for the tests of the LSP client, that communicates over stdio.
+//!
+//! - Publish an error diagnostic for every `error` word in the document.
+//! - Hover returns the word under the position.
+//! - Go to definition returns the first occurrence of the word under the position.
+//! - Fail to initialize if the `fail` initialization option is true.
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Read, Write},
+};
+
+use serde_json::{Value, json};
+
+fn main() {
+    let stdin = io::stdin();
+    let mut reader = BufReader::new(stdin.lock());
+    let mut stdout = io::stdout();
+    let mut documents = HashMap::<String, String>::new();
+
+    while let Some(message) = read_message(&mut reader) {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        if method == "initialize" && params["initializationOptions"]["fail"] == true {
+            write_message(
+                &mut stdout,
+                &json!({
+                    "jsonrpc": "2.0",
+                    "id": message["id"],
+                    "error": { "code": -32603, "message": "fake: failed" },
+                }),
+            );
+            continue;
+        }
+
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                },
+                "serverInfo": { "name": "fake-language-server" },
+            }),
+            "textDocument/didOpen" | "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap().to_string();
+                let text = match method {
+                    "textDocument/didOpen" => &params["textDocument"]["text"],
+                    _ => &params["contentChanges"][0]["text"],
+                };
+                let text = text.as_str().unwrap().to_string();
+                let diagnostics = find_words(&text, "error")
+                    .into_iter()
+                    .map(|range| {
+                        json!({
+                            "range": range,
+                            "severity": 1,
+                            "source": "fake",
+                            "message": "found error",
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                write_message(
+                    &mut stdout,
+                    &json!({
+                        "jsonrpc": "2.0",
+                        "method": "textDocument/publishDiagnostics",
+                        "params": {
+                            "uri": uri,
+                            "version": params["textDocument"]["version"],
+                            "diagnostics": diagnostics,
+                        },
+                    }),
+                );
+                documents.insert(uri, text);
+                continue;
+            }
+            "textDocument/didClose" => {
+                documents.remove(params["textDocument"]["uri"].as_str().unwrap());
+                continue;
+            }
+            "textDocument/hover" | "textDocument/definition" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap();
+                let text = documents.get(uri).map(String::as_str).unwrap_or_default();
+                let line = params["position"]["line"].as_u64().unwrap() as usize;
+                let character = params["position"]["character"].as_u64().unwrap() as usize;
+                match word_at(text, line, character) {
+                    Some(word) if method == "textDocument/hover" => json!({
+                        "contents": { "kind": "markdown", "value": format!("`{}`", word) },
+                    }),
+                    Some(word) => json!({
+                        "uri": uri,
+                        "range": find_words(text, word)[0],
+                    }),
+                    None => Value::Null,
+                }
+            }
+            "shutdown" => Value::Null,
+            "exit" => break,
+            _ => continue,
+        };
+
+        if let Some(id) = message.get("id") {
+            write_message(
+                &mut stdout,
+                &json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            );
+        }
+    }
+}
+
+fn read_message(reader: &mut impl BufRead) -> Option<Value> {
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            content_length = value.trim().parse().ok()?;
+        }
+    }
+
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+fn write_message(writer: &mut impl Write, message: &Value) {
+    let content = message.to_string();
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )
+    .unwrap();
+    writer.flush().unwrap();
+}
+
+/// Return the ranges of the whole `word` in the ASCII `text`.
+fn find_words(text: &str, word: &str) -> Vec<Value> {
+    let mut ranges = vec![];
+    for (line_ix, line) in text.lines().enumerate() {
+        for (start, _) in line.match_indices(word) {
+            let end = start + word.len();
+            let is_boundary = |ix: Option<u8>| ix.is_none_or(|c| !is_word_char(c));
+            let before = start.checked_sub(1).map(|ix| line.as_bytes()[ix]);
+            let after = line.as_bytes().get(end).copied();
+            if is_boundary(before) && is_boundary(after) {
+                ranges.push(json!({
+                    "start": { "line": line_ix, "character": start },
+                    "end": { "line": line_ix, "character": end },
+                }));
+            }
+        }
+    }
+    ranges
+}
+
+/// Return the word at the position of the ASCII `text`.
+fn word_at(text: &str, line: usize, character: usize) -> Option<&str> {
+    let line = text.lines().nth(line)?;
+    let bytes = line.as_bytes();
+    let start = (0..character.min(bytes.len()))
+        .rev()
+        .take_while(|ix| is_word_char(bytes[*ix]))
+        .last()
+        .unwrap_or(character);
+    let end = (character..bytes.len())
+        .take_while(|ix| is_word_char(bytes[*ix]))
+        .last()
+        .map_or(character, |ix| ix + 1);
+    (start < end).then(|| &line[start..end])
+}
+
+fn is_word_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_'
+}